@@ -7,7 +7,10 @@ use godot::classes::{TileMap, TileSet};
 use godot::obj::{Gd, NewAlloc};
 use godot::tools::load;
 
-use grid_forge::godot::{load_gridmap_from_tilemap_manual, write_gridmap_to_tilemap};
+use grid_forge::godot::{
+    load_gridmap_from_tilemap_manual, read_custom_data_from_tilemap, write_custom_data_to_tilemap,
+    write_gridmap_to_tilemap,
+};
 use grid_forge::identifiable::builders::IdentTileTraitBuilder;
 use grid_forge::identifiable::{BasicIdentTileData, IdentifiableTileData};
 use grid_forge::GridSize;
@@ -150,6 +153,54 @@ fn test_from_grindmap_identical() {
     cloned.free();
 }
 
+#[gditest]
+fn test_custom_data_roundtrip() {
+    let collection = get_test_collection();
+    let cloned = collection.clone();
+    let binding = collection.bind();
+    let builder = IdentTileTraitBuilder::<BasicIdentTileData>::default();
+    let tileset = load::<TileSet>(TILESET_RESOURCE_PATH);
+
+    let roads_map = binding.load_vis_map_from_path(ROADS_MAP_PATH).unwrap();
+    let mut roads_tilemap = TileMap::new_alloc();
+    roads_tilemap.set_tileset(tileset);
+    write_gridmap_to_tilemap(
+        &roads_map,
+        &mut roads_tilemap,
+        binding.godot_collection.as_ref().unwrap(),
+    )
+    .expect("cannot write roads to tilemap");
+    write_custom_data_to_tilemap(&roads_map, &mut roads_tilemap, |data| {
+        (data.tile_type_id() % 1000) as i64 - 500
+    });
+
+    let second_roads = load_gridmap_from_tilemap_manual(
+        &roads_tilemap,
+        binding.godot_collection.as_ref().unwrap(),
+        &builder,
+    )
+    .expect("cannot load roads to gridmap");
+    let custom_data = read_custom_data_from_tilemap(&roads_tilemap);
+
+    assert_eq!(roads_map.get_all_positions().len(), custom_data.len());
+    for position in roads_map.get_all_positions() {
+        let tile_type_id = second_roads
+            .get_tile_at_position(&position)
+            .unwrap()
+            .as_ref()
+            .tile_type_id();
+        assert_eq!(
+            Some(&((tile_type_id % 1000) as i64 - 500)),
+            custom_data.get(&position)
+        );
+    }
+
+    std::mem::drop(binding);
+
+    roads_tilemap.free();
+    cloned.free();
+}
+
 fn get_test_collection() -> Gd<TileCollections> {
     let mut collection = TileCollections::new_alloc();
     let out = collection.clone();
@@ -0,0 +1,299 @@
+//! Derive macros for `grid-forge` crate. Enable the `derive` feature of `grid-forge` to use them through its
+//! re-exports.
+
+use proc_macro::{Delimiter, Group, Spacing, Span, TokenStream, TokenTree};
+
+const ATTRIBUTE: &str = "tile_type_id";
+
+/// Derives `TileData` and `IdentifiableTileData` for the struct.
+///
+/// The `tile_type_id` is taken either from the `#[tile_type_id(...)]` attribute placed on the struct, making it
+/// constant for all instances, or from the field marked with `#[tile_type_id]`. The field needs to be [`Copy`] and
+/// convertible into [`u64`].
+///
+/// Only non-generic structs without `where` clauses are supported - any other input is rejected with the compile
+/// error pointing at the unsupported part.
+///
+/// ```ignore
+/// use grid_forge::identifiable::IdentifiableTileData;
+///
+/// #[derive(IdentifiableTileData)]
+/// #[tile_type_id(3)]
+/// struct Wall;
+///
+/// #[derive(IdentifiableTileData)]
+/// struct Floor {
+///     #[tile_type_id]
+///     kind: u32,
+///     height: f32,
+/// }
+/// ```
+#[proc_macro_derive(IdentifiableTileData, attributes(tile_type_id))]
+pub fn derive_identifiable_tile_data(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+/// Error encountered while parsing the derive input, reported as the `compile_error!` at its `span`.
+struct DeriveError {
+    message: String,
+    span: Span,
+}
+
+impl DeriveError {
+    fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    fn into_compile_error(self) -> TokenStream {
+        let tokens = format!("::core::compile_error!({:?});", self.message)
+            .parse()
+            .unwrap();
+        respan(tokens, self.span)
+    }
+}
+
+/// Sets the `span` of all tokens, so the compiler points to it while reporting them.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                return TokenTree::Group(respanned);
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+/// Source of the `tile_type_id` in the generated implementation.
+enum TileTypeId {
+    Constant(String),
+    Field(String),
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, DeriveError> {
+    let mut tokens = input.into_iter().peekable();
+    let mut tile_type_id = None;
+
+    // Outer attributes and visibility.
+    let name = loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.next() {
+                    if let Some(constant) = parse_attribute(&group)? {
+                        let constant = constant.ok_or_else(|| {
+                            DeriveError::new(
+                                "expected `#[tile_type_id(<id>)]` on struct",
+                                group.span(),
+                            )
+                        })?;
+                        tile_type_id = Some(TileTypeId::Constant(constant));
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => match tokens.next() {
+                Some(TokenTree::Ident(name)) => break name,
+                _ => return Err(DeriveError::new("expected struct name", ident.span())),
+            },
+            Some(TokenTree::Ident(ident))
+                if matches!(ident.to_string().as_str(), "enum" | "union") =>
+            {
+                return Err(DeriveError::new(
+                    "`IdentifiableTileData` can be derived only for structs",
+                    ident.span(),
+                ));
+            }
+            Some(_) => {}
+            None => return Err(DeriveError::new("expected struct", Span::call_site())),
+        }
+    };
+
+    let field_id = match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err(DeriveError::new(
+                "`IdentifiableTileData` can't be derived for generic structs",
+                punct.span(),
+            ));
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            find_id_field(group.stream(), true)?
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            find_id_field(group.stream(), false)?
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => None,
+        Some(token) => {
+            return Err(DeriveError::new(
+                "`IdentifiableTileData` can't be derived for structs with `where` clauses",
+                token.span(),
+            ));
+        }
+        None => None,
+    };
+    // Tuple structs can still have the `where` clause after their fields.
+    if let Some(token) =
+        tokens.find(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ';'))
+    {
+        return Err(DeriveError::new(
+            "`IdentifiableTileData` can't be derived for structs with `where` clauses",
+            token.span(),
+        ));
+    }
+
+    let tile_type_id = match (tile_type_id, field_id) {
+        (Some(_), Some(_)) => {
+            return Err(DeriveError::new(
+                "`tile_type_id` can be either constant or taken from a field, not both",
+                name.span(),
+            ))
+        }
+        (Some(id), None) | (None, Some(id)) => id,
+        (None, None) => return Err(DeriveError::new(
+            "expected `#[tile_type_id(<id>)]` on struct or `#[tile_type_id]` on one of its fields",
+            name.span(),
+        )),
+    };
+
+    let body = match tile_type_id {
+        TileTypeId::Constant(constant) => format!("({constant}) as u64"),
+        TileTypeId::Field(field) => format!("::core::convert::From::from(self.{field})"),
+    };
+
+    Ok(format!(
+        "impl ::grid_forge::TileData for {name} {{}}
+
+        impl ::grid_forge::identifiable::IdentifiableTileData for {name} {{
+            fn tile_type_id(&self) -> u64 {{
+                {body}
+            }}
+        }}"
+    )
+    .parse()
+    .unwrap())
+}
+
+/// Parses the content of attribute brackets. Returns `None` if it is not the `tile_type_id` attribute, otherwise its
+/// argument, if present.
+fn parse_attribute(group: &Group) -> Result<Option<Option<String>>, DeriveError> {
+    let mut tokens = group.stream().into_iter();
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == ATTRIBUTE => {}
+        _ => return Ok(None),
+    }
+    match tokens.next() {
+        None => Ok(Some(None)),
+        Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis => {
+            if args.stream().is_empty() {
+                return Err(DeriveError::new(
+                    "`tile_type_id` argument can't be empty",
+                    args.span(),
+                ));
+            }
+            Ok(Some(Some(args.stream().to_string())))
+        }
+        _ => Err(DeriveError::new(
+            "expected `#[tile_type_id]` or `#[tile_type_id(<id>)]`",
+            group.span(),
+        )),
+    }
+}
+
+/// Searches for the field marked with `#[tile_type_id]` attribute, returning its name or index.
+fn find_id_field(fields: TokenStream, named: bool) -> Result<Option<TileTypeId>, DeriveError> {
+    let mut found = None;
+    let mut field_idx = 0;
+    let mut marked_span = None;
+    let mut field_name = None;
+    let mut angle_depth = 0usize;
+    let mut at_field_start = true;
+    let mut tokens = fields.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' && at_field_start => {
+                if let Some(TokenTree::Group(group)) = tokens.next() {
+                    if let Some(argument) = parse_attribute(&group)? {
+                        if argument.is_some() {
+                            return Err(DeriveError::new(
+                                "field attribute shouldn't have arguments: use `#[tile_type_id]`",
+                                group.span(),
+                            ));
+                        }
+                        if marked_span.is_some() {
+                            return Err(DeriveError::new(
+                                "field can be marked with `#[tile_type_id]` only once",
+                                group.span(),
+                            ));
+                        }
+                        marked_span = Some(group.span());
+                    }
+                }
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '<' => {
+                at_field_start = false;
+                angle_depth += 1;
+            }
+            // Skip the `->` in function pointer types.
+            TokenTree::Punct(punct)
+                if punct.as_char() == '-' && punct.spacing() == Spacing::Joint =>
+            {
+                at_field_start = false;
+                tokens.next();
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '>' => {
+                angle_depth = angle_depth.saturating_sub(1);
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ',' && angle_depth == 0 => {
+                if let Some(span) = marked_span.take() {
+                    if found.is_some() {
+                        return Err(DeriveError::new(
+                            "only one field can be marked with `#[tile_type_id]`",
+                            span,
+                        ));
+                    }
+                    found = Some(field_name.take().unwrap_or_else(|| field_idx.to_string()));
+                }
+                field_idx += 1;
+                field_name = None;
+                at_field_start = true;
+            }
+            TokenTree::Ident(ident) if at_field_start => {
+                let ident = ident.to_string();
+                if ident == "pub" {
+                    // Skip restricted visibility, eg. `pub(crate)`.
+                    if matches!(tokens.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+                    {
+                        tokens.next();
+                    }
+                    continue;
+                }
+                if named {
+                    field_name = Some(ident);
+                }
+                at_field_start = false;
+            }
+            _ => at_field_start = false,
+        }
+    }
+    // Last field without trailing comma.
+    if let Some(span) = marked_span {
+        if found.is_some() {
+            return Err(DeriveError::new(
+                "only one field can be marked with `#[tile_type_id]`",
+                span,
+            ));
+        }
+        found = Some(field_name.unwrap_or_else(|| field_idx.to_string()));
+    }
+
+    Ok(found.map(TileTypeId::Field))
+}
@@ -1,14 +1,18 @@
 use std::{error::Error, fmt::Display};
 
+use crate::tile::GridPosition;
+
 #[derive(Debug)]
 pub struct BuilderError {
     missing_fields: Vec<&'static str>,
+    invalid_fields: Vec<&'static str>,
 }
 
 impl BuilderError {
     pub(crate) fn new() -> Self {
         Self {
             missing_fields: Vec::new(),
+            invalid_fields: Vec::new(),
         }
     }
 
@@ -16,8 +20,12 @@ impl BuilderError {
         self.missing_fields.push(field);
     }
 
+    pub(crate) fn add_invalid_field(&mut self, field: &'static str) {
+        self.invalid_fields.push(field);
+    }
+
     pub(crate) fn try_throw(self) -> Result<(), Self> {
-        if self.missing_fields.is_empty() {
+        if self.missing_fields.is_empty() && self.invalid_fields.is_empty() {
             return Ok(());
         }
 
@@ -26,13 +34,161 @@ impl BuilderError {
 }
 
 impl Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.missing_fields.is_empty() {
+            write!(
+                f,
+                "there are missing field values: {missing}",
+                missing = self.missing_fields.join(", ")
+            )?;
+        }
+        if !self.invalid_fields.is_empty() {
+            let prefix = if self.missing_fields.is_empty() {
+                "there are"
+            } else {
+                "; and"
+            };
+            write!(
+                f,
+                "{prefix} invalid field values: {invalid}",
+                invalid = self.invalid_fields.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for BuilderError {}
+
+/// Error returned when the [`GridMap2D`](crate::map::GridMap2D) was expected to be fully populated, but contained
+/// empty positions.
+#[derive(Debug)]
+pub struct UnfilledMapError {
+    empty_positions: Vec<GridPosition>,
+}
+
+impl UnfilledMapError {
+    pub(crate) fn new(empty_positions: Vec<GridPosition>) -> Self {
+        Self { empty_positions }
+    }
+
+    /// Returns all positions which were empty.
+    pub fn empty_positions(&self) -> &[GridPosition] {
+        &self.empty_positions
+    }
+}
+
+impl Display for UnfilledMapError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "there are missing field values: {missing}",
-            missing = self.missing_fields.join(", ")
+            "there are {count} empty positions in the map: {positions}",
+            count = self.empty_positions.len(),
+            positions = self
+                .empty_positions
+                .iter()
+                .map(|pos| pos.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
         )
     }
 }
 
-impl Error for BuilderError {}
+impl Error for UnfilledMapError {}
+
+/// Error returned when the tiles were expected to be placed within the [`GridSize`](crate::map::GridSize), but some
+/// of their positions were out of its bounds.
+#[derive(Debug)]
+pub struct OutOfBoundsError {
+    positions: Vec<GridPosition>,
+}
+
+impl OutOfBoundsError {
+    pub(crate) fn new(positions: Vec<GridPosition>) -> Self {
+        Self { positions }
+    }
+
+    /// Returns all positions which were out of bounds.
+    pub fn positions(&self) -> &[GridPosition] {
+        &self.positions
+    }
+}
+
+impl Display for OutOfBoundsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "there are {count} positions out of bounds: {positions}",
+            count = self.positions.len(),
+            positions = self
+                .positions
+                .iter()
+                .map(|pos| pos.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl Error for OutOfBoundsError {}
+
+/// Error returned when the [`GridSize`](crate::map::GridSize) dimensions are too big for the number of its positions
+/// to fit in `usize`.
+#[derive(Debug)]
+pub struct SizeError {
+    x: u32,
+    y: u32,
+    z: Option<u32>,
+}
+
+impl SizeError {
+    pub(crate) fn new(x: u32, y: u32, z: Option<u32>) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the rejected dimensions as `(x, y, z)`.
+    pub fn dimensions(&self) -> (u32, u32, Option<u32>) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl Display for SizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.z {
+            Some(z) => write!(
+                f,
+                "number of positions in size {x}x{y}x{z} overflows usize",
+                x = self.x,
+                y = self.y
+            ),
+            None => write!(
+                f,
+                "number of positions in size {x}x{y} overflows usize",
+                x = self.x,
+                y = self.y
+            ),
+        }
+    }
+}
+
+impl Error for SizeError {}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::BuilderError;
+
+    #[test]
+    fn builder_error_through_dyn_error() {
+        let mut err = BuilderError::new();
+        err.add_missing_field("size");
+        err.add_invalid_field("direction_weights");
+        let err: Box<dyn Error> = Box::new(err.try_throw().unwrap_err());
+
+        let message = err.to_string();
+        assert!(message.contains("missing field values: size"));
+        assert!(message.contains("invalid field values: direction_weights"));
+        assert!(err.downcast_ref::<BuilderError>().is_some());
+    }
+}
@@ -1,4 +1,7 @@
-use godot::builtin::Rect2i;
+use std::collections::HashMap;
+
+use godot::builtin::meta::{FromGodot, ToGodot};
+use godot::builtin::{Dictionary, Rect2i, Vector2i};
 use godot::classes::TileMap;
 use godot::obj::Gd;
 
@@ -6,11 +9,14 @@ use crate::map::{GridMap2D, GridSize};
 use crate::tile::identifiable::builders::IdentTileBuilder;
 use crate::tile::identifiable::collection::IdentTileCollection;
 use crate::tile::identifiable::IdentifiableTileData;
-use crate::tile::GridPosition;
+use crate::tile::{GridPosition, TileContainer, TileData};
 
 use super::collection::{GodotInfoBuilder, GodotTileMapCollection};
 use super::error::GodotTileError;
 
+/// Name of the [`TileMap`] metadata entry holding the custom per-tile data written by [`write_custom_data_to_tilemap`].
+pub const CUSTOM_DATA_META: &str = "grid_forge_custom_data";
+
 /// Loads [`GridMap2D`] from [`TileMap`], automatically loading read tiles into [`GodotTileMapCollection`].
 ///
 /// Currently supports only `layer = 0`.
@@ -107,6 +113,49 @@ pub fn write_gridmap_to_tilemap<Data: IdentifiableTileData>(
     Ok(())
 }
 
+/// Writes custom data for each tile of [`GridMap2D`] into [`TileMap`] metadata, making it possible to carry data not
+/// represented by the tile type during the roundtrip.
+///
+/// Data is stored as a [`Dictionary`] with tile coordinates as keys under [`CUSTOM_DATA_META`] entry, replacing the
+/// previously written one. Can be read back with [`read_custom_data_from_tilemap`].
+pub fn write_custom_data_to_tilemap<Data: TileData>(
+    gridmap: &GridMap2D<Data>,
+    tilemap: &mut Gd<TileMap>,
+    data_fn: impl Fn(&Data) -> i64,
+) {
+    let mut dict = Dictionary::new();
+
+    for tile in gridmap.iter_tiles() {
+        dict.set(
+            tile.grid_position().get_godot_coords(),
+            data_fn(tile.as_ref()),
+        );
+    }
+
+    tilemap.set_meta(CUSTOM_DATA_META.into(), dict.to_variant());
+}
+
+/// Reads custom data for each tile written into [`TileMap`] metadata by [`write_custom_data_to_tilemap`].
+///
+/// Returns empty map if no custom data was written.
+pub fn read_custom_data_from_tilemap(tilemap: &Gd<TileMap>) -> HashMap<GridPosition, i64> {
+    let mut out = HashMap::new();
+
+    if !tilemap.has_meta(CUSTOM_DATA_META.into()) {
+        return out;
+    }
+
+    let dict = Dictionary::from_variant(&tilemap.get_meta(CUSTOM_DATA_META.into()));
+    for (key, value) in dict.iter_shared() {
+        out.insert(
+            GridPosition::from_godot_v2i(Vector2i::from_variant(&key)),
+            i64::from_variant(&value),
+        );
+    }
+
+    out
+}
+
 fn size_from_rect(rect: Rect2i) -> GridSize {
     GridSize::new_xy(
         (rect.size.x - rect.position.x) as u32,
@@ -48,13 +48,24 @@ impl CollapseError {
 
     /// Returns `true` if the error can be solved by retrying the operation.
     pub fn is_probabilistic(&self) -> bool {
-        !matches!(self.kind, CollapseErrorKind::Init)
+        !matches!(
+            self.kind,
+            CollapseErrorKind::Init
+                | CollapseErrorKind::StampTile
+                | CollapseErrorKind::StampConflict
+        )
     }
 
     /// Returns iteration number when the error occured.
     pub fn failed_iter(&self) -> u32 {
         self.iter
     }
+
+    /// Returns `true` if the collapse process was stopped because of the exceeded timeout. In this case
+    /// [`CollapseError::failed_pos()`] returns the position which was to be collapsed next.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self.kind, CollapseErrorKind::Timeout)
+    }
 }
 
 impl Display for CollapseError {
@@ -62,19 +73,34 @@ impl Display for CollapseError {
         match self.kind {
             CollapseErrorKind::Collapse => write!(
                 f,
-                "tile at position: {:?} have no options left while collapsing on iteration {}!",
+                "contradiction at position {}: no options left while collapsing on iteration {}",
                 self.pos, self.iter
             ),
             CollapseErrorKind::Init => write!(
                 f,
-                "tile at position: {:?} have no options left during initial option removal!",
+                "contradiction at position {}: no options left during initial option removal",
                 self.pos,
             ),
             CollapseErrorKind::Propagation => write!(
                 f,
-                "tile at position: {:?} have no options left during propagation on iteration {}!",
+                "contradiction at position {}: no options left during propagation on iteration {}",
                 self.pos, self.iter
             ),
+            CollapseErrorKind::Timeout => write!(
+                f,
+                "collapse process timed out before collapsing tile at position {} on iteration {}",
+                self.pos, self.iter
+            ),
+            CollapseErrorKind::StampTile => write!(
+                f,
+                "cannot place stamp tile at position {}: position is outside the grid or tile type is missing from the rulesets",
+                self.pos,
+            ),
+            CollapseErrorKind::StampConflict => write!(
+                f,
+                "cannot place stamp tile at position {}: it conflicts with the current constraints",
+                self.pos,
+            ),
         }
     }
 }
@@ -86,8 +112,39 @@ pub(crate) enum CollapseErrorKind {
     Collapse,
     Init,
     Propagation,
+    Timeout,
+    StampTile,
+    StampConflict,
+}
+
+/// Error occuring while loading the serialized rules of the *collapsible grid*.
+///
+/// Returned when the provided bytes are malformed, or their content is inconsistent, eg. the declared number of options
+/// doesn't match the options stored.
+#[derive(Debug)]
+pub struct RulesetDecodeError {
+    reason: &'static str,
+}
+
+impl RulesetDecodeError {
+    pub(crate) fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+
+    /// Returns the description of the encountered problem.
+    pub fn reason(&self) -> &str {
+        self.reason
+    }
 }
 
+impl Display for RulesetDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot decode collapse ruleset: {}", self.reason)
+    }
+}
+
+impl Error for RulesetDecodeError {}
+
 /// Error occuring during the operations on *collapsible grids*.
 ///
 /// Indicates the inconsistency between provided [`CollapsedGrid`](crate::gen::collapse::CollapsedGrid) and target grid,
@@ -95,61 +152,136 @@ pub(crate) enum CollapseErrorKind {
 /// because the grid size incompatibility.
 #[derive(Debug)]
 pub struct CollapsibleGridError {
-    missing_type_ids: Option<Vec<u64>>,
-    sizes: Option<(GridSize, GridSize)>,
-    position: Option<GridPosition>,
+    kind: CollapsibleGridErrorKind,
+}
+
+#[derive(Debug)]
+enum CollapsibleGridErrorKind {
+    Missing(Vec<u64>),
+    WrongSize(GridSize, GridSize),
+    Collapse(GridPosition),
+    InvalidMultiplier(f32),
+    Seam(GridPosition),
 }
 
 impl CollapsibleGridError {
     pub(crate) fn new_missing(missing_type_ids: Vec<u64>) -> Self {
         Self {
-            missing_type_ids: Some(missing_type_ids),
-            sizes: None,
-            position: None,
+            kind: CollapsibleGridErrorKind::Missing(missing_type_ids),
         }
     }
     pub(crate) fn new_wrong_size(source_size: GridSize, target_size: GridSize) -> Self {
         Self {
-            missing_type_ids: None,
-            sizes: Some((source_size, target_size)),
-            position: None,
+            kind: CollapsibleGridErrorKind::WrongSize(source_size, target_size),
         }
     }
     pub(crate) fn new_collapse(position: GridPosition) -> Self {
         Self {
-            missing_type_ids: None,
-            sizes: None,
-            position: Some(position),
+            kind: CollapsibleGridErrorKind::Collapse(position),
+        }
+    }
+    pub(crate) fn new_invalid_multiplier(multiplier: f32) -> Self {
+        Self {
+            kind: CollapsibleGridErrorKind::InvalidMultiplier(multiplier),
+        }
+    }
+    pub(crate) fn new_seam(position: GridPosition) -> Self {
+        Self {
+            kind: CollapsibleGridErrorKind::Seam(position),
         }
     }
 
     /// If error originates from missing types during transforming [`GridMap2D`](crate::map::GridMap2D) of
     /// [`CollapsedTileData`](crate::gen::collapse::tile::CollapsedTileData) into [`CollapsibleGrid`](crate::gen::collapse::grid::CollapsibleGrid),
-    /// it will contain vector of `tile_type_ids` which were missing.
-    pub fn missing_type_ids(&self) -> &Option<Vec<u64>> {
-        &self.missing_type_ids
+    /// it will contain `tile_type_ids` which were missing.
+    pub fn missing_type_ids(&self) -> Option<&[u64]> {
+        match &self.kind {
+            CollapsibleGridErrorKind::Missing(missing_type_ids) => Some(missing_type_ids),
+            _ => None,
+        }
     }
 
     /// If error originates from incompatible [`GridSize`] of source [`GridMap2D`](crate::map::GridMap2D) and target
     /// [`CollapsibleGrid`](crate::gen::collapse::grid::CollapsibleGrid), it will contain tuple of (`source_size`, `target_size`).
-    pub fn sizes(&self) -> &Option<(GridSize, GridSize)> {
-        &self.sizes
+    pub fn sizes(&self) -> Option<(GridSize, GridSize)> {
+        match &self.kind {
+            CollapsibleGridErrorKind::WrongSize(source, target) => Some((*source, *target)),
+            _ => None,
+        }
     }
 
     /// If error originates from incompatible prepopulated [`CollapsedTileData`](crate::gen::collapse::CollapsedTileData) during their transformation
     /// into [`CollapsiblePatternGrid`](crate::gen::collapse::overlap::CollapsiblePatternGrid), it will contain the position of problematic tile.
-    pub fn position(&self) -> &Option<GridPosition> {
-        &self.position
+    /// If it originates from the rulesets of different regions meeting without the seam rules, it will contain the
+    /// position on the region boundary where they meet.
+    pub fn position(&self) -> Option<GridPosition> {
+        match &self.kind {
+            CollapsibleGridErrorKind::Collapse(position)
+            | CollapsibleGridErrorKind::Seam(position) => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// If error originates from the weight multiplier being negative or not a number, it will contain the provided
+    /// multiplier.
+    pub fn invalid_multiplier(&self) -> Option<f32> {
+        match &self.kind {
+            CollapsibleGridErrorKind::InvalidMultiplier(multiplier) => Some(*multiplier),
+            _ => None,
+        }
     }
 }
 
 impl Display for CollapsibleGridError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match (&self.missing_type_ids, &self.sizes, &self.position) {
-            (Some(missing), None, None) => write!(f, "there are {} `tile_type_ids` missing from underlying CollapsibleGrid data. Make sure that the `CollapsibleGrid` have been provided correct rulesets", missing.len()),
-            (None, Some((source, target)), None) => write!(f, "size of source `GridMap`: {source:?} is greater than target `CollapsibleGrid`: {target:?}"),
-            (None, None, Some(position)) => write!(f, "tile at position: {position:?} cannot get any compatible patterns"),
-            _ => unreachable!("either created by `Self::new_missing()` or `Self::new_wrong_size()`"),
+        match &self.kind {
+            CollapsibleGridErrorKind::Missing(missing) => write!(f, "there are {} `tile_type_ids` missing from underlying CollapsibleGrid data. Make sure that the `CollapsibleGrid` have been provided correct rulesets", missing.len()),
+            CollapsibleGridErrorKind::WrongSize(source, target) => write!(f, "size of source `GridMap`: {source:?} is greater than target `CollapsibleGrid`: {target:?}"),
+            CollapsibleGridErrorKind::Collapse(position) => write!(f, "tile at position {position} cannot get any compatible patterns"),
+            CollapsibleGridErrorKind::InvalidMultiplier(multiplier) => write!(f, "weight multiplier should be a non-negative number, got: {multiplier}"),
+            CollapsibleGridErrorKind::Seam(position) => write!(f, "tiles of different regions meeting at position {position} can be adjacent without a seam rule, as they share the rules of some region"),
         }
     }
 }
+
+impl Error for CollapsibleGridError {}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use crate::tile::GridPosition;
+
+    use super::{CollapseError, CollapseErrorKind, CollapsibleGridError};
+
+    #[test]
+    fn collapse_error_through_dyn_error() {
+        let err: Box<dyn Error> = Box::new(CollapseError::new(
+            GridPosition::new_xy(3, 4),
+            CollapseErrorKind::Propagation,
+            12,
+        ));
+        let message = err.to_string();
+        assert!(message.contains("contradiction at position (3, 4)"));
+        assert!(message.contains("iteration 12"));
+
+        let err = err.downcast::<CollapseError>().unwrap();
+        assert_eq!(GridPosition::new_xy(3, 4), err.failed_pos());
+        assert!(err.is_probabilistic());
+    }
+
+    #[test]
+    fn collapsible_grid_error_through_dyn_error() {
+        let err: Box<dyn Error> = Box::new(CollapsibleGridError::new_missing(vec![1, 5]));
+        assert!(err.to_string().contains("2 `tile_type_ids` missing"));
+
+        let err = err.downcast::<CollapsibleGridError>().unwrap();
+        assert_eq!(Some([1, 5].as_slice()), err.missing_type_ids());
+        assert!(err.invalid_multiplier().is_none());
+
+        let err = CollapsibleGridError::new_invalid_multiplier(-1.);
+        assert!(err.to_string().contains("got: -1"));
+        assert_eq!(Some(-1.), err.invalid_multiplier());
+        assert!(err.missing_type_ids().is_none());
+    }
+}
@@ -1,14 +1,18 @@
 use std::collections::HashSet;
 
 use crate::{
-    map::{GridMap2D, GridSize},
+    map::{GridDir, GridMap2D, GridSize},
     tile::{
-        identifiable::{builders::IdentTileBuilder, IdentifiableTileData},
+        identifiable::{
+            builders::IdentTileBuilder, collection::IdentTileCollection, IdentifiableTileData,
+        },
         GridPosition, GridTile, GridTileRef, TileContainer,
     },
 };
 
-use super::{error::CollapsibleGridError, CollapsedTileData, CollapsibleTileData};
+use super::{
+    error::CollapsibleGridError, singular::AdjacencyRules, CollapsedTileData, CollapsibleTileData,
+};
 
 /// [`GridMap2D`] containing data of [`CollapsedTileData`].
 pub struct CollapsedGrid {
@@ -45,6 +49,90 @@ impl CollapsedGrid {
     pub fn tile_type_ids(&self) -> impl Iterator<Item = &u64> {
         self.tile_type_ids.iter()
     }
+
+    /// Returns positions of all collapsed tiles with given `tile_type_id`, eg. to feed them into another generation
+    /// stage.
+    pub fn positions_of_type(&self, tile_type_id: u64) -> Vec<GridPosition> {
+        if !self.tile_type_ids.contains(&tile_type_id) {
+            return Vec::new();
+        }
+        self.grid
+            .iter_tiles()
+            .filter(|tile| tile.as_ref().tile_type_id() == tile_type_id)
+            .map(|tile| tile.grid_position())
+            .collect()
+    }
+
+    /// Inserts collapsed tiles from `other` grid into this one, with their positions shifted by `at`. Tiles which
+    /// would land out of bounds are skipped, and already occupied positions are overwritten only if `overwrite` is
+    /// `true`. Tiles are copied whole, keeping their rotation.
+    ///
+    /// Returns the number of inserted tiles.
+    pub fn overlay(&mut self, other: &Self, at: GridPosition, overwrite: bool) -> usize {
+        let inserted = self.grid.paste(
+            other
+                .grid
+                .iter_tiles()
+                .map(|tile| (tile.grid_position(), tile.as_ref().clone())),
+            at,
+            overwrite,
+        );
+        self.tile_type_ids = self
+            .grid
+            .iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id())
+            .collect();
+        inserted
+    }
+
+    /// Checks if the grid can be tiled - placed next to copies of itself - without breaking the provided
+    /// [`AdjacencyRules`].
+    ///
+    /// For each pair of opposing edges (left/right and top/bottom) the tiles on them are checked to be mutually valid
+    /// neighbours, as if the grid wrapped around. Returns `false` if any of the edge positions is empty, or the grid has
+    /// no positions at all.
+    pub fn is_tileable<Data: IdentifiableTileData>(&self, rules: &AdjacencyRules<Data>) -> bool {
+        let size = self.grid.size();
+        if size.x() == 0 || size.y() == 0 {
+            return false;
+        }
+        let (max_x, max_y) = (size.x() - 1, size.y() - 1);
+
+        let edge_pairs = (0..size.y())
+            .map(|y| {
+                (
+                    GridPosition::new_xy(max_x, y),
+                    GridPosition::new_xy(0, y),
+                    GridDir::RIGHT,
+                )
+            })
+            .chain((0..size.x()).map(|x| {
+                (
+                    GridPosition::new_xy(x, max_y),
+                    GridPosition::new_xy(x, 0),
+                    GridDir::DOWN,
+                )
+            }));
+
+        for (pos, wrapped_pos, direction) in edge_pairs {
+            let (Some(tile), Some(wrapped)) = (
+                self.grid.get_tile_at_position(&pos),
+                self.grid.get_tile_at_position(&wrapped_pos),
+            ) else {
+                return false;
+            };
+            let (tile_id, wrapped_id) = (
+                tile.as_ref().tile_type_id(),
+                wrapped.as_ref().tile_type_id(),
+            );
+            if !rules.is_valid_at_dir(tile_id, direction, wrapped_id)
+                || !rules.is_valid_at_dir(wrapped_id, direction.opposite(), tile_id)
+            {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 impl AsRef<GridMap2D<CollapsedTileData>> for CollapsedGrid {
@@ -53,6 +141,19 @@ impl AsRef<GridMap2D<CollapsedTileData>> for CollapsedGrid {
     }
 }
 
+impl From<GridMap2D<CollapsedTileData>> for CollapsedGrid {
+    fn from(grid: GridMap2D<CollapsedTileData>) -> Self {
+        let tile_type_ids = grid
+            .iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id())
+            .collect();
+        Self {
+            grid,
+            tile_type_ids,
+        }
+    }
+}
+
 /// Trait shared by a structs holding a grid of [`CollapsibleTileData`], useable by dedicated resolvers to collapse
 /// the grid.
 pub trait CollapsibleGrid<IT: IdentifiableTileData, CT: CollapsibleTileData>:
@@ -67,6 +168,20 @@ pub trait CollapsibleGrid<IT: IdentifiableTileData, CT: CollapsibleTileData>:
         builder: &B,
     ) -> Result<GridMap2D<OT>, CollapsibleGridError>;
 
+    /// Returns the internal option index used for given `tile_type_id`, or `None` if it isn't a possible option.
+    ///
+    /// For grids of overlapping patterns the `tile_type_id` is the identifier of the pattern.
+    fn option_index_of(&self, tile_type_id: u64) -> Option<usize> {
+        self._option_data().get_tile_data(&tile_type_id).copied()
+    }
+
+    /// Returns the `tile_type_id` of given internal option index, or `None` if there is no such option.
+    ///
+    /// For grids of overlapping patterns the returned identifier is the identifier of the pattern.
+    fn tile_type_id_of(&self, option_index: usize) -> Option<u64> {
+        self._option_data().get_tile_type_id(&option_index)
+    }
+
     /// Returns all empty positions in the internal grid.
     fn empty_positions(&self) -> Vec<GridPosition> {
         self._grid().get_all_empty_positions()
@@ -102,6 +217,17 @@ pub trait CollapsibleGrid<IT: IdentifiableTileData, CT: CollapsibleTileData>:
             }
         }
     }
+
+    /// Restores every position in the internal grid to uncollapsed tile with all options possible and their original
+    /// weights, as prepared out of the rules. Cheaper than creating the new grid when the rules are unchanged.
+    fn reset(&mut self) {
+        let positions = self._grid().size().get_all_possible_positions();
+        let tiles = CT::new_from_frequency(&positions, self._option_data());
+        let grid = self._grid_mut();
+        for tile in tiles {
+            grid.insert_tile(tile);
+        }
+    }
 }
 
 pub(crate) mod private {
@@ -125,3 +251,139 @@ pub(crate) mod private {
         fn _get_initial_propagate_items(&self, to_collapse: &[GridPosition]) -> Vec<PropagateItem>;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::{
+        gen::collapse::{
+            singular::{
+                learn_tileable_rules, Analyzer, CollapsibleTileGrid, IdentityAnalyzer, Resolver,
+            },
+            CollapsedTileData, CollapsibleGrid,
+        },
+        map::{GridMap2D, GridSize},
+        tile::{identifiable::IdentifiableTileData, GridPosition, TileRotation},
+    };
+
+    use super::CollapsedGrid;
+
+    fn checkerboard(size: GridSize) -> GridMap2D<CollapsedTileData> {
+        GridMap2D::checkerboard(size, CollapsedTileData::new(0), CollapsedTileData::new(1))
+    }
+
+    fn checkerboard_rules() -> IdentityAnalyzer<CollapsedTileData> {
+        let mut analyzer = IdentityAnalyzer::default();
+        analyzer.analyze(&checkerboard(GridSize::new_xy(4, 4)));
+        analyzer
+    }
+
+    #[test]
+    fn tileable_when_edges_wrap() {
+        let size = GridSize::new_xy(4, 6);
+        let (adjacency, frequency) = learn_tileable_rules(&checkerboard(GridSize::new_xy(4, 4)));
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(0),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        assert!(grid.retrieve_collapsed().is_tileable(&adjacency));
+        assert!(!CollapsedGrid::new(GridSize::new_xy(0, 4)).is_tileable(&adjacency));
+    }
+
+    #[test]
+    fn not_tileable_when_edges_conflict() {
+        let analyzer = checkerboard_rules();
+        // Odd width places identical tiles on the opposing left and right edges.
+        let grid = CollapsedGrid::from(checkerboard(GridSize::new_xy(3, 4)));
+
+        assert!(!grid.is_tileable(analyzer.adjacency()));
+    }
+
+    #[test]
+    fn not_tileable_with_empty_edge() {
+        let analyzer = checkerboard_rules();
+        let mut grid = CollapsedGrid::new(GridSize::new_xy(2, 2));
+        grid.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(0));
+
+        assert!(!grid.is_tileable(analyzer.adjacency()));
+    }
+
+    #[test]
+    fn positions_of_type_in_checkerboard() {
+        let mut grid = CollapsedGrid::from(checkerboard(GridSize::new_xy(3, 2)));
+        grid.insert_data(&GridPosition::new_xy(2, 1), CollapsedTileData::new(5));
+
+        let mut positions = grid.positions_of_type(0);
+        positions.sort();
+        assert_eq!(
+            vec![
+                GridPosition::new_xy(0, 0),
+                GridPosition::new_xy(1, 1),
+                GridPosition::new_xy(2, 0)
+            ],
+            positions
+        );
+        assert_eq!(2, grid.positions_of_type(1).len());
+        assert_eq!(vec![GridPosition::new_xy(2, 1)], grid.positions_of_type(5));
+        assert!(grid.positions_of_type(7).is_empty());
+    }
+
+    #[test]
+    fn overlay_combines_grids() {
+        let mut base = CollapsedGrid::new(GridSize::new_xy(4, 4));
+        base.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(1));
+        base.insert_data(&GridPosition::new_xy(2, 2), CollapsedTileData::new(2));
+
+        let mut region = CollapsedGrid::new(GridSize::new_xy(2, 2));
+        region.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(3));
+        region.insert_data(&GridPosition::new_xy(1, 1), CollapsedTileData::new(4));
+
+        let id_at = |grid: &CollapsedGrid, x: u32, y: u32| {
+            grid.as_ref()
+                .get_tile_at_position(&GridPosition::new_xy(x, y))
+                .map(|tile| tile.as_ref().tile_type_id())
+        };
+
+        // (1, 1) of the region lands on occupied (2, 2).
+        assert_eq!(1, base.overlay(&region, GridPosition::new_xy(1, 1), false));
+        assert_eq!(Some(3), id_at(&base, 1, 1));
+        assert_eq!(Some(2), id_at(&base, 2, 2));
+
+        // (1, 1) of the region lands out of bounds at (4, 4).
+        assert_eq!(1, base.overlay(&region, GridPosition::new_xy(3, 3), true));
+        assert_eq!(Some(3), id_at(&base, 3, 3));
+
+        assert_eq!(2, base.overlay(&region, GridPosition::new_xy(1, 1), true));
+        assert_eq!(Some(4), id_at(&base, 2, 2));
+
+        let mut ids = base.tile_type_ids().copied().collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(vec![1, 3, 4], ids);
+        assert_eq!(4, base.as_ref().get_all_positions().len());
+    }
+
+    #[test]
+    fn overlay_keeps_rotation() {
+        let mut base = CollapsedGrid::new(GridSize::new_xy(3, 3));
+        let mut region = CollapsedGrid::new(GridSize::new_xy(1, 1));
+        region.insert_data(
+            &GridPosition::new_xy(0, 0),
+            CollapsedTileData::new(2).with_rotation(TileRotation::R90),
+        );
+
+        assert_eq!(1, base.overlay(&region, GridPosition::new_xy(1, 2), false));
+        let tile = base
+            .as_ref()
+            .get_tile_at_position(&GridPosition::new_xy(1, 2))
+            .unwrap();
+        assert_eq!(2, tile.as_ref().tile_type_id());
+        assert_eq!(Some(TileRotation::R90), tile.as_ref().rotation());
+    }
+}
@@ -46,7 +46,7 @@ mod tile;
 use std::{collections::HashSet, ops::Index};
 
 // Flattened reexports
-pub use error::CollapseError;
+pub use error::{CollapseError, RulesetDecodeError};
 pub use grid::{CollapsedGrid, CollapsibleGrid};
 pub use queue::*;
 pub use tile::*;
@@ -141,6 +141,13 @@ pub(crate) mod private {
             }
         }
 
+        /// Ensures the element is present in the table, even without any adjacencies.
+        pub(crate) fn insert_element(&mut self, el_id: u64) {
+            if let std::collections::hash_map::Entry::Vacant(e) = self.inner.entry(el_id) {
+                e.insert(Adjacencies::new());
+            }
+        }
+
         pub(crate) fn get_all_adjacencies_in_direction(
             &self,
             el_id: &u64,
@@ -151,5 +158,11 @@ pub(crate) mod private {
                 .expect("cannot get adjacencies for provided `el_id`")[*direction]
                 .iter()
         }
+
+        pub(crate) fn is_adjacent(&self, el_id: u64, direction: GridDir, adj_id: u64) -> bool {
+            self.inner
+                .get(&el_id)
+                .is_some_and(|adj| adj[direction].contains(&adj_id))
+        }
     }
 }
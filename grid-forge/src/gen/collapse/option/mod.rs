@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::{Index, IndexMut},
 };
 
@@ -8,6 +8,7 @@ use crate::{
     tile::identifiable::collection::IdentTileCollection,
 };
 
+use super::error::RulesetDecodeError;
 use super::private::AdjacencyTable;
 
 #[derive(Debug, Clone)]
@@ -81,18 +82,35 @@ impl IdentTileCollection for PerOptionData {
 }
 
 impl PerOptionData {
+    /// Bytes starting the data serialized with [`to_bytes`](Self::to_bytes).
+    pub const RULESET_MAGIC: [u8; 4] = *b"GFRS";
+
+    /// Version of the format used by [`to_bytes`](Self::to_bytes). Data serialized in other versions is rejected by
+    /// [`from_bytes`](Self::from_bytes).
+    pub const RULESET_VERSION: u32 = 1;
+
     pub fn populate(
         &mut self,
         options_with_weights: &BTreeMap<u64, u32>,
         adjacencies: &AdjacencyTable,
+    ) {
+        self.populate_excluding(options_with_weights, adjacencies, &BTreeSet::new());
+    }
+
+    /// Populates the data like [`populate`](Self::populate), additionally pruning the `excluded` tiles from the
+    /// adjacencies. Any other tile missing from `options_with_weights` is still considered an invalid ruleset.
+    pub(crate) fn populate_excluding(
+        &mut self,
+        options_with_weights: &BTreeMap<u64, u32>,
+        adjacencies: &AdjacencyTable,
+        excluded: &BTreeSet<u64>,
     ) {
         for (n, (option_id, option_weight)) in options_with_weights.iter().enumerate() {
             self.add_tile_data(*option_id, n);
 
-            self.opt_with_weight.as_mut().push((
-                *option_weight,
-                (*option_weight as f32) * (*option_weight as f32).log2(),
-            ));
+            self.opt_with_weight
+                .as_mut()
+                .push((*option_weight, Self::weight_log(*option_weight)));
         }
 
         self.option_count = self.option_map.len();
@@ -100,14 +118,25 @@ impl PerOptionData {
 
         for trans_id in 0..self.option_count {
             let original_id = self.get_tile_type_id(&trans_id).unwrap();
-            self.adjacencies
-                .table
-                .push(self.translate_adjacency_table(original_id, adjacencies));
+            self.adjacencies.table.push(self.translate_adjacency_table(
+                original_id,
+                adjacencies,
+                excluded,
+            ));
         }
 
         self.generate_ways_to_be_option();
     }
 
+    /// Calculates the `weight * log2(weight)` term of the Shannon entrophy for the option weight. Zero weight
+    /// results in `0`, as the limit of the term, instead of `NaN`.
+    pub(crate) fn weight_log(weight: u32) -> f32 {
+        if weight == 0 {
+            return 0.;
+        }
+        (weight as f32) * (weight as f32).log2()
+    }
+
     pub fn get_all_enabled_in_direction(&self, option_id: usize, direction: GridDir) -> &[usize] {
         &self.adjacencies[option_id][direction]
     }
@@ -132,21 +161,133 @@ impl PerOptionData {
         &self.ways_to_be_option
     }
 
+    /// Serializes the populated data into bytes, which can be loaded back with [`from_bytes`](Self::from_bytes)
+    /// without the need to [`populate`](Self::populate) it again.
+    ///
+    /// Data starts with the [`RULESET_MAGIC`](Self::RULESET_MAGIC) bytes and the
+    /// [`RULESET_VERSION`](Self::RULESET_VERSION) of the format. All numbers are stored in little-endian order: the
+    /// version, option count and possible option count, followed by the `tile_type_id`, weights, adjacencies and ways
+    /// to be option of each option in the order of their indices.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::from(Self::RULESET_MAGIC);
+        bytes.extend(Self::RULESET_VERSION.to_le_bytes());
+        bytes.extend((self.option_count as u32).to_le_bytes());
+        bytes.extend((self.possible_options_count as u32).to_le_bytes());
+
+        for option_idx in 0..self.option_count {
+            let tile_type_id = self
+                .get_tile_type_id(&option_idx)
+                .expect("populated option should have its `tile_type_id`");
+            let (weight, weight_log) = self.get_weights(option_idx);
+            bytes.extend(tile_type_id.to_le_bytes());
+            bytes.extend(weight.to_le_bytes());
+            bytes.extend(weight_log.to_le_bytes());
+            for direction in GridDir::ALL_2D {
+                let adjacent = &self.adjacencies[option_idx][*direction];
+                bytes.extend((adjacent.len() as u32).to_le_bytes());
+                for adjacent_idx in adjacent {
+                    bytes.extend((*adjacent_idx as u32).to_le_bytes());
+                }
+            }
+            for ways in self.ways_to_be_option.table[option_idx].inner() {
+                bytes.extend((*ways as u32).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Loads the data serialized with [`to_bytes`](Self::to_bytes). Returns error if the `bytes` are malformed, were
+    /// serialized in other version of the format or their content is inconsistent, eg. the ways to be option don't
+    /// match the adjacencies.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RulesetDecodeError> {
+        let mut reader = ByteReader::new(bytes);
+        let mut data = Self::default();
+
+        if reader.read::<4>()? != Self::RULESET_MAGIC {
+            return Err(RulesetDecodeError::new("missing ruleset header"));
+        }
+        if reader.read_u32()? != Self::RULESET_VERSION {
+            return Err(RulesetDecodeError::new("unsupported ruleset version"));
+        }
+
+        let option_count = reader.read_u32()? as usize;
+        let possible_options_count = reader.read_u32()? as usize;
+
+        for option_idx in 0..option_count {
+            let tile_type_id = reader.read_u64()?;
+            if !data.add_tile_data(tile_type_id, option_idx) {
+                return Err(RulesetDecodeError::new("duplicated `tile_type_id`"));
+            }
+            let weight = reader.read_u32()?;
+            let weight_log = reader.read_f32()?;
+            data.opt_with_weight.as_mut().push((weight, weight_log));
+
+            let mut adjacencies = DirectionTable::<Vec<usize>>::default();
+            for direction in GridDir::ALL_2D {
+                let len = reader.read_u32()?;
+                for _ in 0..len {
+                    let adjacent_idx = reader.read_u32()? as usize;
+                    if adjacent_idx >= option_count {
+                        return Err(RulesetDecodeError::new(
+                            "adjacent option index out of bounds",
+                        ));
+                    }
+                    adjacencies[*direction].push(adjacent_idx);
+                }
+            }
+
+            let mut ways = WaysToBeOption::EMPTY_TABLE;
+            for way in ways.iter_mut() {
+                *way = reader.read_u32()? as usize;
+            }
+            if Self::ways_to_be_option_for(&adjacencies).inner() != ways {
+                return Err(RulesetDecodeError::new(
+                    "ways to be option inconsistent with adjacencies",
+                ));
+            }
+            data.adjacencies.as_mut().push(adjacencies);
+            data.ways_to_be_option
+                .mut_inner()
+                .as_mut()
+                .push(DirectionTable::new_array(ways));
+        }
+
+        if !reader.is_finished() {
+            return Err(RulesetDecodeError::new("unexpected trailing bytes"));
+        }
+        let counted_possible = data.ways_to_be_option.iter_possible().count();
+        if possible_options_count > option_count || counted_possible != possible_options_count {
+            return Err(RulesetDecodeError::new("inconsistent option count"));
+        }
+
+        data.option_count = option_count;
+        data.possible_options_count = possible_options_count;
+        Ok(data)
+    }
+
     fn generate_ways_to_be_option(&mut self) {
-        let inner = self.ways_to_be_option.mut_inner().as_mut();
         for adj in self.adjacencies.table.iter() {
-            let table = DirectionTable::new_array([
-                adj.index(GridDir::UP).len(),
-                adj.index(GridDir::DOWN).len(),
-                adj.index(GridDir::LEFT).len(),
-                adj.index(GridDir::RIGHT).len(),
-            ]);
-            if table.inner().contains(&0) {
+            let table = Self::ways_to_be_option_for(adj);
+            if table.inner() == WaysToBeOption::EMPTY_TABLE {
                 self.possible_options_count -= 1;
-                inner.push(WaysToBeOption::EMPTY_DIR_TABLE)
-            } else {
-                inner.push(table);
             }
+            self.ways_to_be_option.mut_inner().as_mut().push(table);
+        }
+    }
+
+    /// Number of ways to become the option out of its adjacencies. Options without any adjacency in some direction
+    /// can't be possible, so they get the empty table.
+    fn ways_to_be_option_for(adjacencies: &DirectionTable<Vec<usize>>) -> DirectionTable<usize> {
+        let table = DirectionTable::new_array([
+            adjacencies.index(GridDir::UP).len(),
+            adjacencies.index(GridDir::DOWN).len(),
+            adjacencies.index(GridDir::LEFT).len(),
+            adjacencies.index(GridDir::RIGHT).len(),
+        ]);
+        if table.inner().contains(&0) {
+            WaysToBeOption::EMPTY_DIR_TABLE
+        } else {
+            table
         }
     }
 
@@ -154,12 +295,14 @@ impl PerOptionData {
         &self,
         original_id: u64,
         adjacencies: &AdjacencyTable,
+        excluded: &BTreeSet<u64>,
     ) -> DirectionTable<Vec<usize>> {
         let mut table = DirectionTable::default();
         for direction in GridDir::ALL_2D {
             table[*direction] = Vec::from_iter(
                 adjacencies
                     .get_all_adjacencies_in_direction(&original_id, direction)
+                    .filter(|original_id| !excluded.contains(original_id))
                     .map(|original_id: &u64| {
                         self.get_tile_data(original_id)
                             .expect("cannot get mapped id")
@@ -171,6 +314,43 @@ impl PerOptionData {
     }
 }
 
+/// Sequential reader of little-endian numbers used by [`PerOptionData::from_bytes`].
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], RulesetDecodeError> {
+        let chunk = self
+            .bytes
+            .get(self.position..self.position + N)
+            .ok_or(RulesetDecodeError::new("unexpected end of data"))?;
+        self.position += N;
+        Ok(chunk.try_into().unwrap())
+    }
+
+    fn read_u32(&mut self) -> Result<u32, RulesetDecodeError> {
+        self.read().map(u32::from_le_bytes)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, RulesetDecodeError> {
+        self.read().map(u64::from_le_bytes)
+    }
+
+    fn read_f32(&mut self) -> Result<f32, RulesetDecodeError> {
+        self.read().map(f32::from_le_bytes)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct WaysToBeOption {
     table: PerOptionTable<DirectionTable<usize>>,
@@ -227,3 +407,38 @@ impl WaysToBeOption {
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::gen::collapse::singular::CollapsibleTile;
+    use crate::gen::collapse::CollapsibleTileData;
+
+    use super::PerOptionData;
+
+    #[test]
+    fn weight_log_grows_with_weight() {
+        assert_eq!(0., PerOptionData::weight_log(0));
+        assert_eq!(0., PerOptionData::weight_log(1));
+        assert_eq!(2., PerOptionData::weight_log(2));
+        assert_eq!(24., PerOptionData::weight_log(8));
+
+        let terms = (1..50).map(PerOptionData::weight_log).collect::<Vec<_>>();
+        assert!(terms.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn entrophy_grows_with_option_count() {
+        for weight in [1, 3, 10] {
+            let entrophies = (1..20u32)
+                .map(|count| {
+                    CollapsibleTile::calc_entrophy_ext(
+                        weight * count,
+                        PerOptionData::weight_log(weight) * count as f32,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert!(entrophies[0].abs() < 1e-6);
+            assert!(entrophies.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+}
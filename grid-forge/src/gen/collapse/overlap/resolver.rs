@@ -1,13 +1,12 @@
 use std::marker::PhantomData;
 
-use rand::Rng;
-
 use crate::gen::collapse::error::{CollapseError, CollapseErrorKind};
 use crate::gen::collapse::grid::private::Sealed;
 use crate::gen::collapse::overlap::CollapsiblePattern;
 use crate::gen::collapse::queue::CollapseQueue;
 use crate::gen::collapse::tile::CollapsibleTileData;
 use crate::gen::collapse::{EntrophyQueue, PositionQueue, PropagateItem, Propagator};
+use crate::gen::GridRng;
 
 use crate::tile::identifiable::collection::IdentTileCollection;
 use crate::tile::identifiable::IdentifiableTileData;
@@ -60,7 +59,7 @@ where
         positions: &[GridPosition],
     ) -> Result<CollapsiblePatternGrid<P, Data>, CollapseError>
     where
-        R: Rng,
+        R: GridRng,
     {
         use crate::gen::collapse::queue::private::Sealed as _;
         use crate::gen::collapse::tile::private::Sealed as _;
@@ -143,7 +142,7 @@ where
         mut queue: PositionQueue,
     ) -> Result<CollapsiblePatternGrid<P, Data>, CollapseError>
     where
-        R: Rng,
+        R: GridRng,
     {
         use crate::gen::collapse::queue::private::Sealed as _;
         use crate::gen::collapse::tile::private::Sealed as _;
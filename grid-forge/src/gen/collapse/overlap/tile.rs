@@ -1,18 +1,16 @@
 use std::collections::HashSet;
 use std::marker::PhantomData;
 
-use private::Sealed;
-use rand::distributions::Distribution;
-use rand::Rng;
-
 use crate::gen::collapse::error::CollapsibleGridError;
 use crate::gen::collapse::option::{PerOptionData, WaysToBeOption};
 use crate::gen::collapse::{tile::*, CollapsedGrid, CollapsibleGrid, PropagateItem};
+use crate::gen::GridRng;
 use crate::map::{GridMap2D, GridSize};
 use crate::tile::identifiable::builders::IdentTileBuilder;
 use crate::tile::identifiable::collection::IdentTileCollection;
 use crate::tile::identifiable::IdentifiableTileData;
 use crate::tile::{GridPosition, GridTile, GridTileRef, TileContainer, TileData};
+use private::Sealed;
 
 use super::pattern::OverlappingPattern;
 use super::{AdjacencyRules, FrequencyHints, PatternCollection};
@@ -71,7 +69,7 @@ impl<P: OverlappingPattern> private::Sealed for CollapsiblePattern<P> {
         self.weight_log_sum -= weights.1;
     }
 
-    fn collapse<R: Rng>(
+    fn collapse<R: GridRng>(
         &mut self,
         rng: &mut R,
         options_data: &crate::gen::collapse::option::PerOptionData,
@@ -192,7 +190,7 @@ where
         })
     }
 
-    pub fn new_from_collapsed<R: Rng>(
+    pub fn new_from_collapsed<R: GridRng>(
         rng: &mut R,
         collapsed: &CollapsedGrid,
         patterns: PatternCollection<P>,
@@ -229,13 +227,12 @@ where
         })
     }
 
-    fn collapsed_into_collapsible_pattern<R: Rng>(
+    fn collapsed_into_collapsible_pattern<R: GridRng>(
         rng: &mut R,
         collapsed: &CollapsedGrid,
         patterns: &PatternCollection<P>,
         options: &PerOptionData,
     ) -> Result<Vec<GridTile<CollapsiblePattern<P>>>, CollapsibleGridError> {
-        let entrophy_uniform = CollapsiblePattern::<P>::entrophy_uniform();
         let ways = options.get_ways_to_become_option();
         let mut out = Vec::new();
 
@@ -287,7 +284,7 @@ where
                 current_ways,
                 weights.0,
                 weights.1,
-                entrophy_uniform.sample(rng),
+                CollapsiblePattern::<P>::entrophy_noise(rng),
             ))
         }
 
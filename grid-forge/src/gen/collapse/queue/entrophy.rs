@@ -3,10 +3,9 @@ use std::{
     collections::{BTreeSet, HashMap},
 };
 
-use rand::Rng;
-
 use super::CollapseQueue;
 use crate::gen::collapse::{option::PerOptionData, tile::CollapsibleTileData};
+use crate::gen::GridRng;
 use crate::map::GridMap2D;
 use crate::tile::{GridPosition, GridTile, TileContainer};
 
@@ -48,16 +47,57 @@ impl Ord for EntrophyItem {
 /// Select next position to collapse using smallest entrophy condition.
 ///
 /// Its state will be updated every time after tile entrophy changed by removing some of its options.
+///
+/// Optionally, the next position can be selected uniformly at random among all positions with entrophy within the
+/// band above the smallest one, which breaks up the visible growth fronts of the pure smallest entrophy selection.
 #[derive(Default)]
 pub struct EntrophyQueue {
     by_entrophy: BTreeSet<EntrophyItem>,
     by_pos: HashMap<GridPosition, f32>,
+    band: f32,
 }
 
 impl EntrophyQueue {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Creates queue selecting the next position among the ones with entrophy at most `band` greater than the
+    /// smallest one. Band of `0` or lesser, or not a number, selects always the smallest entrophy position.
+    pub fn with_band(band: f32) -> Self {
+        Self {
+            band: if band.is_nan() { 0. } else { band },
+            ..Default::default()
+        }
+    }
+
+    /// Pop next position for collapsing, randomly chosen within the entrophy band. Randomness is used only if the band
+    /// is positive, so otherwise the `rng` state is left unchanged.
+    ///
+    /// The cost of the pop is linear in the number of positions within the band, so wide bands slow the collapse down.
+    pub(crate) fn get_next_position_banded<R: GridRng>(
+        &mut self,
+        rng: &mut R,
+    ) -> Option<GridPosition> {
+        if self.band <= 0. {
+            return self.get_next_position();
+        }
+        let max_entrophy = self.by_entrophy.first()?.entrophy + self.band;
+        let candidates = self
+            .by_entrophy
+            .iter()
+            .take_while(|item| item.entrophy <= max_entrophy)
+            .copied()
+            .collect::<Vec<_>>();
+        // Not a number entrophy can't be compared with the band, so there may be no candidates at all.
+        if candidates.is_empty() {
+            return self.get_next_position();
+        }
+        let item = candidates[rng.gen_range(0..candidates.len() as u32) as usize];
+        self.by_entrophy.remove(&item);
+        self.by_pos.remove(&item.pos);
+        Some(item.pos)
+    }
 }
 
 impl CollapseQueue for EntrophyQueue {
@@ -99,7 +139,7 @@ impl CollapseQueue for EntrophyQueue {
 }
 
 impl super::private::Sealed for EntrophyQueue {
-    fn populate_inner_grid<R: Rng, Data: CollapsibleTileData>(
+    fn populate_inner_grid<R: GridRng, Data: CollapsibleTileData>(
         &mut self,
         rng: &mut R,
         grid: &mut GridMap2D<Data>,
@@ -34,10 +34,11 @@ where
 }
 
 pub(crate) mod private {
-    use rand::Rng;
-
     use crate::{
-        gen::collapse::{option::PerOptionData, CollapsibleTileData},
+        gen::{
+            collapse::{option::PerOptionData, CollapsibleTileData},
+            GridRng,
+        },
         map::GridMap2D,
         tile::GridPosition,
     };
@@ -51,7 +52,7 @@ pub(crate) mod private {
             positions: &[GridPosition],
             options_data: &PerOptionData,
         ) where
-            R: Rng,
+            R: GridRng,
             Data: CollapsibleTileData;
 
         fn needs_update_after_options_change(&self) -> bool {
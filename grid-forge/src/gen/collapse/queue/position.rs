@@ -1,9 +1,8 @@
 use std::cmp::Ordering;
 
-use rand::Rng;
-
 use crate::gen::collapse::option::PerOptionData;
 use crate::gen::collapse::tile::CollapsibleTileData;
+use crate::gen::GridRng;
 use crate::map::GridMap2D;
 use crate::tile::{GridPosition, GridTile, TileContainer};
 
@@ -119,7 +118,7 @@ impl CollapseQueue for PositionQueue {
 }
 
 impl super::private::Sealed for PositionQueue {
-    fn populate_inner_grid<R: Rng, Data: CollapsibleTileData>(
+    fn populate_inner_grid<R: GridRng, Data: CollapsibleTileData>(
         &mut self,
         _rng: &mut R,
         grid: &mut GridMap2D<Data>,
@@ -26,6 +26,10 @@ impl PropagateItem {
 #[derive(Default)]
 pub struct Propagator {
     inner: Vec<PropagateItem>,
+    /// Options removed during propagation, collected only if tracking is enabled.
+    removed: Option<Vec<PropagateItem>>,
+    /// If `true`, tiles left without options are not reported as contradictions.
+    relaxed: bool,
 }
 
 impl Propagator {
@@ -33,6 +37,26 @@ impl Propagator {
         self.inner.push(item);
     }
 
+    /// Enables collecting of the options removed during propagation, to be retrieved with
+    /// [`drain_removed`](Self::drain_removed).
+    pub(crate) fn track_removed(&mut self) {
+        self.removed = Some(Vec::new());
+    }
+
+    /// Disables reporting of the contradictions. Tiles left without any options are skipped, leaving resolving them
+    /// to the caller.
+    pub(crate) fn relax(&mut self) {
+        self.relaxed = true;
+    }
+
+    /// Retrieves options removed during propagation since the last call.
+    pub(crate) fn drain_removed(&mut self) -> Vec<PropagateItem> {
+        self.removed
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     pub(crate) fn propagate<Tile: CollapsibleTileData>(
         &mut self,
         grid: &mut GridMap2D<Tile>,
@@ -67,10 +91,13 @@ impl Propagator {
                     if removed {
                         binding.remove_option(option_data.get_weights(*option_idx));
                     }
-                    if !binding.has_compatible_options() {
+                    if !binding.has_compatible_options() && !self.relaxed {
                         return Err(pos_to_update);
                     }
                     if removed {
+                        if let Some(tracked) = self.removed.as_mut() {
+                            tracked.push(PropagateItem::new(tile.grid_position(), *option_idx));
+                        }
                         self.push_propagate(PropagateItem::new(tile.grid_position(), *option_idx));
                         tiles_to_update.insert(tile.grid_position());
                     }
@@ -79,7 +106,11 @@ impl Propagator {
         }
 
         for pos in tiles_to_update {
-            queue.update_queue(&grid.get_mut_tile_at_position(&pos).unwrap());
+            let tile = grid.get_mut_tile_at_position(&pos).unwrap();
+            // Entrophy of tiles without options is undefined.
+            if tile.as_ref().has_compatible_options() {
+                queue.update_queue(&tile);
+            }
         }
 
         Ok(())
@@ -1,9 +1,10 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::marker::PhantomData;
 
 use crate::gen::collapse::private::AdjacencyTable;
 use crate::map::{DirectionTable, GridDir, GridMap2D};
 use crate::tile::identifiable::IdentifiableTileData;
+use crate::tile::TileRotation;
 use crate::tile::{GridPosition, TileContainer};
 
 /// Trait shared by analyzers producing [`AdjacencyRules`].
@@ -74,13 +75,178 @@ where
         )
     }
 
-    fn add_adjacency_raw(&mut self, tile_id: u64, adjacent_id: u64, direction: GridDir) {
+    pub(crate) fn add_adjacency_raw(&mut self, tile_id: u64, adjacent_id: u64, direction: GridDir) {
         self.inner.insert_adjacency(tile_id, direction, adjacent_id);
     }
 
     pub(crate) fn inner(&self) -> &AdjacencyTable {
         &self.inner
     }
+
+    /// Checks if the tile of `adjacent_id` can be placed in the given `direction` of the tile of `tile_id`.
+    pub fn is_valid_at_dir(&self, tile_id: u64, direction: GridDir, adjacent_id: u64) -> bool {
+        self.inner.is_adjacent(tile_id, direction, adjacent_id)
+    }
+
+    /// Creates new rules allowing adjacencies valid in either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut rules = self.clone();
+        for tile_id in other.inner.as_ref().keys() {
+            rules.inner.insert_element(*tile_id);
+        }
+        for (tile_id, direction, adjacent_id) in other.iter_adjacencies() {
+            rules.add_adjacency_raw(tile_id, adjacent_id, direction);
+        }
+        rules
+    }
+
+    /// Creates new rules allowing only adjacencies valid in both `self` and `other`.
+    ///
+    /// All tiles present in `self` are kept in the new rules, even if they are left without any valid adjacency.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.filtered(|tile_id, direction, adjacent_id| {
+            other.is_valid_at_dir(tile_id, direction, adjacent_id)
+        })
+    }
+
+    /// Creates new rules allowing adjacencies valid in `self`, but not in `other`. Useful for removing some forbidden
+    /// transitions from the base rules.
+    ///
+    /// All tiles present in `self` are kept in the new rules, even if they are left without any valid adjacency.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.filtered(|tile_id, direction, adjacent_id| {
+            !other.is_valid_at_dir(tile_id, direction, adjacent_id)
+        })
+    }
+
+    /// Derives the adjacency rules of every rotation in provided symmetry `groups` from the rules of their base
+    /// rotation, rotating the directions and the grouped neighbours accordingly. Tiles outside of any group are
+    /// treated as the same in every rotation.
+    ///
+    /// Rules are derived from the adjacencies of base rotations, and the ones of ungrouped tiles with base rotations
+    /// as their neighbours. Already existing rules of other rotations are kept.
+    pub fn derive_symmetry_groups(&mut self, groups: &[TileSymmetryGroup]) {
+        let rotation_of = |tile_id: u64| {
+            groups.iter().find_map(|group| {
+                group
+                    .rotations
+                    .iter()
+                    .position(|id| *id == tile_id)
+                    .map(|idx| (group, idx))
+            })
+        };
+        let rotated = |tile_id: u64, times: usize| {
+            rotation_of(tile_id).map_or(tile_id, |(group, idx)| group.rotation(idx + times))
+        };
+
+        let sources = self
+            .iter_adjacencies()
+            .filter(|(tile_id, _, adjacent_id)| match rotation_of(*tile_id) {
+                Some((_, idx)) => idx == 0,
+                None => matches!(rotation_of(*adjacent_id), Some((_, 0))),
+            })
+            .collect::<Vec<_>>();
+
+        for (tile_id, direction, adjacent_id) in sources {
+            for times in 1..4 {
+                self.add_adjacency_raw(
+                    rotated(tile_id, times),
+                    rotated(adjacent_id, times),
+                    TileSymmetryGroup::rotate_direction(direction, times),
+                );
+            }
+        }
+    }
+
+    /// Exports the rules as a [Graphviz](https://graphviz.org/) DOT graph, with `tile_type_id`s as nodes and directed
+    /// edges from the tile to its valid neighbour, labeled with the [`GridDir`] of the neighbour. Nodes and edges are
+    /// sorted, so the output is stable.
+    pub fn to_dot(&self) -> String {
+        let mut tile_ids = self.inner.as_ref().keys().copied().collect::<Vec<_>>();
+        tile_ids.sort();
+        let mut adjacencies = self.iter_adjacencies().collect::<Vec<_>>();
+        adjacencies.sort_by_key(|(tile_id, direction, adjacent_id)| {
+            (*tile_id, *direction as u8, *adjacent_id)
+        });
+
+        let mut dot = String::from("digraph adjacency {\n");
+        for tile_id in tile_ids {
+            dot.push_str(&format!("    {tile_id};\n"));
+        }
+        for (tile_id, direction, adjacent_id) in adjacencies {
+            dot.push_str(&format!(
+                "    {tile_id} -> {adjacent_id} [label=\"{direction:?}\"];\n"
+            ));
+        }
+        dot.push('}');
+        dot
+    }
+
+    pub(crate) fn filtered(&self, keep: impl Fn(u64, GridDir, u64) -> bool) -> Self {
+        let mut rules = Self::default();
+        for tile_id in self.inner.as_ref().keys() {
+            rules.inner.insert_element(*tile_id);
+        }
+        for (tile_id, direction, adjacent_id) in self.iter_adjacencies() {
+            if keep(tile_id, direction, adjacent_id) {
+                rules.add_adjacency_raw(tile_id, adjacent_id, direction);
+            }
+        }
+        rules
+    }
+
+    fn iter_adjacencies(&self) -> impl Iterator<Item = (u64, GridDir, u64)> + '_ {
+        self.inner
+            .as_ref()
+            .iter()
+            .flat_map(|(tile_id, adjacencies)| {
+                GridDir::ALL_2D.iter().flat_map(move |direction| {
+                    adjacencies[*direction]
+                        .iter()
+                        .map(move |adjacent_id| (*tile_id, *direction, *adjacent_id))
+                })
+            })
+    }
+}
+
+/// Group of tiles being the four rotations of one tile, such as a road turning in a different direction.
+///
+/// Used to derive the adjacency rules of all rotations from the ones of the base one via
+/// [`AdjacencyRules::derive_symmetry_groups`], and to share their frequency with
+/// [`FrequencyHints::share_symmetry_group`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileSymmetryGroup {
+    rotations: [u64; 4],
+}
+
+impl TileSymmetryGroup {
+    /// Creates new group out of `tile_type_id` of its rotations. The first one is the base, and each next one is
+    /// the previous rotated by 90 degrees clockwise.
+    pub fn new(rotations: [u64; 4]) -> Self {
+        Self { rotations }
+    }
+
+    /// All `tile_type_id` in the group, starting with the base rotation.
+    pub fn rotations(&self) -> &[u64; 4] {
+        &self.rotations
+    }
+
+    /// Returns the rotation of the tile with given `tile_type_id` in relation to the base one, or `None` if it is
+    /// not a part of the group.
+    pub fn rotation_of(&self, tile_type_id: u64) -> Option<TileRotation> {
+        self.rotations
+            .iter()
+            .position(|id| *id == tile_type_id)
+            .map(|idx| TileRotation::ALL[idx])
+    }
+
+    fn rotation(&self, idx: usize) -> u64 {
+        self.rotations[idx % 4]
+    }
+
+    fn rotate_direction(direction: GridDir, times: usize) -> GridDir {
+        (0..times % 4).fold(direction, |direction, _| direction.rotate_cw())
+    }
 }
 
 /// Analyzer creating exact adjacency rules on basis of sample map.
@@ -93,6 +259,8 @@ where
 {
     tiles: Vec<u64>,
     adjacency_rules: AdjacencyRules<Data>,
+    frequency: FrequencyHints<Data>,
+    wrapping: bool,
 }
 
 impl<Data> Default for IdentityAnalyzer<Data>
@@ -103,6 +271,8 @@ where
         Self {
             tiles: Vec::new(),
             adjacency_rules: AdjacencyRules::default(),
+            frequency: FrequencyHints::default(),
+            wrapping: false,
         }
     }
 }
@@ -118,16 +288,47 @@ where
             }
 
             for dir in GridDir::ALL_2D {
-                if let Some(neighbour) = map.get_neighbour_at(&pos, dir) {
+                let neighbour = if self.wrapping {
+                    map.get_neighbour_wrapping(&pos, dir)
+                } else {
+                    map.get_neighbour_at(&pos, dir)
+                };
+                if let Some(neighbour) = neighbour {
                     self.adjacency_rules.add_adjacency(&tile, &neighbour, *dir)
                 }
             }
         }
     }
 
+    /// If `true`, the analyzed maps are treated as wrapping around their edges, so the tiles on the opposite edges
+    /// are deemed adjacent. Rules gathered this way allow generating maps which can be tiled seamlessly. Defaults to
+    /// `false`.
+    pub fn with_wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
     pub fn adjacency(&self) -> &AdjacencyRules<Data> {
         &self.adjacency_rules
     }
+
+    /// Analyzes the `map` with given importance. Adjacencies are gathered as with [`analyze`](Analyzer::analyze), but
+    /// each tile occurence is counted `weight` times into the [`frequency`](Self::frequency), so the more important
+    /// samples have greater impact on the generated output. Map with zero `weight` is ignored.
+    pub fn analyze_weighted(&mut self, map: &GridMap2D<Data>, weight: u32) {
+        if weight == 0 {
+            return;
+        }
+        for position in map.get_all_positions() {
+            self.analyze_tile_at_pos(map, position);
+        }
+        self.frequency.analyze_weighted(map, weight);
+    }
+
+    /// Frequency of tiles occurence in all analyzed maps, scaled by their weights.
+    pub fn frequency(&self) -> &FrequencyHints<Data> {
+        &self.frequency
+    }
 }
 
 impl<Data> Analyzer<Data> for IdentityAnalyzer<Data>
@@ -135,9 +336,7 @@ where
     Data: IdentifiableTileData,
 {
     fn analyze(&mut self, map: &GridMap2D<Data>) {
-        for position in map.get_all_positions() {
-            self.analyze_tile_at_pos(map, position);
-        }
+        self.analyze_weighted(map, 1);
     }
 
     fn adjacency(&self) -> &AdjacencyRules<Data> {
@@ -149,6 +348,22 @@ where
     }
 }
 
+/// Learns the rules for generating seamlessly tileable maps out of the `sample`, which is treated as wrapping around
+/// its edges. Shorthand for analyzing the `sample` with [`IdentityAnalyzer`] set up
+/// [`with_wrapping`](IdentityAnalyzer::with_wrapping).
+///
+/// Generated maps can be verified with [`CollapsedGrid::is_tileable`](crate::gen::collapse::CollapsedGrid::is_tileable).
+pub fn learn_tileable_rules<Data>(
+    sample: &GridMap2D<Data>,
+) -> (AdjacencyRules<Data>, FrequencyHints<Data>)
+where
+    Data: IdentifiableTileData,
+{
+    let mut analyzer = IdentityAnalyzer::default().with_wrapping(true);
+    analyzer.analyze(sample);
+    (analyzer.adjacency_rules, analyzer.frequency)
+}
+
 /// Analyzer creating adjacency rules based on the borders between tiles.
 ///
 /// Rules generated by it are more liberal than the ones produced by [`IdentityAnalyzer`], as the tiles are deemed to be adjacent not only
@@ -165,7 +380,17 @@ where
     /// TileId key
     inner: HashMap<u64, TileBordersAdjacency<Data>>,
     /// BorderId key; (TileId; GridDir)
-    border_types: HashMap<u64, Vec<(u64, GridDir)>>,
+    border_types: HashMap<BorderId, Vec<(u64, GridDir)>>,
+}
+
+/// Identifier of the border type in [`BorderAnalyzer`]. Manually declared and learned border types are kept in
+/// separate spaces, so they never collide.
+///
+/// Variant order matters: while unifying two border types, the lesser one is kept, so manual types take precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum BorderId {
+    Manual(u64),
+    Learned(u64),
 }
 
 impl<Data> Default for BorderAnalyzer<Data>
@@ -215,9 +440,36 @@ where
     }
 
     pub fn prepare(&mut self) {
+        // Replaced border types would leave stale rules behind, so they are regenerated from scratch.
+        self.adjacency_rules = AdjacencyRules::default();
         self.generate_adjacency_rules()
     }
 
+    /// Manually declare the border type of the tile in given direction.
+    ///
+    /// Tiles with the same `border_id` on opposite borders will be adjacent to each other. Only the border of the tile
+    /// in given direction is changed: if it had already some border type assigned, either manually or during analysis,
+    /// it is replaced, while the other borders of that type are left intact. Manual border ids are independent from
+    /// the ones assigned during analysis, so the learned borders can be freely mixed with the manual ones.
+    ///
+    /// After declaration of new border types, the [`prepare`](Self::prepare) method should be called to generate the rules.
+    pub fn set_border_type(&mut self, tile_id: u64, direction: GridDir, border_id: u64) {
+        self.ensure_adjacencies_present_for_tiles(&[tile_id]);
+        if !self.tiles.contains(&tile_id) {
+            self.tiles.push(tile_id);
+        }
+
+        let border_id = BorderId::Manual(border_id);
+        match *self.get_border_id(&tile_id, &direction) {
+            Some(existing) if existing == border_id => {}
+            Some(existing) => {
+                self.remove_border_id(existing, tile_id, &direction);
+                self.set_border_id(border_id, tile_id, &direction);
+            }
+            None => self.set_border_id(border_id, tile_id, &direction),
+        }
+    }
+
     fn analyze_tile_at_pos(&mut self, map: &GridMap2D<Data>, pos: GridPosition) {
         if let Some(tile) = map.get_tile_at_position(&pos) {
             if !self.tiles.contains(&tile.as_ref().tile_type_id()) {
@@ -321,7 +573,7 @@ where
         }
     }
 
-    fn set_border_id(&mut self, border_id: u64, tile_id: u64, direction: &GridDir) {
+    fn set_border_id(&mut self, border_id: BorderId, tile_id: u64, direction: &GridDir) {
         self.inner
             .get_mut(&tile_id)
             .unwrap()
@@ -332,23 +584,37 @@ where
             .push((tile_id, *direction));
     }
 
-    fn get_border_id(&self, tile_id: &u64, direction: &GridDir) -> &Option<u64> {
+    fn get_border_id(&self, tile_id: &u64, direction: &GridDir) -> &Option<BorderId> {
         self.inner.get(tile_id).unwrap().get_at_dir(direction)
     }
 
-    fn unify_border_id(&mut self, existing: u64, into: u64) {
+    fn remove_border_id(&mut self, border_id: BorderId, tile_id: u64, direction: &GridDir) {
+        let Some(borders) = self.border_types.get_mut(&border_id) else {
+            return;
+        };
+        borders.retain(|border| *border != (tile_id, *direction));
+        if borders.is_empty() {
+            self.border_types.remove(&border_id);
+        }
+    }
+
+    fn unify_border_id(&mut self, existing: BorderId, into: BorderId) {
         let cache = self.border_types.remove(&existing).unwrap();
         for (tile_id, direction) in cache.iter() {
             self.set_border_id(into, *tile_id, direction);
         }
     }
 
-    fn get_next_border_id(&self) -> u64 {
-        if let Some(max_id) = self.border_types.keys().max() {
-            *max_id + 1
-        } else {
-            0
-        }
+    fn get_next_border_id(&self) -> BorderId {
+        let max_id = self
+            .border_types
+            .keys()
+            .filter_map(|border_id| match border_id {
+                BorderId::Learned(id) => Some(*id),
+                BorderId::Manual(_) => None,
+            })
+            .max();
+        BorderId::Learned(max_id.map_or(0, |max_id| max_id + 1))
     }
 }
 
@@ -356,7 +622,7 @@ struct TileBordersAdjacency<Data>
 where
     Data: IdentifiableTileData,
 {
-    borders: DirectionTable<Option<u64>>,
+    borders: DirectionTable<Option<BorderId>>,
     phantom: PhantomData<Data>,
 }
 
@@ -364,11 +630,11 @@ impl<Data> TileBordersAdjacency<Data>
 where
     Data: IdentifiableTileData,
 {
-    fn set_at_dir(&mut self, dir: &GridDir, border_id: u64) {
+    fn set_at_dir(&mut self, dir: &GridDir, border_id: BorderId) {
         self.borders[*dir] = Some(border_id);
     }
 
-    fn get_at_dir(&self, dir: &GridDir) -> &Option<u64> {
+    fn get_at_dir(&self, dir: &GridDir) -> &Option<BorderId> {
         &self.borders[*dir]
     }
 }
@@ -385,6 +651,167 @@ where
     }
 }
 
+/// Analyzer creating adjacency rules based on the colors of tile corners, as in *Wang* or *blob* tilesets.
+///
+/// Each tile have four corners, and two tiles are deemed adjacent in given direction if both corners on their shared
+/// edge have the same color. Corner colors can be declared manually with [`set_corner_colors`](Self::set_corner_colors)
+/// or derived from the sample gridmap - corners meeting on the shared edge of tiles adjacent in the sample are treated
+/// as the same color.
+pub struct CornerAnalyzer<Data>
+where
+    Data: IdentifiableTileData,
+{
+    tiles: Vec<u64>,
+    adjacency_rules: AdjacencyRules<Data>,
+    /// Union-find parents of the corner nodes.
+    corners: HashMap<CornerNode, CornerNode>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum CornerNode {
+    /// TileId; corner index
+    Tile(u64, usize),
+    Color(u64),
+}
+
+impl<Data> Default for CornerAnalyzer<Data>
+where
+    Data: IdentifiableTileData,
+{
+    fn default() -> Self {
+        Self {
+            tiles: Vec::new(),
+            adjacency_rules: AdjacencyRules::default(),
+            corners: HashMap::new(),
+        }
+    }
+}
+
+impl<Data> Analyzer<Data> for CornerAnalyzer<Data>
+where
+    Data: IdentifiableTileData,
+{
+    fn analyze(&mut self, map: &GridMap2D<Data>) {
+        self.adjacency_rules = AdjacencyRules::default();
+        for position in map.get_all_positions() {
+            self.analyze_tile_at_pos(map, position);
+        }
+        self.generate_adjacency_rules();
+    }
+
+    fn adjacency(&self) -> &AdjacencyRules<Data> {
+        &self.adjacency_rules
+    }
+
+    fn tiles(&self) -> &[u64] {
+        &self.tiles
+    }
+}
+
+impl<Data> CornerAnalyzer<Data>
+where
+    Data: IdentifiableTileData,
+{
+    /// Indices of the corners shared with the neighbour in given direction, as pairs of (`tile_corner`,
+    /// `neighbour_corner`).
+    const fn shared_corners(direction: &GridDir) -> [(usize, usize); 2] {
+        match direction {
+            GridDir::UP => [(0, 2), (1, 3)],
+            GridDir::DOWN => [(2, 0), (3, 1)],
+            GridDir::LEFT => [(0, 1), (2, 3)],
+            GridDir::RIGHT => [(1, 0), (3, 2)],
+        }
+    }
+
+    /// Manually declare the colors of tile corners, in order: *up-left*, *up-right*, *down-left*, *down-right*.
+    ///
+    /// If the corner had already some color assigned, either manually or during analysis, both colors will be treated
+    /// as the same one.
+    ///
+    /// After declaration of new corner colors, the [`prepare`](Self::prepare) method should be called to generate the rules.
+    pub fn set_corner_colors(&mut self, tile_id: u64, colors: [u64; 4]) {
+        self.ensure_tile_present(tile_id);
+        for (corner, color) in colors.into_iter().enumerate() {
+            self.unify(CornerNode::Tile(tile_id, corner), CornerNode::Color(color));
+        }
+    }
+
+    pub fn prepare(&mut self) {
+        self.generate_adjacency_rules()
+    }
+
+    fn analyze_tile_at_pos(&mut self, map: &GridMap2D<Data>, pos: GridPosition) {
+        if let Some(tile) = map.get_tile_at_position(&pos) {
+            let tile_id = tile.as_ref().tile_type_id();
+            self.ensure_tile_present(tile_id);
+
+            for dir in GridDir::ALL_2D {
+                if let Some(neighbour) = map.get_neighbour_at(&pos, dir) {
+                    let neighbour_id = neighbour.as_ref().tile_type_id();
+                    for (corner, neighbour_corner) in Self::shared_corners(dir) {
+                        self.unify(
+                            CornerNode::Tile(tile_id, corner),
+                            CornerNode::Tile(neighbour_id, neighbour_corner),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn generate_adjacency_rules(&mut self) {
+        let tiles = self.tiles.clone();
+        for tile_id in tiles.iter() {
+            for neighbour_id in tiles.iter() {
+                for dir in GridDir::ALL_2D {
+                    let matching =
+                        Self::shared_corners(dir)
+                            .into_iter()
+                            .all(|(corner, neighbour_corner)| {
+                                self.find(CornerNode::Tile(*tile_id, corner))
+                                    == self.find(CornerNode::Tile(*neighbour_id, neighbour_corner))
+                            });
+                    if matching {
+                        self.adjacency_rules
+                            .add_adjacency_raw(*tile_id, *neighbour_id, *dir);
+                    }
+                }
+            }
+        }
+    }
+
+    fn ensure_tile_present(&mut self, tile_id: u64) {
+        if !self.tiles.contains(&tile_id) {
+            self.tiles.push(tile_id);
+        }
+    }
+
+    fn find(&mut self, node: CornerNode) -> CornerNode {
+        let mut root = node;
+        while let Some(parent) = self.corners.get(&root) {
+            if *parent == root {
+                break;
+            }
+            root = *parent;
+        }
+
+        let mut current = node;
+        while current != root {
+            let parent = self.corners.insert(current, root).unwrap_or(root);
+            current = parent;
+        }
+        root
+    }
+
+    fn unify(&mut self, first: CornerNode, second: CornerNode) {
+        let first_root = self.find(first);
+        let second_root = self.find(second);
+        if first_root != second_root {
+            self.corners.insert(first_root, second_root);
+        }
+    }
+}
+
 /// Frequency hints for the *adjacency-based* generative algorithm.
 ///
 /// Describes the frequency of occurence of all distinct tiles. Can be generated automatically while analyzing sample
@@ -395,6 +822,7 @@ where
     Data: IdentifiableTileData,
 {
     weights: BTreeMap<u64, u32>,
+    excluded: BTreeSet<u64>,
     id_type: PhantomData<Data>,
 }
 
@@ -405,6 +833,7 @@ where
     fn clone(&self) -> Self {
         Self {
             weights: self.weights.clone(),
+            excluded: self.excluded.clone(),
             id_type: PhantomData::<Data>,
         }
     }
@@ -417,6 +846,7 @@ where
     fn default() -> Self {
         Self {
             weights: BTreeMap::new(),
+            excluded: BTreeSet::new(),
             id_type: PhantomData::<T>,
         }
     }
@@ -430,6 +860,7 @@ where
     where
         Tile: TileContainer + AsRef<Data>,
     {
+        self.excluded.remove(&tile.as_ref().tile_type_id());
         let entry = self
             .weights
             .entry(tile.as_ref().tile_type_id())
@@ -441,6 +872,7 @@ where
     where
         Tile: TileContainer + AsRef<Data>,
     {
+        self.excluded.remove(&tile.as_ref().tile_type_id());
         if let Some(count) = self.weights.get_mut(&tile.as_ref().tile_type_id()) {
             *count += 1;
         } else {
@@ -448,6 +880,69 @@ where
         }
     }
 
+    /// Excludes tile of given `tile_type_id` from the generation. It won't be considered as an option for any
+    /// position, and it is pruned from adjacencies of all other tiles.
+    ///
+    /// Returns `true` if the tile was present in the hints before exclusion. Setting the weight for the tile or counting
+    /// it afterwards includes it back.
+    ///
+    /// Excluding a tile can leave some other tiles without any possible neighbour in a given direction, making them
+    /// impossible to place. Use [`unsupported_tiles`](Self::unsupported_tiles) to validate the hints afterwards.
+    pub fn exclude(&mut self, tile_type_id: u64) -> bool {
+        self.excluded.insert(tile_type_id);
+        self.weights.remove(&tile_type_id).is_some()
+    }
+
+    pub(crate) fn get_excluded(&self) -> &BTreeSet<u64> {
+        &self.excluded
+    }
+
+    /// Validates the hints against provided `adjacency` rules. Returns `tile_type_id` of every tile that has no
+    /// remaining neighbour in some direction, alongside that direction.
+    pub fn unsupported_tiles(&self, adjacency: &AdjacencyRules<Data>) -> Vec<(u64, GridDir)> {
+        let mut unsupported = Vec::new();
+        for tile_type_id in self.weights.keys() {
+            for direction in GridDir::ALL_2D {
+                let supported = adjacency
+                    .inner
+                    .as_ref()
+                    .get(tile_type_id)
+                    .is_some_and(|adj| {
+                        adj[*direction]
+                            .iter()
+                            .any(|adj_id| self.weights.contains_key(adj_id))
+                    });
+                if !supported {
+                    unsupported.push((*tile_type_id, *direction));
+                }
+            }
+        }
+        unsupported
+    }
+
+    /// Makes all rotations in the `group` equally frequent, splitting their total weight evenly between them. If the
+    /// total isn't divisible by 4, the remainder is distributed one by one starting from the base rotation, so the
+    /// total weight is preserved. Groups without any weight are left untouched.
+    pub fn share_symmetry_group(&mut self, group: &TileSymmetryGroup) {
+        let total = group
+            .rotations
+            .iter()
+            .filter_map(|tile_type_id| self.weights.get(tile_type_id))
+            .sum::<u32>();
+        if total == 0 {
+            return;
+        }
+        let (share, remainder) = (total / 4, total % 4);
+        for (idx, tile_type_id) in group.rotations.into_iter().enumerate() {
+            let extra = u32::from((idx as u32) < remainder);
+            self.weights.insert(tile_type_id, share + extra);
+        }
+    }
+
+    pub(crate) fn set_weight_raw(&mut self, tile_type_id: u64, weight: u32) {
+        self.weights.insert(tile_type_id, weight);
+    }
+
     pub(crate) fn get_all_weights_cloned(&self) -> BTreeMap<u64, u32> {
         self.weights.clone()
     }
@@ -458,4 +953,502 @@ where
             self.count_tile(&reference)
         }
     }
+
+    /// Analyzes the `map`, counting each tile occurence `weight` times.
+    pub fn analyze_weighted(&mut self, map: &GridMap2D<Data>, weight: u32) {
+        for tile in map.iter_tiles() {
+            *self
+                .weights
+                .entry(tile.as_ref().tile_type_id())
+                .or_default() += weight;
+        }
+    }
+}
+
+/// Observed frequency of adjacencies for the *adjacency-based* generative algorithm.
+///
+/// Complements the [`FrequencyHints`], counting how many times each pair of tiles were placed next to each other in
+/// a given direction within the analyzed sample maps.
+#[derive(Debug)]
+pub struct AdjacencyFrequency<Data>
+where
+    Data: IdentifiableTileData,
+{
+    counts: HashMap<(u64, u64, GridDir), u32>,
+    id_type: PhantomData<Data>,
+}
+
+impl<Data> Clone for AdjacencyFrequency<Data>
+where
+    Data: IdentifiableTileData,
+{
+    fn clone(&self) -> Self {
+        Self {
+            counts: self.counts.clone(),
+            id_type: PhantomData::<Data>,
+        }
+    }
+}
+
+impl<Data> Default for AdjacencyFrequency<Data>
+where
+    Data: IdentifiableTileData,
+{
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+            id_type: PhantomData::<Data>,
+        }
+    }
+}
+
+impl<Data> AdjacencyFrequency<Data>
+where
+    Data: IdentifiableTileData,
+{
+    /// Counts all adjacencies present in the `map`, adding them to the already gathered ones. Returns all counts,
+    /// keyed by `(tile_type_id, neighbour_tile_type_id, direction)`, where `direction` points from the tile to its
+    /// neighbour.
+    pub fn analyze(&mut self, map: &GridMap2D<Data>) -> &HashMap<(u64, u64, GridDir), u32> {
+        for tile in map.iter_tiles() {
+            for direction in GridDir::ALL_2D {
+                if let Some(neighbour) = map.get_neighbour_at(&tile.grid_position(), direction) {
+                    *self
+                        .counts
+                        .entry((
+                            tile.as_ref().tile_type_id(),
+                            neighbour.as_ref().tile_type_id(),
+                            *direction,
+                        ))
+                        .or_default() += 1;
+                }
+            }
+        }
+        &self.counts
+    }
+
+    /// Returns number of times the tile of `neighbour_id` was observed in `direction` from the tile of `tile_id`.
+    pub fn count(&self, tile_id: u64, direction: GridDir, neighbour_id: u64) -> u32 {
+        self.counts
+            .get(&(tile_id, neighbour_id, direction))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns all gathered counts.
+    pub fn counts(&self) -> &HashMap<(u64, u64, GridDir), u32> {
+        &self.counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gen::collapse::singular::tile::test::rules_without;
+    use crate::map::{GridDir, GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::BasicIdentTileData;
+    use crate::tile::{GridPosition, GridTile};
+
+    use super::{
+        AdjacencyFrequency, AdjacencyRules, Analyzer, BorderAnalyzer, CornerAnalyzer,
+        FrequencyHints, IdentityAnalyzer, TileSymmetryGroup,
+    };
+
+    #[test]
+    fn tileable_rules_wrap_sample_edges() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaChaRng;
+
+        use crate::gen::collapse::singular::{CollapsibleTileGrid, Resolver};
+        use crate::gen::collapse::CollapsibleGrid;
+
+        let sample_size = GridSize::new_xy(4, 3);
+        let mut sample = GridMap2D::new(sample_size);
+        for pos in sample_size.get_all_possible_positions() {
+            sample.insert_data(&pos, BasicIdentTileData::tile_new(*pos.x() as u64));
+        }
+
+        let mut plain = IdentityAnalyzer::default();
+        plain.analyze(&sample);
+        assert!(!plain.adjacency().is_valid_at_dir(3, GridDir::RIGHT, 0));
+
+        let (adjacency, frequency) = super::learn_tileable_rules(&sample);
+        assert!(adjacency.is_valid_at_dir(3, GridDir::RIGHT, 0));
+        assert!(adjacency.is_valid_at_dir(0, GridDir::LEFT, 3));
+        assert!(adjacency.is_valid_at_dir(2, GridDir::UP, 2));
+        assert!(!adjacency.is_valid_at_dir(2, GridDir::RIGHT, 0));
+
+        let size = GridSize::new_xy(8, 5);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(4),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+        assert!(grid.retrieve_collapsed().is_tileable(&adjacency));
+    }
+
+    fn rules_from(adjacencies: &[(u64, GridDir, u64)]) -> AdjacencyRules<BasicIdentTileData> {
+        let mut rules = AdjacencyRules::default();
+        for (tile_id, direction, adjacent_id) in adjacencies {
+            let tile =
+                |id| GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(id));
+            rules.add_adjacency(&tile(*tile_id), &tile(*adjacent_id), *direction);
+        }
+        rules
+    }
+
+    #[test]
+    fn rules_to_dot() {
+        let mut rules = rules_from(&[(1, GridDir::RIGHT, 2), (2, GridDir::DOWN, 2)]);
+        rules.inner.insert_element(3);
+        let dot = rules.to_dot();
+
+        assert!(dot.starts_with("digraph adjacency {\n"));
+        assert!(dot.ends_with('}'));
+        for node in ["    1;\n", "    2;\n", "    3;\n"] {
+            assert!(dot.contains(node), "missing node {node:?} in {dot}");
+        }
+        for edge in [
+            "    1 -> 2 [label=\"RIGHT\"];\n",
+            "    2 -> 2 [label=\"DOWN\"];\n",
+        ] {
+            assert!(dot.contains(edge), "missing edge {edge:?} in {dot}");
+        }
+        assert_eq!(2, dot.matches("->").count());
+        assert_eq!(dot, rules.clone().to_dot());
+    }
+
+    /// Rules composed with [`COMPOSED_OTHER`] in the set operations tests.
+    const COMPOSED_BASE: &[(u64, GridDir, u64)] = &[
+        (0, GridDir::RIGHT, 0),
+        (0, GridDir::RIGHT, 1),
+        (1, GridDir::UP, 1),
+    ];
+    const COMPOSED_OTHER: &[(u64, GridDir, u64)] = &[(0, GridDir::RIGHT, 1), (2, GridDir::DOWN, 0)];
+
+    #[test]
+    fn rules_union() {
+        let (base, other) = (rules_from(COMPOSED_BASE), rules_from(COMPOSED_OTHER));
+        let union = base.union(&other);
+
+        assert!(union.is_valid_at_dir(0, GridDir::RIGHT, 0));
+        assert!(union.is_valid_at_dir(0, GridDir::RIGHT, 1));
+        assert!(union.is_valid_at_dir(1, GridDir::UP, 1));
+        assert!(union.is_valid_at_dir(2, GridDir::DOWN, 0));
+        assert!(!union.is_valid_at_dir(0, GridDir::LEFT, 1));
+    }
+
+    #[test]
+    fn rules_intersection() {
+        let (base, other) = (rules_from(COMPOSED_BASE), rules_from(COMPOSED_OTHER));
+        let intersection = base.intersection(&other);
+
+        assert!(intersection.is_valid_at_dir(0, GridDir::RIGHT, 1));
+        assert!(!intersection.is_valid_at_dir(0, GridDir::RIGHT, 0));
+        assert!(!intersection.is_valid_at_dir(1, GridDir::UP, 1));
+        assert!(!intersection.is_valid_at_dir(2, GridDir::DOWN, 0));
+        // Tile without adjacencies left is still present.
+        assert!(intersection.inner().as_ref().contains_key(&1));
+    }
+
+    #[test]
+    fn rules_difference() {
+        let (base, other) = (rules_from(COMPOSED_BASE), rules_from(COMPOSED_OTHER));
+        let difference = base.difference(&other);
+
+        assert!(difference.is_valid_at_dir(0, GridDir::RIGHT, 0));
+        assert!(!difference.is_valid_at_dir(0, GridDir::RIGHT, 1));
+        assert!(difference.is_valid_at_dir(1, GridDir::UP, 1));
+        assert!(!difference.is_valid_at_dir(2, GridDir::DOWN, 0));
+        assert!(!difference.inner().as_ref().contains_key(&2));
+    }
+
+    #[test]
+    fn manual_border_types_become_adjacent() {
+        let mut analyzer = BorderAnalyzer::<BasicIdentTileData>::default();
+        analyzer.set_border_type(1, GridDir::RIGHT, 3);
+        analyzer.set_border_type(2, GridDir::LEFT, 3);
+        analyzer.prepare();
+
+        let rules = analyzer.adjacency();
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 2));
+        assert!(rules.is_valid_at_dir(2, GridDir::LEFT, 1));
+        assert!(!rules.is_valid_at_dir(1, GridDir::LEFT, 2));
+        assert!(!rules.is_valid_at_dir(2, GridDir::RIGHT, 1));
+    }
+
+    #[test]
+    fn manual_border_types_mix_with_learned() {
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 1));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(3));
+
+        let mut analyzer = BorderAnalyzer::default();
+        analyzer.analyze(&map);
+        analyzer.set_border_type(1, GridDir::UP, 10);
+        analyzer.set_border_type(2, GridDir::DOWN, 10);
+        // Learned border ids start from `0` too, but are independent from the manual ones.
+        analyzer.set_border_type(4, GridDir::LEFT, 0);
+        analyzer.prepare();
+
+        let rules = analyzer.adjacency();
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 3));
+        assert!(rules.is_valid_at_dir(1, GridDir::UP, 2));
+        assert!(rules.is_valid_at_dir(2, GridDir::DOWN, 1));
+        assert!(!rules.is_valid_at_dir(4, GridDir::LEFT, 1));
+        assert!(analyzer.tiles().contains(&2));
+        assert!(analyzer.tiles().contains(&4));
+    }
+
+    #[test]
+    fn manual_border_type_replaces_only_requested_side() {
+        // Both `1` and `2` are learned to be placed left of `3`, sharing the border type.
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 2));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(3));
+        map.insert_data(&GridPosition::new_xy(0, 1), BasicIdentTileData::tile_new(2));
+        map.insert_data(&GridPosition::new_xy(1, 1), BasicIdentTileData::tile_new(3));
+
+        let mut analyzer = BorderAnalyzer::default();
+        analyzer.analyze(&map);
+        analyzer.set_border_type(1, GridDir::RIGHT, 5);
+        analyzer.set_border_type(4, GridDir::LEFT, 5);
+        analyzer.prepare();
+
+        let rules = analyzer.adjacency();
+        assert!(rules.is_valid_at_dir(2, GridDir::RIGHT, 3));
+        assert!(!rules.is_valid_at_dir(1, GridDir::RIGHT, 3));
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 4));
+        assert!(!rules.is_valid_at_dir(2, GridDir::RIGHT, 4));
+    }
+
+    #[test]
+    fn excluding_only_neighbour_is_reported() {
+        // Tile `1` can be placed only next to tile `0`.
+        let (mut frequency, adjacency) = rules_without(2, 1, |_, _, other| other == 1);
+        assert!(frequency.unsupported_tiles(&adjacency).is_empty());
+
+        assert!(frequency.exclude(0));
+        assert!(!frequency.exclude(0));
+
+        let unsupported = frequency.unsupported_tiles(&adjacency);
+        assert_eq!(4, unsupported.len());
+        for dir in GridDir::ALL_2D {
+            assert!(unsupported.contains(&(1, *dir)));
+        }
+    }
+
+    #[test]
+    fn two_color_corners_produce_adjacencies() {
+        // Each bit of the `tile_id` is the color of one corner.
+        let colors = |id: u64| [id & 1, (id >> 1) & 1, (id >> 2) & 1, (id >> 3) & 1];
+        let mut analyzer = CornerAnalyzer::<BasicIdentTileData>::default();
+        for tile_id in 0..16 {
+            analyzer.set_corner_colors(tile_id, colors(tile_id));
+        }
+        analyzer.prepare();
+
+        let rules = analyzer.adjacency();
+        assert_eq!(16, analyzer.tiles().len());
+        for tile_id in 0..16 {
+            for neighbour_id in 0..16 {
+                let (tile, neighbour) = (colors(tile_id), colors(neighbour_id));
+                assert_eq!(
+                    tile[1] == neighbour[0] && tile[3] == neighbour[2],
+                    rules.is_valid_at_dir(tile_id, GridDir::RIGHT, neighbour_id)
+                );
+                assert_eq!(
+                    tile[0] == neighbour[2] && tile[1] == neighbour[3],
+                    rules.is_valid_at_dir(tile_id, GridDir::UP, neighbour_id)
+                );
+            }
+        }
+        assert!(rules.is_valid_at_dir(0b0101, GridDir::LEFT, 0b1010));
+        assert!(!rules.is_valid_at_dir(0b0101, GridDir::RIGHT, 0b0101));
+    }
+
+    #[test]
+    fn corner_colors_learned_from_sample() {
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 1));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(2));
+
+        let mut analyzer = CornerAnalyzer::default();
+        analyzer.analyze(&map);
+        analyzer.set_corner_colors(3, [7, 7, 7, 7]);
+        analyzer.set_corner_colors(2, [7, 7, 7, 7]);
+        analyzer.prepare();
+
+        let rules = analyzer.adjacency();
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 2));
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 3));
+        assert!(rules.is_valid_at_dir(3, GridDir::UP, 2));
+        assert!(!rules.is_valid_at_dir(1, GridDir::LEFT, 2));
+    }
+
+    #[test]
+    fn adjacency_frequency_counts_pairs() {
+        // 1 1 2
+        // 3 3 3
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 2));
+        for (x, y, id) in [
+            (0, 0, 1),
+            (1, 0, 1),
+            (2, 0, 2),
+            (0, 1, 3),
+            (1, 1, 3),
+            (2, 1, 3),
+        ] {
+            map.insert_data(
+                &GridPosition::new_xy(x, y),
+                BasicIdentTileData::tile_new(id),
+            );
+        }
+
+        let mut frequency = AdjacencyFrequency::default();
+        let counts = frequency.analyze(&map);
+        assert_eq!(Some(&2), counts.get(&(1, 3, GridDir::DOWN)));
+        assert_eq!(Some(&2), counts.get(&(3, 3, GridDir::RIGHT)));
+        assert_eq!(Some(&1), counts.get(&(1, 1, GridDir::LEFT)));
+        assert_eq!(None, counts.get(&(1, 2, GridDir::LEFT)));
+
+        frequency.analyze(&map);
+        assert_eq!(2, frequency.count(2, GridDir::LEFT, 1));
+        assert_eq!(2, frequency.count(3, GridDir::UP, 2));
+        assert_eq!(0, frequency.count(2, GridDir::RIGHT, 1));
+    }
+
+    #[test]
+    fn weighted_sample_shifts_output() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaChaRng;
+
+        use crate::gen::collapse::singular::{CollapsibleTileGrid, Resolver};
+        use crate::gen::collapse::CollapsibleGrid;
+        use crate::tile::identifiable::IdentifiableTileData;
+
+        // Samples filled mostly with one tile type, containing single tile of the other.
+        let sample = |main: u64| {
+            let size = GridSize::new_xy(4, 4);
+            let mut map = GridMap2D::new(size);
+            for pos in size.get_all_possible_positions() {
+                map.insert_data(&pos, BasicIdentTileData::tile_new(main));
+            }
+            map.insert_data(
+                &GridPosition::new_xy(0, 0),
+                BasicIdentTileData::tile_new(1 - main),
+            );
+            map
+        };
+
+        let ratio_of_ones = |weights: [u32; 2]| {
+            let mut analyzer = IdentityAnalyzer::default();
+            analyzer.analyze_weighted(&sample(0), weights[0]);
+            analyzer.analyze_weighted(&sample(1), weights[1]);
+
+            let size = GridSize::new_xy(10, 10);
+            let mut grid =
+                CollapsibleTileGrid::new_empty(size, analyzer.frequency(), analyzer.adjacency());
+            Resolver::default()
+                .generate_entrophy(
+                    &mut grid,
+                    &mut ChaChaRng::seed_from_u64(4),
+                    &size.get_all_possible_positions(),
+                )
+                .unwrap();
+            let collapsed = grid.retrieve_collapsed();
+            let ones = collapsed
+                .as_ref()
+                .iter_tiles()
+                .filter(|tile| tile.as_ref().tile_type_id() == 1)
+                .count();
+            ones as f32 / 100.
+        };
+
+        let mut analyzer = IdentityAnalyzer::default();
+        analyzer.analyze_weighted(&sample(1), 5);
+        analyzer.analyze_weighted(&sample(0), 0);
+        let weights = analyzer.frequency().get_all_weights_cloned();
+        assert_eq!(Some(&75), weights.get(&1));
+        assert_eq!(Some(&5), weights.get(&0));
+
+        let equal = ratio_of_ones([1, 1]);
+        let weighted = ratio_of_ones([1, 5]);
+        assert!(weighted > 0.6, "weighted ratio: {weighted}");
+        assert!(
+            weighted > equal + 0.2,
+            "weighted: {weighted}, equal: {equal}"
+        );
+    }
+
+    #[test]
+    fn symmetry_group_rotates_rules() {
+        let group = TileSymmetryGroup::new([0, 1, 2, 3]);
+        let mut rules = rules_from(&[
+            (0, GridDir::UP, 10),
+            (0, GridDir::RIGHT, 0),
+            (0, GridDir::LEFT, 3),
+            (0, GridDir::DOWN, 10),
+            (10, GridDir::LEFT, 0),
+        ]);
+        let base = rules.clone();
+        rules.derive_symmetry_groups(&[group]);
+
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 10));
+        assert!(rules.is_valid_at_dir(1, GridDir::DOWN, 1));
+        assert!(rules.is_valid_at_dir(10, GridDir::UP, 1));
+        assert!(!rules.is_valid_at_dir(1, GridDir::UP, 10));
+
+        let rotated = |id: u64, times: usize| if id < 4 { (id + times as u64) % 4 } else { id };
+        let rotated_dir = |direction: GridDir, times: usize| {
+            let clockwise = [GridDir::UP, GridDir::RIGHT, GridDir::DOWN, GridDir::LEFT];
+            let idx = clockwise.iter().position(|dir| *dir == direction).unwrap();
+            clockwise[(idx + times) % 4]
+        };
+        for times in 0..4 {
+            for direction in GridDir::ALL_2D {
+                for adjacent_id in [0, 1, 2, 3, 10] {
+                    assert_eq!(
+                        base.is_valid_at_dir(0, *direction, adjacent_id),
+                        rules.is_valid_at_dir(
+                            rotated(0, times),
+                            rotated_dir(*direction, times),
+                            rotated(adjacent_id, times)
+                        ),
+                        "rotation {times}, {direction:?}, {adjacent_id}"
+                    );
+                }
+            }
+        }
+
+        let mut frequency = FrequencyHints::<BasicIdentTileData>::default();
+        frequency.set_weight_for_tile(
+            &GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(0)),
+            6,
+        );
+        frequency.set_weight_for_tile(
+            &GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(2)),
+            2,
+        );
+        let group_weights = |frequency: &FrequencyHints<BasicIdentTileData>| {
+            group
+                .rotations()
+                .iter()
+                .map(|id| frequency.get_all_weights_cloned()[id])
+                .collect::<Vec<_>>()
+        };
+        frequency.share_symmetry_group(&group);
+        assert_eq!(vec![2; 4], group_weights(&frequency));
+
+        frequency.set_weight_for_tile(
+            &GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1)),
+            3,
+        );
+        // Total weight of 9 is not divisible by 4.
+        frequency.share_symmetry_group(&group);
+        assert_eq!(vec![3, 2, 2, 2], group_weights(&frequency));
+    }
 }
@@ -12,15 +12,18 @@
 //!
 //! - [`AdjacencyRules`] and [`FrequencyHints`] are self-descriptive. The latter are not produced by the *analyzer*, but the method
 //! for their derivation from the sample gridmap is exposed..
-//! - [`Analyzer`] is a trait implemented by two distincts analyzers. The [`IdentityAnalyzer`] in general produced more restrictive rules,
+//! - [`AdjacencyFrequency`] gathers the statistics of observed tile adjacencies in the sample gridmaps, useful for tuning the rules.
+//! - [`Analyzer`] is a trait implemented by three distinct analyzers. The [`IdentityAnalyzer`] in general produced more restrictive rules,
 //! as it search for exact neigbours on the sample gridmap. The [`BorderAnalyzer`] is more liberal, as it takes an extra step and derives
 //! more rules based on the distinct tile borders, making additional options available if they *could be* placed on the sample gridmap
-//! next to each other.
+//! next to each other. The [`CornerAnalyzer`] derives the rules from matching colors of tile corners, as in *Wang* tilesets.
 //! - [`CollapsibleTileGrid`] is the collection of [`CollapsibleTile`].
 //! - [`Resolver`] is the main executor of the algorithm.
+//! - [`RegionedResolver`] collapses the grid divided into regions, each with its own rulesets.
 
 mod analyzer;
+mod regioned;
 mod resolver;
 mod tile;
 
-pub use {analyzer::*, resolver::*, tile::*};
+pub use {analyzer::*, regioned::*, resolver::*, tile::*};
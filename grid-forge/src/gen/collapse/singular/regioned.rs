@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::gen::collapse::error::{CollapseError, CollapsibleGridError};
+use crate::gen::GridRng;
+use crate::map::{GridDir, GridSize};
+use crate::tile::identifiable::IdentifiableTileData;
+use crate::tile::GridPosition;
+
+use super::{AdjacencyRules, CollapsibleTileGrid, FrequencyHints, Resolver};
+
+/// Resolver collapsing the grid divided into regions, each using its own rulesets - eg. for generating different
+/// biomes within one map.
+///
+/// Tiles at every position are restricted to the ones present in the [`FrequencyHints`] of its region, weighted
+/// accordingly. Adjacencies of all regions are merged, alongside the *seam rules* describing which tiles of
+/// different regions can be placed next to each other on their boundaries. As the rules are merged, tiles present
+/// in multiple regions share their adjacencies - so the seam rules need to allow them on the boundaries of these
+/// regions too, or [`build_grid`](Self::build_grid) will return an error.
+///
+/// Positions without assigned region, or with region without rulesets, can be collapsed into any tile.
+pub struct RegionedResolver<Data>
+where
+    Data: IdentifiableTileData,
+{
+    regions: HashMap<GridPosition, u32>,
+    rulesets: BTreeMap<u32, (FrequencyHints<Data>, AdjacencyRules<Data>)>,
+    seams: AdjacencyRules<Data>,
+    resolver: Resolver<Data>,
+}
+
+impl<Data> RegionedResolver<Data>
+where
+    Data: IdentifiableTileData,
+{
+    /// Creates new resolver with `regions` assigning region identifier to the positions.
+    pub fn new(regions: HashMap<GridPosition, u32>) -> Self {
+        Self {
+            regions,
+            rulesets: BTreeMap::new(),
+            seams: AdjacencyRules::default(),
+            resolver: Resolver::default(),
+        }
+    }
+
+    /// Sets the rulesets used by the region of given `region_id`.
+    pub fn with_region_rules(
+        mut self,
+        region_id: u32,
+        frequencies: &FrequencyHints<Data>,
+        adjacencies: &AdjacencyRules<Data>,
+    ) -> Self {
+        self.rulesets
+            .insert(region_id, (frequencies.clone(), adjacencies.clone()));
+        self
+    }
+
+    /// Sets the adjacency rules for tiles of different regions, required for them to be placed next to each other
+    /// on the region boundaries.
+    pub fn with_seam_rules(mut self, seams: &AdjacencyRules<Data>) -> Self {
+        self.seams = seams.clone();
+        self
+    }
+
+    /// Sets the inner [`Resolver`] used for collapsing, eg. to attach subscriber or observers.
+    pub fn with_resolver(mut self, resolver: Resolver<Data>) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Retrieves the inner [`Resolver`].
+    pub fn resolver_mut(&mut self) -> &mut Resolver<Data> {
+        &mut self.resolver
+    }
+
+    /// Creates new [`CollapsibleTileGrid`] of given `size` with merged rulesets of all regions, and options at every
+    /// position restricted to the ones of its region.
+    ///
+    /// Adjacencies of each region are limited to its own tiles, so the tiles of different regions can be placed next to
+    /// each other only according to the seam rules. Method returns an error with the first position on the region
+    /// boundary at which the regions share any tiles allowed by their rules to be placed next to each other, but not
+    /// by the seam rules.
+    pub fn build_grid(
+        &self,
+        size: GridSize,
+    ) -> Result<CollapsibleTileGrid<Data>, CollapsibleGridError> {
+        let mut merged_weights = BTreeMap::<u64, u32>::new();
+        let mut adjacencies = self.seams.clone();
+        for (region_frequencies, region_adjacencies) in self.rulesets.values() {
+            let region_weights = region_frequencies.get_all_weights_cloned();
+            adjacencies = adjacencies.union(&region_adjacencies.filtered(
+                |tile_type_id, _, adjacent_id| {
+                    region_weights.contains_key(&tile_type_id)
+                        && region_weights.contains_key(&adjacent_id)
+                },
+            ));
+            for (tile_type_id, weight) in region_weights {
+                let merged = merged_weights.entry(tile_type_id).or_default();
+                *merged = weight.max(*merged);
+            }
+        }
+        self.check_seams(size, &adjacencies)?;
+
+        let mut frequencies = FrequencyHints::default();
+        for (tile_type_id, weight) in merged_weights.iter() {
+            frequencies.set_weight_raw(*tile_type_id, *weight);
+        }
+
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequencies, &adjacencies);
+        for position in size.get_all_possible_positions() {
+            let Some((region_frequencies, _)) = self.region_rules_at(&position) else {
+                continue;
+            };
+            let region_weights = region_frequencies.get_all_weights_cloned();
+            for (tile_type_id, merged) in merged_weights.iter() {
+                match region_weights.get(tile_type_id) {
+                    Some(weight) if weight == merged => {}
+                    Some(weight) => grid.set_weight_multiplier(
+                        &position,
+                        *tile_type_id,
+                        *weight as f32 / *merged as f32,
+                    )?,
+                    None => grid.ban_option(&position, *tile_type_id)?,
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Collapses all positions within the `grid` built with [`build_grid`](Self::build_grid).
+    pub fn generate<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        let positions = grid.grid.size().get_all_possible_positions();
+        self.resolver.generate_entrophy(grid, rng, &positions)
+    }
+
+    fn region_rules_at(
+        &self,
+        position: &GridPosition,
+    ) -> Option<&(FrequencyHints<Data>, AdjacencyRules<Data>)> {
+        self.regions
+            .get(position)
+            .and_then(|region_id| self.rulesets.get(region_id))
+    }
+
+    /// Checks that the tiles of neighbouring regions can be placed next to each other only according to the seam
+    /// rules, while using the `merged` adjacencies.
+    fn check_seams(
+        &self,
+        size: GridSize,
+        merged: &AdjacencyRules<Data>,
+    ) -> Result<(), CollapsibleGridError> {
+        let mut checked = HashSet::new();
+        for position in size.get_all_possible_positions() {
+            let Some(region_id) = self.regions.get(&position) else {
+                continue;
+            };
+            for direction in GridDir::PRIMARY_2D {
+                let Some(neighbour_region_id) = direction
+                    .march_step(&position, &size)
+                    .and_then(|neighbour| self.regions.get(&neighbour))
+                else {
+                    continue;
+                };
+                if region_id == neighbour_region_id
+                    || !checked.insert((*region_id, *direction, *neighbour_region_id))
+                {
+                    continue;
+                }
+                let (Some((frequencies, _)), Some((neighbour_frequencies, _))) = (
+                    self.rulesets.get(region_id),
+                    self.rulesets.get(neighbour_region_id),
+                ) else {
+                    continue;
+                };
+                let neighbour_weights = neighbour_frequencies.get_all_weights_cloned();
+                let leaking = frequencies
+                    .get_all_weights_cloned()
+                    .keys()
+                    .any(|tile_type_id| {
+                        neighbour_weights.keys().any(|adjacent_id| {
+                            merged.is_valid_at_dir(*tile_type_id, *direction, *adjacent_id)
+                                && !self.seams.is_valid_at_dir(
+                                    *tile_type_id,
+                                    *direction,
+                                    *adjacent_id,
+                                )
+                        })
+                    });
+                if leaking {
+                    return Err(CollapsibleGridError::new_seam(position));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::gen::collapse::singular::tile::test::rules_without;
+    use crate::gen::collapse::singular::{AdjacencyRules, FrequencyHints};
+    use crate::gen::collapse::CollapsibleGrid;
+    use crate::map::{GridDir, GridSize};
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::{GridPosition, TileContainer};
+
+    use super::RegionedResolver;
+
+    /// Rulesets where only the provided tiles are possible, with all the tiles allowed to be next to each other.
+    fn region_of(
+        tile_type_ids: &[u64],
+    ) -> (
+        FrequencyHints<BasicIdentTileData>,
+        AdjacencyRules<BasicIdentTileData>,
+    ) {
+        let (mut frequency, adjacency) = rules_without(4, 10, |_, _, _| false);
+        for tile_type_id in 0..4 {
+            if !tile_type_ids.contains(&tile_type_id) {
+                frequency.exclude(tile_type_id);
+            }
+        }
+        (frequency, adjacency)
+    }
+
+    /// Divides the grid into two regions, with the boundary between the third and fourth column.
+    fn halves(size: GridSize) -> HashMap<GridPosition, u32> {
+        size.get_all_possible_positions()
+            .into_iter()
+            .map(|pos| (pos, (*pos.x() >= 3) as u32))
+            .collect()
+    }
+
+    #[test]
+    fn regions_meet_at_valid_seam() {
+        let size = GridSize::new_xy(6, 4);
+        let (desert_frequency, desert_adjacency) = region_of(&[0, 1]);
+        let (forest_frequency, forest_adjacency) = region_of(&[2, 3]);
+        // Only the dunes can meet the grass.
+        let (_, seams) = rules_without(4, 1, |tile, dir, other| {
+            (tile, dir, other) != (1, GridDir::RIGHT, 2)
+                && (tile, dir, other) != (2, GridDir::LEFT, 1)
+        });
+
+        let mut resolver = RegionedResolver::new(halves(size))
+            .with_region_rules(0, &desert_frequency, &desert_adjacency)
+            .with_region_rules(1, &forest_frequency, &forest_adjacency)
+            .with_seam_rules(&seams);
+        let mut grid = resolver.build_grid(size).unwrap();
+        resolver
+            .generate(&mut grid, &mut ChaChaRng::seed_from_u64(7))
+            .unwrap();
+        let collapsed = grid.retrieve_collapsed();
+
+        let id_at = |x: u32, y: u32| {
+            collapsed
+                .as_ref()
+                .get_tile_at_position(&GridPosition::new_xy(x, y))
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        };
+        for tile in collapsed.as_ref().iter_tiles() {
+            let expected: &[u64] = if *tile.grid_position().x() < 3 {
+                &[0, 1]
+            } else {
+                &[2, 3]
+            };
+            assert!(expected.contains(&tile.as_ref().tile_type_id()));
+        }
+        for y in 0..size.y() {
+            assert_eq!((1, 2), (id_at(2, y), id_at(3, y)));
+        }
+        assert_eq!(
+            size.max_tile_count(),
+            collapsed.as_ref().get_all_positions().len()
+        );
+    }
+
+    #[test]
+    fn shared_tiles_without_seam_rejected() {
+        let size = GridSize::new_xy(6, 4);
+        let (desert_frequency, desert_adjacency) = region_of(&[0, 1]);
+        let (forest_frequency, forest_adjacency) = region_of(&[1, 2]);
+        // Allows the shared tile to meet itself across the boundary, alongside its region neighbours.
+        let (_, seams) = rules_without(4, 1, |tile, _, other| tile == 3 || other == 3);
+
+        let resolver = RegionedResolver::new(halves(size))
+            .with_region_rules(0, &desert_frequency, &desert_adjacency)
+            .with_region_rules(1, &forest_frequency, &forest_adjacency);
+        let err = resolver.build_grid(size).err().unwrap();
+        assert_eq!(Some(GridPosition::new_xy(2, 0)), err.position());
+
+        let resolver = resolver.with_seam_rules(&seams);
+        assert!(resolver.build_grid(size).is_ok());
+    }
+}
@@ -1,21 +1,32 @@
 use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 
 use crate::gen::collapse::grid::private::Sealed;
 use crate::gen::collapse::grid::CollapsibleGrid;
 use crate::gen::collapse::{
     CollapsibleTileData, EntrophyQueue, PositionQueue, PropagateItem, Propagator,
 };
+use crate::map::GridDir;
 use crate::tile::identifiable::collection::IdentTileCollection;
 use crate::tile::identifiable::IdentifiableTileData;
-use crate::tile::GridPosition;
+use crate::tile::{GridPosition, TileContainer};
 
 use crate::gen::collapse::error::{CollapseError, CollapseErrorKind};
 use crate::gen::collapse::queue::CollapseQueue;
+use crate::gen::GridRng;
 
-use super::{CollapsibleTile, CollapsibleTileGrid};
+use super::{AdjacencyFrequency, CollapsibleTile, CollapsibleTileGrid};
 
-use rand::Rng;
+/// Number of collapses between elapsed time checks in [`Resolver::generate_entrophy_timeout`].
+const TIMEOUT_CHECK_INTERVAL: u32 = 64;
+
+/// Distance from the collapsed tile, up to which its options are tentatively propagated by the lookahead enabled with
+/// [`Resolver::with_lookahead`].
+const LOOKAHEAD_RADIUS: u32 = 3;
 
 /// Resolver of the singular collapsible procedural algorithm.
 ///
@@ -26,6 +37,11 @@ where
     Data: IdentifiableTileData,
 {
     subscriber: Option<Box<dyn Subscriber>>,
+    observers: Vec<Box<dyn CollapseObserver>>,
+    entrophy_band: f32,
+    temperature: f32,
+    void_probability: f32,
+    lookahead: bool,
     tile_type: PhantomData<Data>,
 }
 
@@ -36,6 +52,11 @@ where
     fn default() -> Self {
         Self {
             subscriber: None,
+            observers: Vec::new(),
+            entrophy_band: 0.,
+            temperature: 1.,
+            void_probability: 0.,
+            lookahead: false,
             tile_type: PhantomData,
         }
     }
@@ -56,6 +77,62 @@ where
         self.subscriber.take()
     }
 
+    /// Attach an observer to the resolver. Any number of observers can be attached, and all of them will be notified
+    /// about the events of the generation process.
+    pub fn with_observer(mut self, observer: Box<dyn CollapseObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Retrieve all observers attached to the resolver.
+    pub fn retrieve_observers(&mut self) -> Vec<Box<dyn CollapseObserver>> {
+        std::mem::take(&mut self.observers)
+    }
+
+    /// Sets the entrophy band of the [`EntrophyQueue`] used by the `generate_entrophy*` methods. Instead of always
+    /// collapsing the tile with the lowest entrophy, the next tile is chosen at random among the tiles with entrophy
+    /// at most `band` greater than the lowest, producing more organic output. Defaults to `0`.
+    ///
+    /// See [`EntrophyQueue::with_band`], which also describes the handling of invalid bands.
+    pub fn with_entrophy_band(mut self, band: f32) -> Self {
+        self.entrophy_band = band;
+        self
+    }
+
+    /// Sets the temperature of the weighted choice during the collapse in the `generate_entrophy*` methods. Weights of
+    /// the options, after applying any multipliers, are reshaped into `weight^(1 / temperature)` before sampling.
+    ///
+    /// Temperature of `1` leaves the weights unchanged, which is the default. Temperatures near `0` make the choice
+    /// nearly greedy, always picking the option with the highest weight, while large temperatures make it close to
+    /// uniform among valid options. Negative temperatures are treated as `0`.
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature.max(0.);
+        self
+    }
+
+    /// Sets the probability of every position provided to the `generate_entrophy*` methods, including
+    /// [`generate_entrophy_relaxed`](Self::generate_entrophy_relaxed), to be left permanently empty. Empty positions are excluded from the generation before the collapse begins, so they don't constrain
+    /// their neighbours in any way. Defaults to `0`, the value is clamped to `0..=1`.
+    ///
+    /// Unlike the *void* tile set by [`CollapsibleTileGrid::with_void_tile_id`], empty positions are not subject to
+    /// any adjacency rules.
+    pub fn with_void_probability(mut self, probability: f32) -> Self {
+        self.void_probability = probability.clamp(0., 1.);
+        self
+    }
+
+    /// Enables the one-ply lookahead during the collapse in the `generate_entrophy*` methods, including
+    /// [`generate_entrophy_relaxed`](Self::generate_entrophy_relaxed). Before collapsing the tile, each of its options is checked against the options remaining for the nearby tiles: options which would
+    /// leave any direct neighbour, or any neighbour of these, without compatible options are rejected, as long as some
+    /// other option doesn't.
+    ///
+    /// It makes the contradictions less frequent at the cost of slower collapse, though it can't rule them out, as
+    /// only the tiles at most two steps away are checked. Disabled by default.
+    pub fn with_lookahead(mut self, lookahead: bool) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
     /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`].
     ///
     /// Contrary to [`generate_position`](Self::generate_position), this method don't require providing the precreated
@@ -64,7 +141,7 @@ where
     /// # Arguments
     /// * `grid` - [`CollapsibleTileGrid`] to be processed. All non-collapsed tiles provided within will be
     /// removed on the beginning of the process.
-    /// * `rng` - [`Rng`] to be used for randomness.
+    /// * `rng` - [`GridRng`] to be used for randomness.
     /// * `positions` - [`GridPosition`]s to be collapsed. If any collapsed tile is present inside the provided `grid`
     /// at one of the positions provided, the tile will be overwritten with uncollapsed one.
     ///
@@ -77,35 +154,354 @@ where
         positions: &[GridPosition],
     ) -> Result<(), CollapseError>
     where
-        R: Rng,
+        R: GridRng,
+    {
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy(grid, rng, positions, EntrophyCollapse::default())
+        })
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], sending every collapsed tile through the `tx`
+    /// channel as soon as it is collapsed. Useful for generating on a background thread while incrementally
+    /// processing the tiles on the receiving one.
+    ///
+    /// Sending stops silently if the receiver is dropped, while the generation continues. The `tx` is dropped on
+    /// return, so the receiver gets disconnected if it was the last sender.
+    ///
+    /// Arguments are the same as in [`generate_entrophy`](Self::generate_entrophy).
+    pub fn generate_streaming<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        tx: Sender<(GridPosition, u64)>,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        self.observers.push(Box::new(ChannelObserver { tx }));
+        let result = self.generate_entrophy(grid, rng, positions);
+        self.observers.pop();
+        result
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], with randomness split between two sources:
+    /// `order_rng` used for the decisions about the order in which positions are collapsed, and `choice_rng` used for
+    /// choosing the option at each collapse. Useful for varying one of them while keeping the other fixed.
+    ///
+    /// As the next position is chosen by its entrophy, the order still depends on the options removed after the
+    /// previous collapses - it stays the same for different `choice_rng` only if the choices don't affect the
+    /// entrophy of the remaining tiles.
+    ///
+    /// Other arguments are the same as in [`generate_entrophy`](Self::generate_entrophy).
+    pub fn generate_entrophy_split<O, C>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        order_rng: &mut O,
+        choice_rng: &mut C,
+        positions: &[GridPosition],
+    ) -> Result<(), CollapseError>
+    where
+        O: GridRng,
+        C: GridRng,
+    {
+        let mut rng = SplitRng {
+            order: order_rng,
+            choice: choice_rng,
+        };
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy(grid, &mut rng, positions, EntrophyCollapse::default())
+        })
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], giving up after the `timeout` elapses.
+    ///
+    /// Elapsed time is checked every few collapses. If the `timeout` is exceeded, [`CollapseError`] for which
+    /// [`CollapseError::is_timeout()`] returns `true` is returned. Tiles collapsed before the timeout are left in the
+    /// `grid` and can be retrieved as usual.
+    pub fn generate_entrophy_timeout<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        timeout: Duration,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        let settings = EntrophyCollapse {
+            deadline: Some(Instant::now() + timeout),
+            ..Default::default()
+        };
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy(grid, rng, positions, settings)
+        })
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], producing output symmetric about the vertical axis.
+    ///
+    /// Whenever the tile at `(x, y)` is collapsed, the tile at `(size.x - 1 - x, y)` is forced into the mirrored tile
+    /// type and both are propagated. The `mirror` maps the `tile_type_id` into the `tile_type_id` of its mirror image,
+    /// which is needed for directional tiles - tiles not present in the mapping are their own mirrors. Tiles on the
+    /// mirror axis can only be collapsed into the tiles which are their own mirrors, and tiles next to it only into the
+    /// tiles which can neighbour their own mirror images.
+    ///
+    /// Provided *adjacency rules* should be consistent with the `mirror` mapping, otherwise contradictions can occur.
+    pub fn generate_entrophy_mirrored<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        mirror: &HashMap<u64, u64>,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        let settings = EntrophyCollapse {
+            mirror: Some(mirror),
+            ..Default::default()
+        };
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy(grid, rng, positions, settings)
+        })
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], weighting the options by their compatibility with
+    /// already collapsed neighbours.
+    ///
+    /// At each collapse, the weight of every possible option is multiplied by the sum of the times it was observed
+    /// next to the collapsed neighbours in their directions, as gathered in provided [`AdjacencyFrequency`]. Tiles
+    /// without collapsed neighbours, or without any observed adjacency with them, are collapsed using the plain
+    /// weights.
+    pub fn generate_entrophy_adjacency_weighted<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        adjacency: &AdjacencyFrequency<Data>,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        let settings = EntrophyCollapse {
+            adjacency: Some(adjacency),
+            ..Default::default()
+        };
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy(grid, rng, positions, settings)
+        })
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], placing the least-bad option instead of failing on
+    /// contradictions.
+    ///
+    /// When a tile is left without any valid option, it is collapsed into the option compatible with the most of its
+    /// collapsed neighbours, preferring the one with higher weight on ties, and the generation continues. Returns all
+    /// pairs of neighbouring tiles violating the adjacency rules in which any of the `positions` takes part, so the
+    /// empty vector means that the output is fully valid.
+    ///
+    /// Apart from that, the collapse is the same as in [`generate_entrophy`](Self::generate_entrophy), including all
+    /// the settings of the resolver.
+    pub fn generate_entrophy_relaxed<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+    ) -> Vec<AdjacencyViolation>
+    where
+        R: GridRng,
+    {
+        let settings = EntrophyCollapse {
+            relaxed: true,
+            ..Default::default()
+        };
+        self.notified(positions.len(), |resolver| {
+            resolver
+                .collapse_entrophy(grid, rng, positions, settings)
+                .expect("relaxed collapse should not fail");
+        });
+        Self::adjacency_violations(grid, positions)
+    }
+
+    /// Collapse the [`CollapsibleTileGrid`] using [`EntrophyQueue`], recovering from contradictions by localized
+    /// backtracking instead of failing the whole process.
+    ///
+    /// On contradiction, all tiles from `positions` within the square of given radius around the failed position are
+    /// reset to uncollapsed state and collapsed again, with constraints re-derived from the surrounding collapsed
+    /// tiles. If the next contradiction occurs within the just reset region, the radius is escalated up to the cap set
+    /// in [`RegionBacktracking`]. Each reset is reported to the [`Subscriber::on_region_reset`].
+    ///
+    /// Returns the last [`CollapseError`] if the radius cap or maximum number of resets is exceeded, or if the error is
+    /// not [probabilistic](CollapseError::is_probabilistic), eg. caused by the incompatible pre-collapsed tiles.
+    pub fn generate_entrophy_backtracking<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        backtracking: RegionBacktracking,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_entrophy_backtracking(grid, rng, positions, backtracking)
+        })
+    }
+
+    fn collapse_entrophy_backtracking<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        backtracking: RegionBacktracking,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        let mut last_reset: Option<(GridPosition, u32)> = None;
+        let mut resets = 0;
+        let to_reset = positions.iter().copied().collect::<HashSet<_>>();
+
+        loop {
+            let to_collapse = positions
+                .iter()
+                .filter(|pos| {
+                    !grid
+                        .grid
+                        .get_tile_at_position(pos)
+                        .is_some_and(|tile| tile.as_ref().is_collapsed())
+                })
+                .copied()
+                .collect::<Vec<_>>();
+
+            let Err(err) =
+                self.collapse_entrophy(grid, rng, &to_collapse, EntrophyCollapse::default())
+            else {
+                return Ok(());
+            };
+
+            // Only contradictions reached while collapsing can be solved by collapsing the region again.
+            if !err.is_probabilistic() || resets >= backtracking.max_resets {
+                return Err(err);
+            }
+
+            let failed_pos = err.failed_pos();
+            let radius = match last_reset {
+                Some((center, radius)) if failed_pos.in_range(&center, radius) => {
+                    if radius >= backtracking.max_radius {
+                        return Err(err);
+                    }
+                    radius + 1
+                }
+                _ => backtracking.radius,
+            };
+
+            let (x, y) = failed_pos.xy();
+            let from = GridPosition::new_xy(x.saturating_sub(radius), y.saturating_sub(radius));
+            let to = GridPosition::new_xy(x.saturating_add(radius), y.saturating_add(radius));
+            // Tiles not passed to collapse are fixed, so they are put back.
+            for tile in grid.grid.drain_region(&from, &to) {
+                if !to_reset.contains(&tile.grid_position()) {
+                    grid.grid.insert_tile(tile);
+                }
+            }
+
+            if let Some(subscriber) = self.subscriber.as_mut() {
+                subscriber.on_region_reset(&failed_pos, radius);
+            }
+
+            last_reset = Some((failed_pos, radius));
+            resets += 1;
+        }
+    }
+
+    fn collapse_entrophy<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        settings: EntrophyCollapse<Data>,
+    ) -> Result<(), CollapseError>
+    where
+        R: CollapseRng,
     {
         use crate::gen::collapse::queue::private::Sealed as _;
         use crate::gen::collapse::tile::private::Sealed as _;
 
+        let EntrophyCollapse {
+            deadline,
+            mirror,
+            adjacency,
+            relaxed,
+        } = settings;
         let mut iter = 0;
-        let mut queue = EntrophyQueue::default();
+        let mut queue = EntrophyQueue::with_band(self.entrophy_band);
         let mut propagator = Propagator::default();
-
-        if let Some(subscriber) = self.subscriber.as_mut() {
-            subscriber.on_generation_start();
+        if relaxed {
+            propagator.relax();
+        }
+        if !self.observers.is_empty() {
+            propagator.track_removed();
         }
 
         grid.remove_uncollapsed();
 
-        queue.populate_inner_grid(rng, &mut grid.grid, positions, &grid.option_data);
+        let non_void;
+        let positions = if self.void_probability > 0. {
+            non_void = positions
+                .iter()
+                .filter(|position| {
+                    if !rng.order().gen_bool(self.void_probability as f64) {
+                        return true;
+                    }
+                    grid.grid.remove_tile_at_position(position);
+                    false
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            &non_void
+        } else {
+            positions
+        };
 
+        queue.populate_inner_grid(rng.order(), &mut grid.grid, positions, &grid.option_data);
+
+        let banned = grid.apply_bans(&mut queue, relaxed);
+        for item in CollapseError::from_result(banned, CollapseErrorKind::Init, iter)? {
+            propagator.push_propagate(item);
+        }
         for initial_propagate in grid._get_initial_propagate_items(positions) {
             propagator.push_propagate(initial_propagate);
         }
 
-        CollapseError::from_result(
-            propagator.propagate(&mut grid.grid, &grid.option_data, &mut queue),
-            CollapseErrorKind::Init,
-            iter,
-        )?;
+        let result = propagator.propagate(&mut grid.grid, &grid.option_data, &mut queue);
+        self.notify_propagated(&mut propagator, grid);
+        CollapseError::from_result(result, CollapseErrorKind::Init, iter)?;
+
+        let size = *grid.grid.size();
 
         // Progress with collapse.
-        while let Some(collapse_position) = queue.get_next_position() {
+        while let Some(collapse_position) = queue.get_next_position_banded(rng.order()) {
+            let mirror_position =
+                GridPosition::new_xy(size.x() - 1 - collapse_position.x(), *collapse_position.y());
+            let axis_multipliers = mirror.and_then(|mirror| {
+                Self::mirror_multipliers(grid, &collapse_position, &mirror_position, mirror)
+            });
+            let adjacency_multipliers = adjacency.and_then(|adjacency| {
+                Self::adjacency_multipliers(grid, &collapse_position, adjacency)
+            });
+            let multipliers = axis_multipliers
+                .as_ref()
+                .or(adjacency_multipliers.as_ref())
+                .or_else(|| grid.weight_multipliers.get(&collapse_position));
+            let lookahead_multipliers = self
+                .lookahead
+                .then(|| Self::lookahead_multipliers(grid, &collapse_position, multipliers))
+                .flatten();
+            let multipliers = lookahead_multipliers.as_ref().or(multipliers);
+            let tempered_multipliers =
+                self.tempered_multipliers(grid, &collapse_position, multipliers);
+
             let mut to_collapse = grid
                 .grid
                 .get_mut_tile_at_position(&collapse_position)
@@ -114,46 +510,237 @@ where
             if to_collapse.as_ref().is_collapsed() {
                 continue;
             }
-            if !to_collapse.as_ref().has_compatible_options() {
+            if iter % TIMEOUT_CHECK_INTERVAL == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
                 return Err(CollapseError::new(
                     collapse_position,
-                    CollapseErrorKind::Collapse,
+                    CollapseErrorKind::Timeout,
                     iter,
                 ));
             }
-            let Some(removed_options) = to_collapse.as_mut().collapse(rng, &grid.option_data)
-            else {
-                return Err(CollapseError::new(
-                    collapse_position,
-                    CollapseErrorKind::Collapse,
-                    iter,
-                ));
+            let removed_options = if !to_collapse.as_ref().has_compatible_options() {
+                None
+            } else {
+                match tempered_multipliers.as_ref().or(multipliers) {
+                    Some(multipliers) => to_collapse.as_mut().collapse_biased(
+                        rng.choice(),
+                        &grid.option_data,
+                        multipliers,
+                    ),
+                    None => to_collapse
+                        .as_mut()
+                        .collapse(rng.choice(), &grid.option_data),
+                }
             };
-            let collapsed_idx = to_collapse.as_ref().collapse_idx().unwrap();
-            if let Some(subscriber) = self.subscriber.as_mut() {
-                let collapsed_id = grid
-                    ._option_data()
-                    .get_tile_type_id(&collapsed_idx)
-                    .unwrap();
-                subscriber
-                    .as_mut()
-                    .on_collapse(&collapse_position, collapsed_id);
-            }
+            let removed_options = match removed_options {
+                Some(removed_options) => removed_options,
+                // Relaxed collapse places the least violating option instead of failing.
+                None if relaxed => {
+                    let option_idx = Self::least_violating_option(grid, &collapse_position);
+                    grid.grid
+                        .get_mut_tile_at_position(&collapse_position)
+                        .unwrap()
+                        .as_mut()
+                        .mark_collapsed(option_idx);
+                    Vec::new()
+                }
+                None => {
+                    return Err(CollapseError::new(
+                        collapse_position,
+                        CollapseErrorKind::Collapse,
+                        iter,
+                    ))
+                }
+            };
+            let collapsed_idx = grid
+                .grid
+                .get_tile_at_position(&collapse_position)
+                .and_then(|tile| tile.as_ref().collapse_idx())
+                .unwrap();
+            self.notify_collapse(&collapse_position, collapsed_idx, grid);
             for removed_option in removed_options.into_iter() {
                 propagator.push_propagate(PropagateItem::new(collapse_position, removed_option))
             }
-            CollapseError::from_result(
-                propagator.propagate(&mut grid.grid, &grid.option_data, &mut queue),
-                CollapseErrorKind::Propagation,
-                iter,
-            )?;
+            let result = propagator.propagate(&mut grid.grid, &grid.option_data, &mut queue);
+            self.notify_propagated(&mut propagator, grid);
+            CollapseError::from_result(result, CollapseErrorKind::Propagation, iter)?;
+            // Mirrored tile is collapsed only after the propagation, so its options are already restricted by the
+            // collapsed tile if they are adjacent.
+            if let Some(mirror) = mirror.filter(|_| mirror_position != collapse_position) {
+                let removed_options = CollapseError::from_result(
+                    Self::collapse_mirrored(grid, collapsed_idx, &mirror_position, mirror),
+                    CollapseErrorKind::Collapse,
+                    iter,
+                )?;
+                if let Some(removed_options) = removed_options {
+                    let mirrored_idx = grid
+                        .grid
+                        .get_tile_at_position(&mirror_position)
+                        .and_then(|tile| tile.as_ref().collapse_idx())
+                        .unwrap();
+                    self.notify_collapse(&mirror_position, mirrored_idx, grid);
+                    for removed_option in removed_options.into_iter() {
+                        propagator
+                            .push_propagate(PropagateItem::new(mirror_position, removed_option))
+                    }
+                    let result =
+                        propagator.propagate(&mut grid.grid, &grid.option_data, &mut queue);
+                    self.notify_propagated(&mut propagator, grid);
+                    CollapseError::from_result(result, CollapseErrorKind::Propagation, iter)?;
+                }
+            }
             iter += 1;
         }
 
         Ok(())
     }
 
+    /// Calculates weight multipliers for options of the tile at `position` reshaping their weights, including the
+    /// `multipliers`, according to the temperature. Returns `None` if the temperature is `1` or no option has positive
+    /// weight.
+    fn tempered_multipliers(
+        &self,
+        grid: &CollapsibleTileGrid<Data>,
+        position: &GridPosition,
+        multipliers: Option<&HashMap<u64, f32>>,
+    ) -> Option<HashMap<u64, f32>> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        if self.temperature == 1. {
+            return None;
+        }
+        let weighted = grid
+            .grid
+            .get_tile_at_position(position)?
+            .as_ref()
+            .ways_to_be_option()
+            .iter_possible()
+            .filter_map(|option_idx| {
+                let tile_type_id = grid.option_data.get_tile_type_id(&option_idx)?;
+                let weight = grid.option_data.get_weights(option_idx).0 as f32;
+                let factor = multipliers
+                    .and_then(|multipliers| multipliers.get(&tile_type_id))
+                    .copied()
+                    .unwrap_or(1.);
+                Some((tile_type_id, weight, weight * factor))
+            })
+            .collect::<Vec<_>>();
+        // Weights are normalized by the highest one, so the reshaping doesn't overflow for low temperatures.
+        let max_weight = weighted
+            .iter()
+            .map(|(_, _, weighted)| *weighted)
+            .fold(0., f32::max);
+        if max_weight <= 0. {
+            return None;
+        }
+
+        Some(
+            weighted
+                .into_iter()
+                .map(|(tile_type_id, weight, weighted)| {
+                    let tempered = (weighted / max_weight).powf(1. / self.temperature);
+                    let factor = if weight > 0. { tempered / weight } else { 0. };
+                    (tile_type_id, factor)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the option for the tile at `position` which is compatible with the most of its collapsed neighbours.
+    fn least_violating_option(grid: &CollapsibleTileGrid<Data>, position: &GridPosition) -> usize {
+        let neighbours = GridDir::ALL_2D
+            .iter()
+            .filter_map(|direction| {
+                let neighbour = grid.grid.get_neighbour_at(position, direction)?;
+                Some((*direction, neighbour.as_ref().collapse_idx()?))
+            })
+            .collect::<Vec<_>>();
+
+        grid.option_data
+            .get_ways_to_become_option()
+            .iter_possible()
+            .max_by_key(|option_idx| {
+                let compatible = neighbours
+                    .iter()
+                    .filter(|(direction, neighbour_idx)| {
+                        grid.option_data
+                            .get_all_enabled_in_direction(*option_idx, *direction)
+                            .contains(neighbour_idx)
+                    })
+                    .count();
+                (compatible, grid.option_data.get_weights(*option_idx).0)
+            })
+            .expect("ruleset should contain possible options")
+    }
+
+    /// Gathers pairs of neighbouring collapsed tiles which aren't valid according to the adjacency rules, in which
+    /// any of the `positions` takes part. Each pair is reported once.
+    fn adjacency_violations(
+        grid: &CollapsibleTileGrid<Data>,
+        positions: &[GridPosition],
+    ) -> Vec<AdjacencyViolation> {
+        let generated = positions.iter().collect::<HashSet<_>>();
+        let size = grid.grid.size();
+        let collapsed_idx = |position: &GridPosition| {
+            grid.grid
+                .get_tile_at_position(position)
+                .and_then(|tile| tile.as_ref().collapse_idx())
+        };
+
+        let mut violations = Vec::new();
+        for position in positions {
+            let Some(option_idx) = collapsed_idx(position) else {
+                continue;
+            };
+            for direction in GridDir::ALL_2D {
+                let Some(neighbour_pos) = direction.march_step(position, size) else {
+                    continue;
+                };
+                // Pairs of generated tiles are checked from the lesser position only.
+                if generated.contains(&neighbour_pos) && neighbour_pos < *position {
+                    continue;
+                }
+                let Some(neighbour_idx) = collapsed_idx(&neighbour_pos) else {
+                    continue;
+                };
+                if grid
+                    .option_data
+                    .get_all_enabled_in_direction(option_idx, *direction)
+                    .contains(&neighbour_idx)
+                {
+                    continue;
+                }
+                violations.push(AdjacencyViolation {
+                    position: *position,
+                    direction: *direction,
+                    tile_type_id: grid.option_data.get_tile_type_id(&option_idx).unwrap(),
+                    neighbour_tile_type_id: grid
+                        .option_data
+                        .get_tile_type_id(&neighbour_idx)
+                        .unwrap(),
+                });
+            }
+        }
+        violations
+    }
+
     pub fn generate_position<R>(
+        &mut self,
+        grid: &mut CollapsibleTileGrid<Data>,
+        rng: &mut R,
+        positions: &[GridPosition],
+        queue: PositionQueue,
+    ) -> Result<(), CollapseError>
+    where
+        R: GridRng,
+    {
+        self.notified(positions.len(), |resolver| {
+            resolver.collapse_position(grid, rng, positions, queue)
+        })
+    }
+
+    fn collapse_position<R>(
         &mut self,
         grid: &mut CollapsibleTileGrid<Data>,
         rng: &mut R,
@@ -161,16 +748,12 @@ where
         mut queue: PositionQueue,
     ) -> Result<(), CollapseError>
     where
-        R: Rng,
+        R: GridRng,
     {
         use crate::gen::collapse::queue::private::Sealed as _;
         use crate::gen::collapse::tile::private::Sealed as _;
         let mut iter = 0;
 
-        if let Some(subscriber) = self.subscriber.as_mut() {
-            subscriber.on_generation_start();
-        }
-
         grid.remove_uncollapsed();
 
         queue.populate_inner_grid(rng, &mut grid.grid, positions, &grid.option_data);
@@ -201,7 +784,21 @@ where
                 .grid
                 .get_mut_tile_at_position(&collapse_position)
                 .unwrap();
-            to_collapse.as_mut().collapse_basic(rng, &grid.option_data);
+            if let Some(multipliers) = grid.weight_multipliers.get(&collapse_position) {
+                if to_collapse
+                    .as_mut()
+                    .collapse_biased(rng, &grid.option_data, multipliers)
+                    .is_none()
+                {
+                    return Err(CollapseError::new(
+                        collapse_position,
+                        CollapseErrorKind::Collapse,
+                        iter,
+                    ));
+                }
+            } else {
+                to_collapse.as_mut().collapse_basic(rng, &grid.option_data);
+            }
 
             let collapsed_idx = to_collapse.as_ref().collapse_idx().unwrap();
             // Purge options for the neighbours. This step is not required for the generation to be sound at the end,
@@ -213,19 +810,427 @@ where
                 &grid.option_data,
             );
 
-            if let Some(subscriber) = self.subscriber.as_mut() {
-                let collapsed_id = grid
-                    ._option_data()
-                    .get_tile_type_id(&collapsed_idx)
-                    .unwrap();
-                subscriber
-                    .as_mut()
-                    .on_collapse(&collapse_position, collapsed_id);
-            }
+            self.notify_collapse(&collapse_position, collapsed_idx, grid);
             iter += 1;
         }
         Ok(())
     }
+
+    /// Calculates weight multipliers for options of the tile at `position` out of observed adjacencies with its
+    /// collapsed neighbours, including the multipliers set for the position. Returns `None` if no possible option was
+    /// observed next to the neighbours.
+    fn adjacency_multipliers(
+        grid: &CollapsibleTileGrid<Data>,
+        position: &GridPosition,
+        adjacency: &AdjacencyFrequency<Data>,
+    ) -> Option<HashMap<u64, f32>> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let neighbours = GridDir::ALL_2D
+            .iter()
+            .filter_map(|direction| {
+                let neighbour = grid.grid.get_neighbour_at(position, direction)?;
+                let collapse_idx = neighbour.as_ref().collapse_idx()?;
+                Some((
+                    *direction,
+                    grid.option_data.get_tile_type_id(&collapse_idx)?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        if neighbours.is_empty() {
+            return None;
+        }
+
+        let position_multipliers = grid.weight_multipliers.get(position);
+        let mut multipliers = HashMap::new();
+        let mut any_observed = false;
+        for option_idx in grid
+            .grid
+            .get_tile_at_position(position)?
+            .as_ref()
+            .ways_to_be_option()
+            .iter_possible()
+        {
+            let tile_type_id = grid.option_data.get_tile_type_id(&option_idx)?;
+            let score = neighbours
+                .iter()
+                .map(|(direction, neighbour_id)| {
+                    adjacency.count(tile_type_id, *direction, *neighbour_id)
+                })
+                .sum::<u32>();
+            any_observed |= score > 0;
+            let factor = position_multipliers
+                .and_then(|multipliers| multipliers.get(&tile_type_id))
+                .copied()
+                .unwrap_or(1.);
+            multipliers.insert(tile_type_id, score as f32 * factor);
+        }
+
+        any_observed.then_some(multipliers)
+    }
+
+    /// Extends the `multipliers` for options of the tile at `position`, zeroing the options which would leave some
+    /// nearby tile without compatible options, as checked by [`keeps_nearby_possible`](Self::keeps_nearby_possible).
+    /// Returns `None` if no option is rejected this way, or none of the remaining options has positive weight.
+    fn lookahead_multipliers(
+        grid: &CollapsibleTileGrid<Data>,
+        position: &GridPosition,
+        multipliers: Option<&HashMap<u64, f32>>,
+    ) -> Option<HashMap<u64, f32>> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let mut out = multipliers.cloned().unwrap_or_default();
+        let mut any_rejected = false;
+        let mut any_viable = false;
+        for option_idx in grid
+            .grid
+            .get_tile_at_position(position)?
+            .as_ref()
+            .ways_to_be_option()
+            .iter_possible()
+        {
+            let tile_type_id = grid.option_data.get_tile_type_id(&option_idx)?;
+            if !Self::keeps_nearby_possible(grid, position, option_idx) {
+                any_rejected = true;
+                out.insert(tile_type_id, 0.);
+            } else if out.get(&tile_type_id).copied().unwrap_or(1.) > 0.
+                && grid.option_data.get_weights(option_idx).0 > 0
+            {
+                any_viable = true;
+            }
+        }
+
+        (any_rejected && any_viable).then_some(out)
+    }
+
+    /// Checks if the tile at `position` can be collapsed into `option_idx` without emptying the domain of nearby
+    /// tiles.
+    ///
+    /// The collapse is propagated tentatively on the copies of the options of the tiles at most
+    /// [`LOOKAHEAD_RADIUS`] steps away, leaving the grid unchanged. Tiles further away are not checked.
+    fn keeps_nearby_possible(
+        grid: &CollapsibleTileGrid<Data>,
+        position: &GridPosition,
+        option_idx: usize,
+    ) -> bool {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let size = grid.grid.size();
+        let mut domains = HashMap::from([(*position, vec![option_idx])]);
+        let mut to_propagate = vec![*position];
+
+        while let Some(current) = to_propagate.pop() {
+            let current_domain = domains[&current].clone();
+            for direction in GridDir::ALL_2D {
+                let Some(next) = direction
+                    .march_step(&current, size)
+                    .filter(|next| next.in_range(position, LOOKAHEAD_RADIUS))
+                else {
+                    continue;
+                };
+                let domain = match domains.entry(next) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let Some(tile) = grid.grid.get_tile_at_position(&next) else {
+                            continue;
+                        };
+                        entry.insert(match tile.as_ref().collapse_idx() {
+                            Some(collapsed_idx) => vec![collapsed_idx],
+                            None => tile.as_ref().ways_to_be_option().iter_possible().collect(),
+                        })
+                    }
+                };
+
+                let allowed = current_domain
+                    .iter()
+                    .flat_map(|current_idx| {
+                        grid.option_data
+                            .get_all_enabled_in_direction(*current_idx, *direction)
+                    })
+                    .collect::<HashSet<_>>();
+                let before = domain.len();
+                domain.retain(|next_idx| allowed.contains(next_idx));
+                if domain.is_empty() {
+                    return false;
+                }
+                if domain.len() < before {
+                    to_propagate.push(next);
+                }
+            }
+        }
+        true
+    }
+
+    /// Gathers the multipliers disabling the options at `position` which can't be collapsed together with their mirror
+    /// images at `mirror_position`.
+    ///
+    /// Tiles on the mirror axis need to be mirrors of themselves, while tiles next to the axis need to be valid
+    /// neighbours of their mirror images. Returns `None` for all other positions.
+    fn mirror_multipliers(
+        grid: &CollapsibleTileGrid<Data>,
+        position: &GridPosition,
+        mirror_position: &GridPosition,
+        mirror: &HashMap<u64, u64>,
+    ) -> Option<HashMap<u64, f32>> {
+        let size = grid.grid.size();
+        let direction = [GridDir::LEFT, GridDir::RIGHT]
+            .into_iter()
+            .find(|direction| {
+                direction.march_step(position, size).as_ref() == Some(mirror_position)
+            });
+        if position != mirror_position && direction.is_none() {
+            return None;
+        }
+
+        let mut multipliers = grid
+            .weight_multipliers
+            .get(position)
+            .cloned()
+            .unwrap_or_default();
+        for (tile_type_id, option_idx) in grid.option_data.inner() {
+            let mirrored_id = mirror.get(tile_type_id).unwrap_or(tile_type_id);
+            let valid = match direction {
+                None => mirrored_id == tile_type_id,
+                Some(direction) => {
+                    grid.option_data
+                        .get_tile_data(mirrored_id)
+                        .is_some_and(|mirrored_idx| {
+                            grid.option_data
+                                .get_all_enabled_in_direction(*option_idx, direction)
+                                .contains(mirrored_idx)
+                        })
+                }
+            };
+            if !valid {
+                multipliers.insert(*tile_type_id, 0.);
+            }
+        }
+        Some(multipliers)
+    }
+
+    /// Forces the tile at `mirror_position` into the mirror of the option collapsed at its counterpart position.
+    ///
+    /// Returns the options removed from the mirrored tile, `None` if there is no tile to collapse at the position, or
+    /// the position as an error if the mirrored option is not possible there.
+    fn collapse_mirrored(
+        grid: &mut CollapsibleTileGrid<Data>,
+        collapsed_idx: usize,
+        mirror_position: &GridPosition,
+        mirror: &HashMap<u64, u64>,
+    ) -> Result<Option<Vec<usize>>, GridPosition> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let collapsed_id = grid.option_data.get_tile_type_id(&collapsed_idx).unwrap();
+        let mirrored_id = mirror.get(&collapsed_id).copied().unwrap_or(collapsed_id);
+        let mirrored_idx = *grid
+            .option_data
+            .get_tile_data(&mirrored_id)
+            .ok_or(*mirror_position)?;
+
+        let Some(mut tile) = grid.grid.get_mut_tile_at_position(mirror_position) else {
+            return Ok(None);
+        };
+        if let Some(existing_idx) = tile.as_ref().collapse_idx() {
+            if existing_idx != mirrored_idx {
+                return Err(*mirror_position);
+            }
+            return Ok(None);
+        }
+
+        let possible = tile
+            .as_ref()
+            .ways_to_be_option()
+            .iter_possible()
+            .collect::<Vec<_>>();
+        if !possible.contains(&mirrored_idx) {
+            return Err(*mirror_position);
+        }
+        tile.as_mut().mark_collapsed(mirrored_idx);
+
+        Ok(Some(
+            possible
+                .into_iter()
+                .filter(|option_idx| *option_idx != mirrored_idx)
+                .collect(),
+        ))
+    }
+
+    /// Runs the `collapse`, notifying the subscriber and observers about the start and finish of the generation of
+    /// `total` positions.
+    fn notified<T>(&mut self, total: usize, collapse: impl FnOnce(&mut Self) -> T) -> T {
+        if let Some(subscriber) = self.subscriber.as_mut() {
+            subscriber.on_generation_start();
+        }
+        self.notify_start(total);
+        let result = collapse(self);
+        self.notify_finish();
+        result
+    }
+
+    fn notify_start(&mut self, total: usize) {
+        for observer in self.observers.iter_mut() {
+            observer.on_start(total);
+        }
+    }
+
+    fn notify_finish(&mut self) {
+        for observer in self.observers.iter_mut() {
+            observer.on_finish();
+        }
+    }
+
+    fn notify_collapse(
+        &mut self,
+        position: &GridPosition,
+        collapsed_idx: usize,
+        grid: &CollapsibleTileGrid<Data>,
+    ) {
+        if self.subscriber.is_none() && self.observers.is_empty() {
+            return;
+        }
+        let collapsed_id = grid
+            ._option_data()
+            .get_tile_type_id(&collapsed_idx)
+            .unwrap();
+        if let Some(subscriber) = self.subscriber.as_mut() {
+            subscriber.as_mut().on_collapse(position, collapsed_id);
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_collapse(position, collapsed_id);
+        }
+    }
+
+    fn notify_propagated(&mut self, propagator: &mut Propagator, grid: &CollapsibleTileGrid<Data>) {
+        for item in propagator.drain_removed() {
+            let removed_id = grid
+                ._option_data()
+                .get_tile_type_id(&item.to_remove)
+                .unwrap();
+            for observer in self.observers.iter_mut() {
+                observer.on_propagate(&item.position, removed_id);
+            }
+        }
+    }
+}
+
+/// Variation of the collapse loop shared by the `generate_entrophy*` methods of the [`Resolver`].
+struct EntrophyCollapse<'a, Data>
+where
+    Data: IdentifiableTileData,
+{
+    /// Time after which the collapse fails with timeout.
+    deadline: Option<Instant>,
+    /// Mapping of `tile_type_id`s into their mirror images, to produce the output symmetric about the vertical axis.
+    mirror: Option<&'a HashMap<u64, u64>>,
+    /// Observed adjacencies weighting the options by their compatibility with collapsed neighbours.
+    adjacency: Option<&'a AdjacencyFrequency<Data>>,
+    /// Place the least violating option instead of failing on contradictions.
+    relaxed: bool,
+}
+
+impl<Data> Default for EntrophyCollapse<'_, Data>
+where
+    Data: IdentifiableTileData,
+{
+    fn default() -> Self {
+        Self {
+            deadline: None,
+            mirror: None,
+            adjacency: None,
+            relaxed: false,
+        }
+    }
+}
+
+/// Source of randomness for the collapse, possibly split between the decisions about the order of collapsed positions
+/// and the choice of the options.
+trait CollapseRng {
+    type Order: GridRng;
+    type Choice: GridRng;
+
+    fn order(&mut self) -> &mut Self::Order;
+
+    fn choice(&mut self) -> &mut Self::Choice;
+}
+
+impl<R: GridRng> CollapseRng for R {
+    type Order = R;
+    type Choice = R;
+
+    fn order(&mut self) -> &mut R {
+        self
+    }
+
+    fn choice(&mut self) -> &mut R {
+        self
+    }
+}
+
+/// Separate sources of randomness, used by [`Resolver::generate_entrophy_split`].
+struct SplitRng<'a, O, C> {
+    order: &'a mut O,
+    choice: &'a mut C,
+}
+
+impl<O: GridRng, C: GridRng> CollapseRng for SplitRng<'_, O, C> {
+    type Order = O;
+    type Choice = C;
+
+    fn order(&mut self) -> &mut O {
+        self.order
+    }
+
+    fn choice(&mut self) -> &mut C {
+        self.choice
+    }
+}
+
+/// Pair of neighbouring tiles violating the adjacency rules, reported by [`Resolver::generate_entrophy_relaxed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdjacencyViolation {
+    /// Position of the tile.
+    pub position: GridPosition,
+    /// Direction from the tile to its neighbour.
+    pub direction: GridDir,
+    pub tile_type_id: u64,
+    pub neighbour_tile_type_id: u64,
+}
+
+/// Configuration of the localized backtracking used by
+/// [`Resolver::generate_entrophy_backtracking`].
+#[derive(Debug, Clone, Copy)]
+pub struct RegionBacktracking {
+    radius: u32,
+    max_radius: u32,
+    max_resets: u32,
+}
+
+impl Default for RegionBacktracking {
+    fn default() -> Self {
+        Self {
+            radius: 2,
+            max_radius: 8,
+            max_resets: 100,
+        }
+    }
+}
+
+impl RegionBacktracking {
+    /// Creates new configuration with the initial `radius` of the reset region, escalated on repeated failures in the
+    /// same region up to `max_radius`.
+    pub fn new(radius: u32, max_radius: u32) -> Self {
+        Self {
+            radius,
+            max_radius: max_radius.max(radius),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of region resets during single generation.
+    pub fn with_max_resets(mut self, max_resets: u32) -> Self {
+        self.max_resets = max_resets;
+        self
+    }
 }
 
 /// When applied to the struct allows injecting it into [`singular::Resolver`](Resolver) to react on each tile being collapsed.
@@ -239,10 +1244,122 @@ pub trait Subscriber: Any {
     /// Called when a tile is collapsed.
     fn on_collapse(&mut self, position: &GridPosition, tile_type_id: u64);
 
+    /// Called when the region around failed position is reset during
+    /// [`generate_entrophy_backtracking`](Resolver::generate_entrophy_backtracking). No-op by default.
+    fn on_region_reset(&mut self, _position: &GridPosition, _radius: u32) {
+        // no-op
+    }
+
     /// To retrieve the concrete subscriber type from [`singular::Resolver`](Resolver).
     fn as_any(&self) -> &dyn Any;
 }
 
+/// When applied to the struct allows attaching it to [`singular::Resolver`](Resolver) to observe the events of the
+/// generation process, eg. for integration with external visualizers.
+///
+/// Contrary to the [`Subscriber`], any number of observers can be attached to the resolver.
+pub trait CollapseObserver: Any {
+    /// Called when the generation process starts, with the `total` number of positions to collapse.
+    fn on_start(&mut self, _total: usize) {
+        // no-op
+    }
+
+    /// Called when a tile is collapsed.
+    fn on_collapse(&mut self, position: &GridPosition, tile_type_id: u64);
+
+    /// Called when the option of given `tile_type_id` is removed from the tile during propagation. Propagation is
+    /// performed only by the [`EntrophyQueue`]-based generation. No-op by default.
+    fn on_propagate(&mut self, _position: &GridPosition, _removed_tile_type_id: u64) {
+        // no-op
+    }
+
+    /// Called when the generation process ends, either successfully or with an error. No-op by default.
+    fn on_finish(&mut self) {
+        // no-op
+    }
+
+    /// To retrieve the concrete observer type from [`singular::Resolver`](Resolver).
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Simple observer counting the events of the generation process.
+///
+/// Counts are accumulated across multiple generations, while `total` is overwritten on every start.
+#[derive(Debug, Clone, Default)]
+pub struct CountingObserver {
+    total: usize,
+    collapses: usize,
+    propagations: usize,
+    starts: usize,
+    finishes: usize,
+}
+
+impl CountingObserver {
+    /// Returns the number of positions to collapse reported at the start of the last generation.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of collapsed tiles.
+    pub fn collapses(&self) -> usize {
+        self.collapses
+    }
+
+    /// Returns the number of options removed during propagation.
+    pub fn propagations(&self) -> usize {
+        self.propagations
+    }
+
+    /// Returns the number of started generations.
+    pub fn starts(&self) -> usize {
+        self.starts
+    }
+
+    /// Returns the number of finished generations.
+    pub fn finishes(&self) -> usize {
+        self.finishes
+    }
+}
+
+impl CollapseObserver for CountingObserver {
+    fn on_start(&mut self, total: usize) {
+        self.total = total;
+        self.starts += 1;
+    }
+
+    fn on_collapse(&mut self, _position: &GridPosition, _tile_type_id: u64) {
+        self.collapses += 1;
+    }
+
+    fn on_propagate(&mut self, _position: &GridPosition, _removed_tile_type_id: u64) {
+        self.propagations += 1;
+    }
+
+    fn on_finish(&mut self) {
+        self.finishes += 1;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Observer sending collapsed tiles through the channel, used by [`Resolver::generate_streaming`].
+struct ChannelObserver {
+    tx: Sender<(GridPosition, u64)>,
+}
+
+impl CollapseObserver for ChannelObserver {
+    fn on_collapse(&mut self, position: &GridPosition, tile_type_id: u64) {
+        // Receiver being dropped shouldn't stop the generation.
+        let _ = self.tx.send((*position, tile_type_id));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 /// Event in the history of tile generation process, containing the [`GridPosition`] of the tile alongside its collapsed
 /// `tile_type_id`.
 #[derive(Debug, Clone)]
@@ -291,3 +1408,627 @@ impl Subscriber for CollapseHistorySubscriber {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::gen::collapse::singular::tile::test::rules_without;
+    use crate::gen::collapse::singular::{
+        AdjacencyFrequency, AdjacencyRules, CollapseHistorySubscriber, CollapsibleTileGrid,
+        CountingObserver, FrequencyHints, RegionBacktracking, Resolver, Subscriber,
+    };
+    use crate::gen::collapse::{CollapsedGrid, CollapsedTileData, CollapsibleGrid};
+    use crate::map::{GridDir, GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::{GridPosition, GridTile, TileContainer};
+
+    #[derive(Default)]
+    struct ResetSubscriber {
+        resets: Vec<(GridPosition, u32)>,
+    }
+
+    impl Subscriber for ResetSubscriber {
+        fn on_collapse(&mut self, _position: &GridPosition, _tile_type_id: u64) {}
+
+        fn on_region_reset(&mut self, position: &GridPosition, radius: u32) {
+            self.resets.push((*position, radius));
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Wang tiles with binary edges, where `tile_type_id` bits describe the edge for each [`GridDir`]. Only a subset of
+    /// tiles is available, so some arrangements can't be completed - but contradictions can be solved locally.
+    fn wang_rules() -> (
+        FrequencyHints<BasicIdentTileData>,
+        AdjacencyRules<BasicIdentTileData>,
+    ) {
+        let tiles = [1, 8, 10, 11, 14]
+            .into_iter()
+            .map(|id| GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(id)))
+            .collect::<Vec<_>>();
+        let edge = |tile: &GridTile<BasicIdentTileData>, dir: GridDir| {
+            (tile.as_ref().tile_type_id() >> dir as u64) & 1
+        };
+        let mut frequency = FrequencyHints::default();
+        let mut adjacency = AdjacencyRules::default();
+
+        for tile in tiles.iter() {
+            frequency.set_weight_for_tile(tile, 1);
+            for other in tiles.iter() {
+                for dir in GridDir::ALL_2D {
+                    if edge(tile, *dir) == edge(other, dir.opposite()) {
+                        adjacency.add_adjacency(tile, other, *dir);
+                    }
+                }
+            }
+        }
+        (frequency, adjacency)
+    }
+
+    #[test]
+    fn backtracking_resets_only_local_regions() {
+        let (frequency, adjacency) = wang_rules();
+        let size = GridSize::new_xy(20, 20);
+        let mut total_resets = 0;
+
+        for seed in 0..16 {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            let mut resolver =
+                Resolver::default().with_subscriber(Box::<ResetSubscriber>::default());
+            let mut rng = ChaChaRng::seed_from_u64(seed);
+
+            resolver
+                .generate_entrophy_backtracking(
+                    &mut grid,
+                    &mut rng,
+                    &size.get_all_possible_positions(),
+                    RegionBacktracking::new(1, 4),
+                )
+                .unwrap();
+
+            let subscriber = resolver.retrieve_subscriber().unwrap();
+            let subscriber = subscriber
+                .as_any()
+                .downcast_ref::<ResetSubscriber>()
+                .unwrap();
+            for (_, radius) in subscriber.resets.iter() {
+                assert!((1..=4).contains(radius));
+            }
+            total_resets += subscriber.resets.len();
+
+            assert!(grid.empty_positions().is_empty());
+            assert!(grid.retrieve_positions(false).is_empty());
+
+            let collapsed = grid.retrieve_collapsed();
+            for pos in size.get_all_possible_positions() {
+                let tile = collapsed.as_ref().get_tile_at_position(&pos).unwrap();
+                for dir in GridDir::ALL_2D {
+                    if let Some(neighbour) = collapsed.as_ref().get_neighbour_at(&pos, dir) {
+                        assert!(adjacency.is_valid_at_dir(
+                            tile.as_ref().tile_type_id(),
+                            *dir,
+                            neighbour.as_ref().tile_type_id()
+                        ));
+                    }
+                }
+            }
+        }
+        assert!(total_resets > 0);
+    }
+
+    #[test]
+    fn backtracking_fails_on_incompatible_precollapsed() {
+        let (frequency, adjacency) = wang_rules();
+        let size = GridSize::new_xy(6, 6);
+        // Position between them would need the left edge without the right one, which none of the tiles has.
+        let mut precollapsed = CollapsedGrid::new(size);
+        precollapsed.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(8));
+        precollapsed.insert_data(&GridPosition::new_xy(2, 0), CollapsedTileData::new(1));
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        grid.populate_from_collapsed(&precollapsed).unwrap();
+        let mut resolver = Resolver::default().with_subscriber(Box::<ResetSubscriber>::default());
+
+        let positions = grid.empty_positions();
+        let err = resolver
+            .generate_entrophy_backtracking(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(0),
+                &positions,
+                RegionBacktracking::default(),
+            )
+            .unwrap_err();
+
+        assert!(!err.is_probabilistic());
+        let subscriber = resolver.retrieve_subscriber().unwrap();
+        let subscriber = subscriber
+            .as_any()
+            .downcast_ref::<ResetSubscriber>()
+            .unwrap();
+        assert!(subscriber.resets.is_empty());
+    }
+
+    #[test]
+    fn lookahead_reduces_contradictions() {
+        let (frequency, adjacency) = wang_rules();
+        let size = GridSize::new_xy(12, 12);
+        let positions = size.get_all_possible_positions();
+        let successes = |lookahead: bool| {
+            (0..64)
+                .filter(|seed| {
+                    let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+                    let mut rng = ChaChaRng::seed_from_u64(*seed);
+                    Resolver::default()
+                        .with_lookahead(lookahead)
+                        .generate_entrophy(&mut grid, &mut rng, &positions)
+                        .is_ok()
+                })
+                .count()
+        };
+
+        let naive = successes(false);
+        let lookahead = successes(true);
+        assert!(naive < 64, "naive successes: {naive}");
+        assert!(lookahead > naive, "lookahead: {lookahead}, naive: {naive}");
+    }
+
+    #[test]
+    fn timeout_stops_generation_promptly() {
+        let (frequency, adjacency) = wang_rules();
+        let size = GridSize::new_xy(200, 200);
+        let positions = size.get_all_possible_positions();
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let mut rng = ChaChaRng::seed_from_u64(3);
+
+        let start = Instant::now();
+        let err = Resolver::default()
+            .generate_entrophy_timeout(&mut grid, &mut rng, &positions, Duration::from_millis(1))
+            .unwrap_err();
+
+        assert!(err.is_timeout());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(grid.retrieve_positions(true).len() < positions.len());
+        assert_eq!(
+            grid.retrieve_positions(true).len(),
+            grid.retrieve_collapsed().as_ref().get_all_positions().len()
+        );
+    }
+
+    #[test]
+    fn counting_observer_counts_all_collapses() {
+        // Tile `1` can't be placed next to itself.
+        let (frequency, adjacency) = rules_without(2, 1, |_, _, other| other == 1);
+
+        let size = GridSize::new_xy(10, 10);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let mut resolver = Resolver::default()
+            .with_observer(Box::<CountingObserver>::default())
+            .with_observer(Box::<CountingObserver>::default());
+        resolver
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(3),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+        let tile_count = grid.retrieve_collapsed().as_ref().get_all_positions().len();
+
+        let observers = resolver.retrieve_observers();
+        assert_eq!(2, observers.len());
+        for observer in observers {
+            let observer = observer
+                .as_any()
+                .downcast_ref::<CountingObserver>()
+                .unwrap();
+            assert_eq!(100, observer.total());
+            assert_eq!(tile_count, observer.collapses());
+            assert_eq!(1, observer.starts());
+            assert_eq!(1, observer.finishes());
+            assert!(observer.propagations() > 0);
+        }
+    }
+
+    #[test]
+    fn mirrored_output_is_symmetric() {
+        // Tiles `1` and `2` are mirror images of each other, and `2` can't be placed to the right of `1`.
+        let (frequency, adjacency) = rules_without(3, 1, |tile, dir, other| {
+            (tile, dir, other) == (1, GridDir::RIGHT, 2)
+                || (tile, dir, other) == (2, GridDir::LEFT, 1)
+        });
+        let mirror = HashMap::from([(1, 2), (2, 1)]);
+
+        for size in [GridSize::new_xy(9, 8), GridSize::new_xy(10, 8)] {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            Resolver::default()
+                .generate_entrophy_mirrored(
+                    &mut grid,
+                    &mut ChaChaRng::seed_from_u64(17),
+                    &size.get_all_possible_positions(),
+                    &mirror,
+                )
+                .unwrap();
+
+            let collapsed = grid.retrieve_collapsed();
+            let map = collapsed.as_ref();
+            assert_eq!(size.x() * size.y(), map.get_all_positions().len() as u32);
+            for tile in map.iter_tiles() {
+                let position = tile.grid_position();
+                let mirror_position =
+                    GridPosition::new_xy(size.x() - 1 - position.x(), *position.y());
+                let tile_type_id = tile.as_ref().tile_type_id();
+                let mirrored = map.get_tile_at_position(&mirror_position).unwrap();
+                assert_eq!(
+                    *mirror.get(&tile_type_id).unwrap_or(&tile_type_id),
+                    mirrored.as_ref().tile_type_id()
+                );
+            }
+            for (tile, dir, neighbour) in map.adjacent_pairs() {
+                assert!(adjacency.is_valid_at_dir(
+                    tile.as_ref().tile_type_id(),
+                    dir,
+                    neighbour.as_ref().tile_type_id()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn lower_temperature_lowers_output_entrophy() {
+        let (mut frequency, adjacency) = rules_without(3, 1, |_, _, _| false);
+        for (id, weight) in [(0, 10), (1, 30), (2, 60)] {
+            frequency.set_weight_for_tile(
+                &GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(id)),
+                weight,
+            );
+        }
+        let size = GridSize::new_xy(20, 20);
+
+        let output_entrophy = |temperature: f32| {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            Resolver::default()
+                .with_temperature(temperature)
+                .generate_entrophy(
+                    &mut grid,
+                    &mut ChaChaRng::seed_from_u64(11),
+                    &size.get_all_possible_positions(),
+                )
+                .unwrap();
+            let mut counts = HashMap::<u64, u32>::new();
+            for tile in grid.retrieve_collapsed().as_ref().iter_tiles() {
+                *counts.entry(tile.as_ref().tile_type_id()).or_default() += 1;
+            }
+            let total = size.max_tile_count() as f32;
+            counts
+                .values()
+                .map(|count| {
+                    let p = *count as f32 / total;
+                    -p * p.log2()
+                })
+                .sum::<f32>()
+        };
+
+        let hot = output_entrophy(8.);
+        let neutral = output_entrophy(1.);
+        let cold = output_entrophy(0.2);
+        let greedy = output_entrophy(0.);
+        assert!(hot > neutral, "hot: {hot}, neutral: {neutral}");
+        assert!(neutral > cold, "neutral: {neutral}, cold: {cold}");
+        assert!(hot > 1.5, "hot: {hot}");
+        assert_eq!(0., greedy);
+    }
+
+    #[test]
+    fn entrophy_band_picks_beyond_minimum() {
+        // Tile `0` can't be placed next to tile `1`, lowering the entrophy of neighbours of the collapsed tile.
+        let (frequency, adjacency) = rules_without(3, 1, |tile, _, other| tile + other == 1);
+        let size = GridSize::new_xy(8, 8);
+        let mut collapsed = CollapsedGrid::new(size);
+        collapsed.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(0));
+        let lowest = [GridPosition::new_xy(1, 0), GridPosition::new_xy(0, 1)];
+
+        let first_collapsed = |band: f32, seed: u64| {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            grid.populate_from_collapsed(&collapsed).unwrap();
+            let positions = grid.empty_positions();
+            let mut resolver = Resolver::default()
+                .with_entrophy_band(band)
+                .with_subscriber(Box::<CollapseHistorySubscriber>::default());
+            resolver
+                .generate_entrophy(&mut grid, &mut ChaChaRng::seed_from_u64(seed), &positions)
+                .unwrap();
+            let subscriber = resolver.retrieve_subscriber().unwrap();
+            subscriber
+                .as_any()
+                .downcast_ref::<CollapseHistorySubscriber>()
+                .unwrap()
+                .history()[0]
+                .position
+        };
+
+        assert!((0..8).all(|seed| lowest.contains(&first_collapsed(0., seed))));
+        assert!((0..8).any(|seed| !lowest.contains(&first_collapsed(10., seed))));
+        // Not a number band falls back to the lowest entrophy.
+        assert!((0..8).all(|seed| lowest.contains(&first_collapsed(f32::NAN, seed))));
+    }
+
+    #[test]
+    fn relaxed_completes_over_constrained_grid() {
+        // Checkerboard rules, with pre-collapsed tiles of the same type on the opposite parity.
+        let (frequency, adjacency) = rules_without(2, 1, |tile, _, other| tile == other);
+
+        let size = GridSize::new_xy(6, 6);
+        let mut collapsed = CollapsedGrid::new(size);
+        collapsed.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(0));
+        collapsed.insert_data(&GridPosition::new_xy(3, 4), CollapsedTileData::new(0));
+        let new_grid = || {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            grid.populate_from_collapsed(&collapsed).unwrap();
+            grid
+        };
+
+        let mut grid = new_grid();
+        let positions = grid.empty_positions();
+        assert!(Resolver::default()
+            .generate_entrophy(&mut grid, &mut ChaChaRng::seed_from_u64(1), &positions)
+            .is_err());
+
+        let mut grid = new_grid();
+        let violations = Resolver::default().generate_entrophy_relaxed(
+            &mut grid,
+            &mut ChaChaRng::seed_from_u64(1),
+            &positions,
+        );
+
+        assert!(grid.empty_positions().is_empty());
+        assert!(grid.retrieve_positions(false).is_empty());
+        assert!(!violations.is_empty());
+        for violation in violations.iter() {
+            assert!(!adjacency.is_valid_at_dir(
+                violation.tile_type_id,
+                violation.direction,
+                violation.neighbour_tile_type_id
+            ));
+        }
+
+        let output = grid.retrieve_collapsed();
+        let mut invalid_pairs = 0;
+        for tile in output.as_ref().iter_tiles() {
+            for dir in [GridDir::RIGHT, GridDir::DOWN] {
+                if let Some(neighbour) = output
+                    .as_ref()
+                    .get_neighbour_at(&tile.grid_position(), &dir)
+                {
+                    if !adjacency.is_valid_at_dir(
+                        tile.as_ref().tile_type_id(),
+                        dir,
+                        neighbour.as_ref().tile_type_id(),
+                    ) {
+                        invalid_pairs += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!(invalid_pairs, violations.len());
+    }
+
+    #[test]
+    fn adjacency_weighting_clusters_output() {
+        let (frequency, adjacency) = rules_without(2, 10, |_, _, _| false);
+
+        // Sample with two large blobs: left half of `0` and right half of `1`.
+        let sample_size = GridSize::new_xy(8, 8);
+        let mut sample = GridMap2D::new(sample_size);
+        for pos in sample_size.get_all_possible_positions() {
+            sample.insert_data(&pos, BasicIdentTileData::tile_new((*pos.x() >= 4) as u64));
+        }
+        let mut adjacency_frequency = AdjacencyFrequency::default();
+        adjacency_frequency.analyze(&sample);
+
+        let size = GridSize::new_xy(16, 16);
+        let mismatch_ratio = |weighted: bool| {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            let mut rng = ChaChaRng::seed_from_u64(9);
+            let positions = size.get_all_possible_positions();
+            if weighted {
+                Resolver::default()
+                    .generate_entrophy_adjacency_weighted(
+                        &mut grid,
+                        &mut rng,
+                        &positions,
+                        &adjacency_frequency,
+                    )
+                    .unwrap();
+            } else {
+                Resolver::default()
+                    .generate_entrophy(&mut grid, &mut rng, &positions)
+                    .unwrap();
+            }
+
+            let collapsed = grid.retrieve_collapsed();
+            let map = collapsed.as_ref();
+            let (mut pairs, mut mismatched) = (0, 0);
+            for tile in map.iter_tiles() {
+                for dir in [GridDir::RIGHT, GridDir::DOWN] {
+                    if let Some(neighbour) = map.get_neighbour_at(&tile.grid_position(), &dir) {
+                        pairs += 1;
+                        if neighbour.as_ref().tile_type_id() != tile.as_ref().tile_type_id() {
+                            mismatched += 1;
+                        }
+                    }
+                }
+            }
+            mismatched as f32 / pairs as f32
+        };
+
+        let plain = mismatch_ratio(false);
+        let weighted = mismatch_ratio(true);
+        assert!(plain > 0.3, "plain mismatch ratio: {plain}");
+        assert!(
+            weighted < plain * 0.75,
+            "weighted: {weighted}, plain: {plain}"
+        );
+    }
+
+    #[test]
+    fn void_probability_leaves_empty_positions() {
+        // `0` and `1` can't be placed next to each other.
+        let (frequency, adjacency) = rules_without(3, 10, |tile, _, other| tile + other == 1);
+        let size = GridSize::new_xy(20, 20);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        Resolver::default()
+            .with_void_probability(0.5)
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(11),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let empty = grid.empty_positions().len();
+        assert!((140..=260).contains(&empty), "empty positions: {empty}");
+        assert_eq!(
+            size.max_tile_count() - empty,
+            grid.retrieve_positions(true).len()
+        );
+
+        let collapsed = grid.retrieve_collapsed();
+        for (tile, dir, neighbour) in collapsed.as_ref().adjacent_pairs() {
+            assert!(adjacency.is_valid_at_dir(
+                tile.as_ref().tile_type_id(),
+                dir,
+                neighbour.as_ref().tile_type_id()
+            ));
+        }
+    }
+
+    #[test]
+    fn relaxed_honours_void_probability_and_lookahead() {
+        let (frequency, adjacency) = rules_without(3, 10, |tile, _, other| tile + other == 1);
+        let size = GridSize::new_xy(20, 20);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let violations = Resolver::default()
+            .with_void_probability(0.5)
+            .generate_entrophy_relaxed(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(11),
+                &size.get_all_possible_positions(),
+            );
+
+        assert!(violations.is_empty());
+        let empty = grid.empty_positions().len();
+        assert!((140..=260).contains(&empty), "empty positions: {empty}");
+
+        let (frequency, adjacency) = wang_rules();
+        let size = GridSize::new_xy(12, 12);
+        let violations = |lookahead: bool| {
+            (0..16)
+                .map(|seed| {
+                    let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+                    Resolver::default()
+                        .with_lookahead(lookahead)
+                        .generate_entrophy_relaxed(
+                            &mut grid,
+                            &mut ChaChaRng::seed_from_u64(seed),
+                            &size.get_all_possible_positions(),
+                        )
+                        .len()
+                })
+                .sum::<usize>()
+        };
+        let naive = violations(false);
+        let lookahead = violations(true);
+        assert!(lookahead < naive, "lookahead: {lookahead}, naive: {naive}");
+    }
+
+    #[test]
+    fn streaming_sends_every_collapse() {
+        let (frequency, adjacency) = rules_without(3, 10, |_, _, _| false);
+        let size = GridSize::new_xy(6, 5);
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let handle = std::thread::spawn(move || {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            let mut resolver =
+                Resolver::default().with_observer(Box::<CountingObserver>::default());
+            resolver
+                .generate_streaming(
+                    &mut grid,
+                    &mut ChaChaRng::seed_from_u64(3),
+                    &size.get_all_possible_positions(),
+                    tx,
+                )
+                .unwrap();
+            (
+                grid.retrieve_collapsed(),
+                resolver.retrieve_observers().len(),
+            )
+        });
+
+        let received = rx.iter().collect::<Vec<_>>();
+        let (collapsed, observers) = handle.join().unwrap();
+
+        assert_eq!(1, observers);
+        assert_eq!(size.max_tile_count(), received.len());
+        for (position, tile_type_id) in received {
+            assert_eq!(
+                tile_type_id,
+                collapsed
+                    .as_ref()
+                    .get_tile_at_position(&position)
+                    .unwrap()
+                    .as_ref()
+                    .tile_type_id()
+            );
+        }
+    }
+
+    #[test]
+    fn split_rng_keeps_order_with_different_choices() {
+        let (frequency, adjacency) = rules_without(4, 10, |_, _, _| false);
+        let size = GridSize::new_xy(6, 6);
+
+        // Every tile can be placed next to any other, so the choices don't affect the entrophy.
+        let generate = |choice_seed: u64| {
+            let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+            let mut resolver =
+                Resolver::default().with_subscriber(Box::<CollapseHistorySubscriber>::default());
+            resolver
+                .generate_entrophy_split(
+                    &mut grid,
+                    &mut ChaChaRng::seed_from_u64(1),
+                    &mut ChaChaRng::seed_from_u64(choice_seed),
+                    &size.get_all_possible_positions(),
+                )
+                .unwrap();
+            let subscriber = resolver.retrieve_subscriber().unwrap();
+            let history = subscriber
+                .as_any()
+                .downcast_ref::<CollapseHistorySubscriber>()
+                .unwrap()
+                .history()
+                .to_vec();
+            (
+                history.iter().map(|item| item.position).collect::<Vec<_>>(),
+                history
+                    .iter()
+                    .map(|item| item.tile_type_id)
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let (first_order, first_tiles) = generate(2);
+        let (second_order, second_tiles) = generate(3);
+        assert_eq!(size.max_tile_count(), first_order.len());
+        assert_eq!(first_order, second_order);
+        assert_ne!(first_tiles, second_tiles);
+    }
+}
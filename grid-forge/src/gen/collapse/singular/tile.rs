@@ -1,19 +1,22 @@
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
-use rand::Rng;
-
-use crate::gen::collapse::error::CollapsibleGridError;
+use crate::gen::collapse::error::{
+    CollapseError, CollapseErrorKind, CollapsibleGridError, RulesetDecodeError,
+};
 use crate::gen::collapse::grid::CollapsibleGrid;
 use crate::gen::collapse::option::{PerOptionData, WaysToBeOption};
-use crate::gen::collapse::{self, tile::*, CollapsedGrid, PropagateItem};
-use crate::map::{GridMap2D, GridSize};
+use crate::gen::collapse::{
+    self, tile::*, CollapseQueue, CollapsedGrid, EntrophyQueue, PropagateItem, Propagator,
+};
+use crate::gen::GridRng;
+use crate::map::{GridDir, GridMap2D, GridSize};
 use crate::tile::identifiable::builders::IdentTileBuilder;
 use crate::tile::identifiable::collection::IdentTileCollection;
 use crate::tile::identifiable::IdentifiableTileData;
-use crate::tile::{GridPosition, GridTile, TileContainer, TileData};
+use crate::tile::{GridPosition, GridTile, TileContainer, TileData, TileRotation};
 
-use super::{AdjacencyRules, FrequencyHints};
+use super::{AdjacencyRules, FrequencyHints, TileSymmetryGroup};
 
 /// Tile with options that can be collapsed into one of them. Mostly used within the [`CollapsibleTileGrid`].
 #[derive(Clone, Debug)]
@@ -71,7 +74,7 @@ impl crate::gen::collapse::tile::private::Sealed for CollapsibleTile {
         self.num_possible_options
     }
 
-    fn collapse<R: Rng>(
+    fn collapse<R: GridRng>(
         &mut self,
         rng: &mut R,
         options_data: &PerOptionData,
@@ -109,6 +112,63 @@ impl crate::gen::collapse::tile::private::Sealed for CollapsibleTile {
     }
 }
 
+impl CollapsibleTile {
+    /// Collapses tile into one of possible options, with their weights multiplied by the factors provided for their
+    /// `tile_type_id`. Returns the vector of the removed options, or `None` if all possible options have zero weight
+    /// after the multiplication.
+    pub(crate) fn collapse_biased<R: GridRng>(
+        &mut self,
+        rng: &mut R,
+        options_data: &PerOptionData,
+        multipliers: &HashMap<u64, f32>,
+    ) -> Option<Vec<usize>> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let weighted = self
+            .ways_to_be_option()
+            .iter_possible()
+            .map(|option_idx| {
+                let factor = options_data
+                    .get_tile_type_id(&option_idx)
+                    .and_then(|tile_type_id| multipliers.get(&tile_type_id))
+                    .copied()
+                    .unwrap_or(1.);
+                (
+                    option_idx,
+                    options_data.get_weights(option_idx).0 as f32 * factor,
+                )
+            })
+            .collect::<Vec<_>>();
+        let total = weighted.iter().map(|(_, weight)| weight).sum::<f32>();
+        if total <= 0. {
+            return None;
+        }
+
+        let random = rng.gen_range_f32(0. ..total);
+        let mut current_sum = 0.;
+        let mut chosen = None;
+        for (option_idx, weight) in weighted.iter() {
+            if *weight <= 0. {
+                continue;
+            }
+            // Falls back to the last option with positive weight in case of rounding errors.
+            chosen = Some(*option_idx);
+            current_sum += weight;
+            if random < current_sum {
+                break;
+            }
+        }
+        let chosen = chosen?;
+        self.mark_collapsed(chosen);
+        Some(
+            weighted
+                .into_iter()
+                .filter_map(|(option_idx, _)| (option_idx != chosen).then_some(option_idx))
+                .collect(),
+        )
+    }
+}
+
 impl CollapsibleTileData for CollapsibleTile {
     fn collapse_idx(&self) -> Option<usize> {
         self.collapsed_option
@@ -188,6 +248,13 @@ impl CollapsibleTileData for CollapsibleTile {
 pub struct CollapsibleTileGrid<Tile: IdentifiableTileData> {
     pub(crate) grid: GridMap2D<CollapsibleTile>,
     pub(crate) option_data: PerOptionData,
+    /// Weight multipliers for `tile_type_id` at given position.
+    pub(crate) weight_multipliers: HashMap<GridPosition, HashMap<u64, f32>>,
+    /// Rotations of `tile_type_id` being part of symmetry groups.
+    rotations: HashMap<u64, TileRotation>,
+    /// Banned `tile_type_id` at given position.
+    banned: HashMap<GridPosition, HashSet<u64>>,
+    void_tile_id: Option<u64>,
     tile_type: PhantomData<Tile>,
 }
 
@@ -199,15 +266,43 @@ impl<Tile: IdentifiableTileData> CollapsibleTileGrid<Tile> {
         adjacencies: &AdjacencyRules<Tile>,
     ) -> Self {
         let mut option_data = PerOptionData::default();
-        option_data.populate(&frequencies.get_all_weights_cloned(), adjacencies.inner());
+        option_data.populate_excluding(
+            &frequencies.get_all_weights_cloned(),
+            adjacencies.inner(),
+            frequencies.get_excluded(),
+        );
 
         Self {
             grid: GridMap2D::new(size),
             option_data,
+            weight_multipliers: HashMap::new(),
+            rotations: HashMap::new(),
+            banned: HashMap::new(),
+            void_tile_id: None,
             tile_type: PhantomData,
         }
     }
 
+    /// Creates a new empty grid with given [`GridSize`], loading the prepared rules serialized with
+    /// [`rules_to_bytes`](Self::rules_to_bytes) instead of preparing them from the rulesets.
+    pub fn new_empty_from_bytes(size: GridSize, bytes: &[u8]) -> Result<Self, RulesetDecodeError> {
+        Ok(Self {
+            grid: GridMap2D::new(size),
+            option_data: PerOptionData::from_bytes(bytes)?,
+            weight_multipliers: HashMap::new(),
+            rotations: HashMap::new(),
+            banned: HashMap::new(),
+            void_tile_id: None,
+            tile_type: PhantomData,
+        })
+    }
+
+    /// Serializes the rules prepared out of the *frequency hints* and *adjacency rules*, allowing to skip their
+    /// preparation when creating the grid with [`new_empty_from_bytes`](Self::new_empty_from_bytes).
+    pub fn rules_to_bytes(&self) -> Vec<u8> {
+        self.option_data.to_bytes()
+    }
+
     /// Creates a new grid using the [`CollapsedGrid`] as a source grid. Created grid will have the same size as the
     /// source grid, and will be populated with existing collapsed tiles.
     ///
@@ -219,7 +314,11 @@ impl<Tile: IdentifiableTileData> CollapsibleTileGrid<Tile> {
         adjacencies: &AdjacencyRules<Tile>,
     ) -> Result<Self, CollapsibleGridError> {
         let mut option_data = PerOptionData::default();
-        option_data.populate(&frequencies.get_all_weights_cloned(), adjacencies.inner());
+        option_data.populate_excluding(
+            &frequencies.get_all_weights_cloned(),
+            adjacencies.inner(),
+            frequencies.get_excluded(),
+        );
 
         let missing_ids = collapsed
             .tile_type_ids()
@@ -247,6 +346,10 @@ impl<Tile: IdentifiableTileData> CollapsibleTileGrid<Tile> {
         Ok(Self {
             grid,
             option_data,
+            weight_multipliers: HashMap::new(),
+            rotations: HashMap::new(),
+            banned: HashMap::new(),
+            void_tile_id: None,
             tile_type: PhantomData,
         })
     }
@@ -260,9 +363,194 @@ impl<Tile: IdentifiableTileData> CollapsibleTileGrid<Tile> {
         frequencies: &FrequencyHints<Tile>,
         adjacencies: &AdjacencyRules<Tile>,
     ) -> Result<Self, CollapsibleGridError> {
-        let collapsed = self.retrieve_collapsed();
+        let collapsed = self.collapsed_with_void(true);
+
+        let mut changed = Self::new_from_collapsed(&collapsed, frequencies, adjacencies)?;
+        changed.void_tile_id = self.void_tile_id;
+        changed.weight_multipliers = self.weight_multipliers;
+        changed.rotations = self.rotations;
+        changed.banned = self.banned;
+        Ok(changed)
+    }
+
+    /// Designates the `tile_type_id` representing emptiness. The *void* tile participates in the generation process
+    /// like any other tile, but the positions collapsed into it are left empty while retrieving the collapsed tiles
+    /// via [`CollapsibleGrid`] methods.
+    pub fn with_void_tile_id(mut self, tile_type_id: u64) -> Self {
+        self.void_tile_id = Some(tile_type_id);
+        self
+    }
 
-        Self::new_from_collapsed(&collapsed, frequencies, adjacencies)
+    /// Sets the multiplier for the weight of the tile with given `tile_type_id` at the specified position. It is
+    /// applied during the weighted choice of the option while collapsing the tile at this position, making it
+    /// possible to bias the generation spatially without changing the global [`FrequencyHints`].
+    ///
+    /// Method can return an error if the `tile_type_id` is not present in the provided frequency hints and adjacency
+    /// rules, or the `factor` is negative or not a number.
+    pub fn set_weight_multiplier(
+        &mut self,
+        position: &GridPosition,
+        tile_type_id: u64,
+        factor: f32,
+    ) -> Result<(), CollapsibleGridError> {
+        if factor.is_nan() || factor < 0. {
+            return Err(CollapsibleGridError::new_invalid_multiplier(factor));
+        }
+        if self.option_data.get_tile_data(&tile_type_id).is_none() {
+            return Err(CollapsibleGridError::new_missing(vec![tile_type_id]));
+        }
+        self.weight_multipliers
+            .entry(*position)
+            .or_default()
+            .insert(tile_type_id, factor);
+        Ok(())
+    }
+
+    /// Bans the option of given `tile_type_id` at the specified position, so the tile there won't be collapsed into
+    /// it. Bans are propagated alongside the constraints of already collapsed tiles at the beginning of the
+    /// [`EntrophyQueue`]-based generation and in [`propagate_constraints`](Self::propagate_constraints).
+    ///
+    /// Method can return an error if the `tile_type_id` is not present in the provided frequency hints and adjacency
+    /// rules.
+    pub fn ban_option(
+        &mut self,
+        position: &GridPosition,
+        tile_type_id: u64,
+    ) -> Result<(), CollapsibleGridError> {
+        if self.option_data.get_tile_data(&tile_type_id).is_none() {
+            return Err(CollapsibleGridError::new_missing(vec![tile_type_id]));
+        }
+        self.banned
+            .entry(*position)
+            .or_default()
+            .insert(tile_type_id);
+        Ok(())
+    }
+
+    /// Bans all options with `tile_type_id` outside of the allowed set at every position for which the `mask` returns
+    /// `Some`, as with [`ban_option`](Self::ban_option). Useful for constraining the generation in bulk with masks
+    /// precomputed out of the higher-level layout.
+    ///
+    /// Method returns an error with the first position at which no option would be left, including the previously
+    /// banned ones. Nothing is banned in such case.
+    pub fn apply_possibility_mask(
+        &mut self,
+        mask: impl Fn(GridPosition) -> Option<HashSet<u64>>,
+    ) -> Result<(), CollapsibleGridError> {
+        let tile_type_ids = self.option_data.inner().keys().copied().collect::<Vec<_>>();
+        let mut to_ban = Vec::new();
+
+        for position in self.grid.size().get_all_possible_positions() {
+            let Some(allowed) = mask(position) else {
+                continue;
+            };
+            let banned = self.banned.get(&position);
+            if !tile_type_ids.iter().any(|tile_type_id| {
+                allowed.contains(tile_type_id)
+                    && !banned.is_some_and(|banned| banned.contains(tile_type_id))
+            }) {
+                return Err(CollapsibleGridError::new_collapse(position));
+            }
+            to_ban.push((
+                position,
+                tile_type_ids
+                    .iter()
+                    .filter(|tile_type_id| !allowed.contains(tile_type_id))
+                    .copied()
+                    .collect::<Vec<_>>(),
+            ));
+        }
+
+        for (position, tile_type_ids) in to_ban {
+            self.banned
+                .entry(position)
+                .or_default()
+                .extend(tile_type_ids);
+        }
+        Ok(())
+    }
+
+    /// Removes banned options from uncollapsed tiles, updating them in the `queue`. Returns the items to propagate
+    /// the removals, or the position of the tile left without any options if not `relaxed`.
+    pub(crate) fn apply_bans(
+        &mut self,
+        queue: &mut EntrophyQueue,
+        relaxed: bool,
+    ) -> Result<Vec<PropagateItem>, GridPosition> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let mut items = Vec::new();
+        for (position, tile_type_ids) in self.banned.iter() {
+            let Some(mut tile) = self.grid.get_mut_tile_at_position(position) else {
+                continue;
+            };
+            if tile.as_ref().is_collapsed() {
+                continue;
+            }
+            for option_idx in tile_type_ids
+                .iter()
+                .filter_map(|tile_type_id| self.option_data.get_tile_data(tile_type_id))
+            {
+                if tile
+                    .as_mut()
+                    .mut_ways_to_be_option()
+                    .purge_option(*option_idx)
+                {
+                    tile.as_mut()
+                        .remove_option(self.option_data.get_weights(*option_idx));
+                    items.push(PropagateItem::new(*position, *option_idx));
+                }
+            }
+            if tile.as_ref().has_compatible_options() {
+                queue.update_queue(&tile);
+            } else if !relaxed {
+                return Err(*position);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Registers the symmetry `groups` used to create the rules, so the tiles retrieved via
+    /// [`CollapsibleGrid::retrieve_collapsed`] carry their [`TileRotation`].
+    pub fn with_symmetry_groups(mut self, groups: &[TileSymmetryGroup]) -> Self {
+        for group in groups {
+            for (tile_type_id, rotation) in group.rotations().iter().zip(TileRotation::ALL) {
+                self.rotations.insert(*tile_type_id, rotation);
+            }
+        }
+        self
+    }
+
+    /// Returns the `tile_type_id` representing emptiness, if designated.
+    pub fn void_tile_id(&self) -> Option<u64> {
+        self.void_tile_id
+    }
+
+    fn collapsed_type_id(&self, tile: &CollapsibleTile) -> u64 {
+        self.option_data
+            .get_tile_type_id(&tile.collapse_idx().expect("cannot get collapse idx"))
+            .expect("cannot get option id for collapse idx")
+    }
+
+    fn collapsed_with_void(&self, include_void: bool) -> CollapsedGrid {
+        let mut out = CollapsedGrid::new(*self.grid.size());
+
+        for tile in self.grid.iter_tiles() {
+            if !tile.as_ref().is_collapsed() {
+                continue;
+            }
+            let tile_type_id = self.collapsed_type_id(tile.as_ref());
+            if !include_void && self.void_tile_id == Some(tile_type_id) {
+                continue;
+            }
+            let mut data = CollapsedTileData::new(tile_type_id);
+            if let Some(rotation) = self.rotations.get(&tile_type_id) {
+                data = data.with_rotation(*rotation);
+            }
+            out.insert_data(&tile.grid_position(), data);
+        }
+
+        out
     }
 
     /// Populates the grid with all collapsed tiles from the provided [`CollapsedGrid`].
@@ -309,34 +597,148 @@ impl<Tile: IdentifiableTileData> CollapsibleTileGrid<Tile> {
 
         Ok(())
     }
+
+    /// Places the tiles from the `stamp` into the grid as collapsed ones, with upper-left corner of the stamp placed
+    /// at `at` position. Useful for guaranteeing some authored arrangement of tiles to be present in the generated grid.
+    ///
+    /// Stamp tiles are checked against each other and already collapsed tiles in the grid, and then their constraints
+    /// are propagated through the rest of the grid. [`CollapseError`] is returned without modifying the grid if the
+    /// stamp doesn't fit into the grid, contains `tile_type_id`s not present in the rulesets or conflicts with the
+    /// current constraints. Placement involves no random choices, so unlike the collapse methods it doesn't need the
+    /// random number generator.
+    ///
+    /// Propagation only validates the placement: the grid keeps the stamp tiles, while the constraints are propagated
+    /// again at the beginning of the collapse process. If the stamp positions are passed to the resolver to collapse,
+    /// the stamp will be overwritten.
+    pub fn place_stamp(
+        &mut self,
+        at: &GridPosition,
+        stamp: &CollapsedGrid,
+    ) -> Result<(), CollapseError> {
+        let mut to_place = HashMap::new();
+
+        for tile in stamp.as_ref().iter_tiles() {
+            let position = *at + tile.grid_position();
+            match self
+                .option_data
+                .get_tile_data(&tile.as_ref().tile_type_id())
+            {
+                Some(option_idx) if self.grid.size().is_position_valid(&position) => {
+                    to_place.insert(position, *option_idx);
+                }
+                _ => {
+                    return Err(CollapseError::new(
+                        position,
+                        CollapseErrorKind::StampTile,
+                        0,
+                    ))
+                }
+            }
+        }
+
+        for (position, option_idx) in to_place.iter() {
+            for direction in GridDir::ALL_2D {
+                let Some(neighbour_pos) = direction.march_step(position, self.grid.size()) else {
+                    continue;
+                };
+                let neighbour_idx = to_place.get(&neighbour_pos).copied().or_else(|| {
+                    self.grid
+                        .get_tile_at_position(&neighbour_pos)
+                        .and_then(|tile| tile.as_ref().collapse_idx())
+                });
+                if let Some(neighbour_idx) = neighbour_idx {
+                    if !self
+                        .option_data
+                        .get_all_enabled_in_direction(*option_idx, *direction)
+                        .contains(&neighbour_idx)
+                    {
+                        return Err(CollapseError::new(
+                            *position,
+                            CollapseErrorKind::StampConflict,
+                            0,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let previous = self.grid.clone();
+        for (position, option_idx) in to_place {
+            self.grid
+                .insert_data(&position, CollapsibleTile::new_collapsed_data(option_idx));
+        }
+        let placed = self.grid.clone();
+
+        match self.propagate_constraints() {
+            Ok(()) => {
+                self.grid = placed;
+                Ok(())
+            }
+            Err(err) => {
+                self.grid = previous;
+                Err(CollapseError::new(
+                    err.failed_pos(),
+                    CollapseErrorKind::StampConflict,
+                    0,
+                ))
+            }
+        }
+    }
+
+    /// Propagates the constraints put by already collapsed tiles and banned options onto all remaining positions, without collapsing any
+    /// of them. Empty positions are filled with uncollapsed tiles with their options reduced to the ones compatible
+    /// with the collapsed tiles, which can be inspected with [`possible_tile_type_ids`](Self::possible_tile_type_ids).
+    ///
+    /// Returns [`CollapseError`] if any position is left without options, detecting over-constrained grid before the
+    /// generation. Uncollapsed tiles are removed at the beginning of every generation, so calling this method doesn't
+    /// affect the resolvers.
+    pub fn propagate_constraints(&mut self) -> Result<(), CollapseError> {
+        use crate::gen::collapse::grid::private::Sealed as _;
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        self.remove_uncollapsed();
+        let positions = self.grid.get_all_empty_positions();
+        for tile in CollapsibleTile::new_from_frequency(&positions, &self.option_data) {
+            self.grid.insert_tile(tile);
+        }
+
+        let mut queue = EntrophyQueue::default();
+        let mut propagator = Propagator::default();
+        let banned = self.apply_bans(&mut queue, false);
+        for item in CollapseError::from_result(banned, CollapseErrorKind::Init, 0)? {
+            propagator.push_propagate(item);
+        }
+        for item in self._get_initial_propagate_items(&positions) {
+            propagator.push_propagate(item);
+        }
+        let result = propagator.propagate(&mut self.grid, &self.option_data, &mut queue);
+        CollapseError::from_result(result, CollapseErrorKind::Init, 0)
+    }
+
+    /// Returns the `tile_type_id` of all options still possible at given `position`, or `None` if there is no tile.
+    /// Collapsed tiles have only their collapsed option possible.
+    pub fn possible_tile_type_ids(&self, position: &GridPosition) -> Option<Vec<u64>> {
+        use crate::gen::collapse::tile::private::Sealed as _;
+
+        let tile = self.grid.get_tile_at_position(position)?;
+        if let Some(option_idx) = tile.as_ref().collapse_idx() {
+            return Some(vec![self.option_data.get_tile_type_id(&option_idx)?]);
+        }
+        Some(
+            tile.as_ref()
+                .ways_to_be_option()
+                .iter_possible()
+                .filter_map(|option_idx| self.option_data.get_tile_type_id(&option_idx))
+                .collect(),
+        )
+    }
 }
 
 impl<Tile: IdentifiableTileData> CollapsibleGrid<Tile, CollapsibleTile>
     for CollapsibleTileGrid<Tile>
 {
     fn retrieve_collapsed(&self) -> CollapsedGrid {
-        let mut out = CollapsedGrid::new(*self.grid.size());
-
-        for tile in self.grid.iter_tiles() {
-            if !tile.as_ref().is_collapsed() {
-                continue;
-            }
-            out.insert_data(
-                &tile.grid_position(),
-                CollapsedTileData::new(
-                    self.option_data
-                        .get_tile_type_id(
-                            &tile
-                                .as_ref()
-                                .collapse_idx()
-                                .expect("cannot get collapse idx"),
-                        )
-                        .expect("cannot get option id for collapse idx"),
-                ),
-            );
-        }
-
-        out
+        self.collapsed_with_void(false)
     }
 
     fn retrieve_ident<T: IdentifiableTileData, B: IdentTileBuilder<T>>(
@@ -349,19 +751,11 @@ impl<Tile: IdentifiableTileData> CollapsibleGrid<Tile, CollapsibleTile>
             if !tile.as_ref().is_collapsed() {
                 continue;
             }
-            out.insert_tile(
-                builder.build_tile_unchecked(
-                    tile.grid_position(),
-                    self.option_data
-                        .get_tile_type_id(
-                            &tile
-                                .as_ref()
-                                .collapse_idx()
-                                .expect("cannot get collapse idx"),
-                        )
-                        .expect("cannot get option id for collapse idx"),
-                ),
-            );
+            let tile_type_id = self.collapsed_type_id(tile.as_ref());
+            if self.void_tile_id == Some(tile_type_id) {
+                continue;
+            }
+            out.insert_tile(builder.build_tile_unchecked(tile.grid_position(), tile_type_id));
         }
 
         Ok(out)
@@ -412,3 +806,505 @@ impl<Tile: IdentifiableTileData> collapse::grid::private::Sealed<CollapsibleTile
         out
     }
 }
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::collections::HashSet;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::gen::collapse::singular::{
+        AdjacencyRules, CollapseHistorySubscriber, CollapsibleTileGrid, FrequencyHints, Resolver,
+        TileSymmetryGroup,
+    };
+    use crate::gen::collapse::{CollapsedGrid, CollapsedTileData, CollapsibleGrid};
+    use crate::map::{GridDir, GridSize};
+    use crate::tile::identifiable::builders::{
+        ConstructableViaIdentifierTile, IdentTileTraitBuilder,
+    };
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::TileRotation;
+    use crate::tile::{GridPosition, GridTile, TileContainer};
+
+    const VOID_ID: u64 = 0;
+    const SOLID_ID: u64 = 1;
+
+    #[test]
+    fn void_tiles_are_left_empty() {
+        let void = GridTile::new(
+            GridPosition::new_xy(0, 0),
+            BasicIdentTileData::tile_new(VOID_ID),
+        );
+        let solid = GridTile::new(
+            GridPosition::new_xy(0, 0),
+            BasicIdentTileData::tile_new(SOLID_ID),
+        );
+
+        let mut frequency = FrequencyHints::default();
+        frequency.set_weight_for_tile(&void, 1);
+        frequency.set_weight_for_tile(&solid, 1);
+
+        // Void can't be placed next to another void.
+        let mut adjacency = AdjacencyRules::default();
+        for dir in GridDir::ALL_2D {
+            adjacency.add_adjacency(&void, &solid, *dir);
+            adjacency.add_adjacency(&solid, &void, *dir);
+            adjacency.add_adjacency(&solid, &solid, *dir);
+        }
+
+        let size = GridSize::new_xy(10, 10);
+        let mut grid =
+            CollapsibleTileGrid::new_empty(size, &frequency, &adjacency).with_void_tile_id(VOID_ID);
+        let mut resolver =
+            Resolver::default().with_subscriber(Box::<CollapseHistorySubscriber>::default());
+        resolver
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(7),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let subscriber = resolver.retrieve_subscriber().unwrap();
+        let history = subscriber
+            .as_any()
+            .downcast_ref::<CollapseHistorySubscriber>()
+            .unwrap()
+            .history();
+        let mut void_positions = history
+            .iter()
+            .filter(|item| item.tile_type_id == VOID_ID)
+            .map(|item| item.position)
+            .collect::<Vec<_>>();
+        void_positions.sort();
+        assert!(!void_positions.is_empty());
+
+        let collapsed = grid.retrieve_collapsed();
+        let mut empty_positions = collapsed.as_ref().get_all_empty_positions();
+        empty_positions.sort();
+        assert_eq!(void_positions, empty_positions);
+
+        let ident = grid
+            .retrieve_ident(&IdentTileTraitBuilder::<BasicIdentTileData>::default())
+            .unwrap();
+        let mut empty_positions = ident.get_all_empty_positions();
+        empty_positions.sort();
+        assert_eq!(void_positions, empty_positions);
+    }
+
+    /// Tiles `0..n` of the same `weight`, which can be placed next to each other in every direction, unless
+    /// `forbidden` returns `true` for the `(tile_type_id, direction, neighbour_type_id)`.
+    pub(crate) fn rules_without(
+        n: u64,
+        weight: u32,
+        forbidden: impl Fn(u64, GridDir, u64) -> bool,
+    ) -> (
+        FrequencyHints<BasicIdentTileData>,
+        AdjacencyRules<BasicIdentTileData>,
+    ) {
+        let tiles = (0..n)
+            .map(|id| GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(id)))
+            .collect::<Vec<_>>();
+        let mut frequency = FrequencyHints::default();
+        let mut adjacency = AdjacencyRules::default();
+
+        for tile in tiles.iter() {
+            frequency.set_weight_for_tile(tile, weight);
+            for other in tiles.iter() {
+                let (tile_id, other_id) =
+                    (tile.as_ref().tile_type_id(), other.as_ref().tile_type_id());
+                for dir in GridDir::ALL_2D {
+                    if !forbidden(tile_id, *dir, other_id) {
+                        adjacency.add_adjacency(tile, other, *dir);
+                    }
+                }
+            }
+        }
+        (frequency, adjacency)
+    }
+
+    /// Three tiles, where `0` and `1` can't be placed next to each other.
+    fn exclusive_rules() -> (
+        FrequencyHints<BasicIdentTileData>,
+        AdjacencyRules<BasicIdentTileData>,
+    ) {
+        rules_without(3, 1, |tile, _, other| tile + other == 1)
+    }
+
+    fn stamp(tiles: &[((u32, u32), u64)]) -> CollapsedGrid {
+        let mut stamp = CollapsedGrid::new(GridSize::new_xy(2, 2));
+        for ((x, y), tile_type_id) in tiles {
+            stamp.insert_data(
+                &GridPosition::new_xy(*x, *y),
+                CollapsedTileData::new(*tile_type_id),
+            );
+        }
+        stamp
+    }
+
+    #[test]
+    fn stamp_present_in_output() {
+        let (frequency, adjacency) = exclusive_rules();
+        let size = GridSize::new_xy(8, 8);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let stamp = stamp(&[((0, 0), 0), ((1, 0), 2), ((0, 1), 2), ((1, 1), 1)]);
+        let at = GridPosition::new_xy(3, 4);
+
+        grid.place_stamp(&at, &stamp).unwrap();
+        let positions = grid.empty_positions();
+        Resolver::default()
+            .generate_entrophy(&mut grid, &mut ChaChaRng::seed_from_u64(5), &positions)
+            .unwrap();
+
+        let collapsed = grid.retrieve_collapsed();
+        assert_eq!(64, collapsed.as_ref().get_all_positions().len());
+        for tile in stamp.as_ref().iter_tiles() {
+            let placed = collapsed
+                .as_ref()
+                .get_tile_at_position(&(at + tile.grid_position()))
+                .unwrap();
+            assert_eq!(tile.as_ref().tile_type_id(), placed.as_ref().tile_type_id());
+        }
+    }
+
+    #[test]
+    fn conflicting_stamp_is_rejected() {
+        let (frequency, adjacency) = exclusive_rules();
+        let size = GridSize::new_xy(8, 8);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+
+        let conflicting = stamp(&[((0, 0), 0), ((1, 0), 1)]);
+        assert!(grid
+            .place_stamp(&GridPosition::new_xy(0, 0), &conflicting)
+            .is_err());
+
+        grid.place_stamp(&GridPosition::new_xy(0, 0), &stamp(&[((0, 0), 0)]))
+            .unwrap();
+        assert!(grid
+            .place_stamp(&GridPosition::new_xy(1, 0), &stamp(&[((0, 0), 1)]))
+            .is_err());
+        assert!(grid
+            .place_stamp(&GridPosition::new_xy(7, 7), &stamp(&[((1, 1), 2)]))
+            .is_err());
+        assert_eq!(1, grid.retrieve_positions(true).len());
+    }
+
+    #[test]
+    fn option_index_round_trip() {
+        let (frequency, adjacency) = exclusive_rules();
+        let grid = CollapsibleTileGrid::new_empty(GridSize::new_xy(4, 4), &frequency, &adjacency);
+
+        let mut indices = Vec::new();
+        for tile_type_id in 0..3 {
+            let option_index = grid.option_index_of(tile_type_id).unwrap();
+            assert_eq!(Some(tile_type_id), grid.tile_type_id_of(option_index));
+            indices.push(option_index);
+        }
+        indices.sort();
+        assert_eq!(vec![0, 1, 2], indices);
+
+        assert_eq!(None, grid.option_index_of(3));
+        assert_eq!(None, grid.tile_type_id_of(3));
+    }
+
+    #[test]
+    fn rules_bytes_round_trip() {
+        let (frequency, adjacency) = exclusive_rules();
+        let size = GridSize::new_xy(8, 8);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let bytes = grid.rules_to_bytes();
+        let mut loaded =
+            CollapsibleTileGrid::<BasicIdentTileData>::new_empty_from_bytes(size, &bytes).unwrap();
+
+        for grid in [&mut grid, &mut loaded] {
+            Resolver::default()
+                .generate_entrophy(
+                    grid,
+                    &mut ChaChaRng::seed_from_u64(21),
+                    &size.get_all_possible_positions(),
+                )
+                .unwrap();
+        }
+        let tiles = |grid: &CollapsibleTileGrid<BasicIdentTileData>| {
+            grid.retrieve_collapsed()
+                .as_ref()
+                .iter_tiles_sorted()
+                .map(|tile| (tile.grid_position(), tile.as_ref().tile_type_id()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(64, tiles(&grid).len());
+        assert_eq!(tiles(&grid), tiles(&loaded));
+
+        let decode_error = |bytes: &[u8]| {
+            CollapsibleTileGrid::<BasicIdentTileData>::new_empty_from_bytes(size, bytes)
+                .err()
+                .map(|err| err.reason().to_string())
+        };
+        // Magic bytes, version, option count, possible option count and ways to be the last option from the right.
+        for (idx, reason) in [
+            (0, "missing ruleset header"),
+            (4, "unsupported ruleset version"),
+            (8, "unexpected end of data"),
+            (12, "inconsistent option count"),
+            (
+                bytes.len() - 4,
+                "ways to be option inconsistent with adjacencies",
+            ),
+        ] {
+            let mut inconsistent = bytes.clone();
+            inconsistent[idx] += 1;
+            assert_eq!(Some(reason.to_string()), decode_error(&inconsistent));
+        }
+        assert!(decode_error(&bytes[..bytes.len() - 1]).is_some());
+    }
+
+    #[test]
+    fn possibility_mask_respected() {
+        let (frequency, adjacency) = exclusive_rules();
+        let size = GridSize::new_xy(6, 6);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+
+        assert!(grid
+            .apply_possibility_mask(|pos| (pos.x() == &5).then(|| HashSet::from([7])))
+            .is_err());
+        grid.apply_possibility_mask(|pos| (pos.x() < &3).then(|| HashSet::from([1])))
+            .unwrap();
+        assert!(grid
+            .apply_possibility_mask(|pos| (pos.xy() == (0, 0)).then(|| HashSet::from([0, 2])))
+            .is_err());
+
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(9),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let collapsed = grid.retrieve_collapsed();
+        for tile in collapsed.as_ref().iter_tiles() {
+            let tile_type_id = tile.as_ref().tile_type_id();
+            match tile.grid_position().x() {
+                0..=2 => assert_eq!(1, tile_type_id),
+                3 => assert_ne!(0, tile_type_id),
+                _ => {}
+            }
+        }
+        assert_eq!(36, collapsed.as_ref().get_all_positions().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot get mapped id")]
+    fn missing_tile_is_not_treated_as_excluded() {
+        let (_, adjacency) = exclusive_rules();
+        let mut frequency = FrequencyHints::default();
+        frequency.set_weight_for_tile(
+            &GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(0)),
+            1,
+        );
+
+        CollapsibleTileGrid::new_empty(GridSize::new_xy(2, 2), &frequency, &adjacency);
+    }
+
+    #[test]
+    fn excluded_tile_never_generated() {
+        let (mut frequency, adjacency) = exclusive_rules();
+        assert!(frequency.exclude(2));
+        assert!(frequency.unsupported_tiles(&adjacency).is_empty());
+
+        let size = GridSize::new_xy(8, 8);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(3),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let collapsed = grid.retrieve_collapsed();
+        assert_eq!(64, collapsed.as_ref().get_all_positions().len());
+        assert!(collapsed
+            .as_ref()
+            .iter_tiles()
+            .all(|tile| tile.as_ref().tile_type_id() != 2));
+    }
+
+    #[test]
+    fn reset_restores_all_options() {
+        let (frequency, adjacency) = exclusive_rules();
+        let size = GridSize::new_xy(6, 6);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(5),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+        assert_eq!(36, grid.retrieve_positions(true).len());
+
+        grid.reset();
+        assert!(grid.retrieve_positions(true).is_empty());
+        assert_eq!(36, grid.retrieve_positions(false).len());
+        for pos in size.get_all_possible_positions() {
+            let mut ids = grid.possible_tile_type_ids(&pos).unwrap();
+            ids.sort();
+            assert_eq!(vec![0, 1, 2], ids);
+        }
+        assert!(grid
+            .retrieve_collapsed()
+            .as_ref()
+            .get_all_positions()
+            .is_empty());
+    }
+
+    #[test]
+    fn weight_multiplier_biases_region() {
+        let (frequency, adjacency) = rules_without(3, 1, |_, _, _| false);
+
+        let size = GridSize::new_xy(30, 30);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency);
+        let biased = |pos: &GridPosition| *pos.y() < 10;
+        for pos in size
+            .get_all_possible_positions()
+            .iter()
+            .filter(|p| biased(p))
+        {
+            grid.set_weight_multiplier(pos, 0, 50.).unwrap();
+        }
+        assert!(grid
+            .set_weight_multiplier(&GridPosition::new_xy(0, 0), 5, 1.)
+            .is_err());
+        for factor in [-1., f32::NAN] {
+            let err = grid
+                .set_weight_multiplier(&GridPosition::new_xy(0, 0), 0, factor)
+                .unwrap_err();
+            assert!(err.invalid_multiplier().is_some());
+        }
+
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(11),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let collapsed = grid.retrieve_collapsed();
+        let (mut inside, mut outside) = (0, 0);
+        for tile in collapsed.as_ref().iter_tiles() {
+            if tile.as_ref().tile_type_id() != 0 {
+                continue;
+            }
+            if biased(&tile.grid_position()) {
+                inside += 1;
+            } else {
+                outside += 1;
+            }
+        }
+        // 300 positions in the biased region and 600 outside of it.
+        let (inside, outside) = (inside as f32 / 300., outside as f32 / 600.);
+        assert!(inside > 0.85, "inside: {inside}");
+        assert!(
+            inside > outside + 0.2,
+            "inside: {inside}, outside: {outside}"
+        );
+    }
+
+    #[test]
+    fn propagation_reduces_options() {
+        let (frequency, adjacency) = exclusive_rules();
+        let mut grid =
+            CollapsibleTileGrid::new_empty(GridSize::new_xy(4, 4), &frequency, &adjacency);
+        grid.place_stamp(
+            &GridPosition::new_xy(0, 0),
+            &stamp(&[((0, 0), 0), ((0, 1), 0)]),
+        )
+        .unwrap();
+        grid.place_stamp(&GridPosition::new_xy(2, 0), &stamp(&[((0, 0), 1)]))
+            .unwrap();
+
+        grid.propagate_constraints().unwrap();
+
+        let possible = |x, y| {
+            let mut ids = grid
+                .possible_tile_type_ids(&GridPosition::new_xy(x, y))
+                .unwrap();
+            ids.sort();
+            ids
+        };
+        assert_eq!(vec![2], possible(1, 0));
+        assert_eq!(vec![0, 2], possible(1, 1));
+        assert_eq!(vec![0, 1, 2], possible(3, 3));
+        assert_eq!(vec![1], possible(2, 0));
+        assert_eq!(3, grid.retrieve_positions(true).len());
+    }
+
+    #[test]
+    fn propagation_detects_contradiction() {
+        let (frequency, adjacency) = rules_without(2, 1, |tile, _, other| tile != other);
+        let mut grid =
+            CollapsibleTileGrid::new_empty(GridSize::new_xy(4, 4), &frequency, &adjacency);
+        let mut collapsed = CollapsedGrid::new(GridSize::new_xy(4, 4));
+        collapsed.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(0));
+        collapsed.insert_data(&GridPosition::new_xy(2, 0), CollapsedTileData::new(1));
+        grid.populate_from_collapsed(&collapsed).unwrap();
+
+        // Tiles `0` and `1` can only neighbour themselves, so they can't both be present in connected grid.
+        let err = grid.propagate_constraints().unwrap_err();
+        assert!(!err.is_probabilistic());
+
+        // The same contradiction is detected while placing the stamps.
+        let mut grid =
+            CollapsibleTileGrid::new_empty(GridSize::new_xy(4, 4), &frequency, &adjacency);
+        grid.place_stamp(&GridPosition::new_xy(0, 0), &stamp(&[((0, 0), 0)]))
+            .unwrap();
+        let err = grid
+            .place_stamp(&GridPosition::new_xy(2, 0), &stamp(&[((0, 0), 1)]))
+            .unwrap_err();
+        assert!(!err.is_probabilistic());
+        assert_eq!(1, grid.retrieve_positions(true).len());
+        assert_eq!(15, grid.empty_positions().len());
+    }
+
+    #[test]
+    fn collapsed_tiles_carry_rotation() {
+        const GRASS_ID: u64 = 10;
+        let group = TileSymmetryGroup::new([4, 5, 6, 7]);
+        let tile = |id| GridTile::new(GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(id));
+        let mut frequency = FrequencyHints::default();
+        let mut adjacency = AdjacencyRules::default();
+        for id in [4, 5, 6, 7, GRASS_ID] {
+            frequency.set_weight_for_tile(&tile(id), 10);
+        }
+        for dir in GridDir::ALL_2D {
+            adjacency.add_adjacency(&tile(4), &tile(GRASS_ID), *dir);
+            adjacency.add_adjacency(&tile(GRASS_ID), &tile(4), *dir);
+            adjacency.add_adjacency(&tile(GRASS_ID), &tile(GRASS_ID), *dir);
+        }
+        adjacency.derive_symmetry_groups(&[group]);
+
+        let size = GridSize::new_xy(8, 8);
+        let mut grid = CollapsibleTileGrid::new_empty(size, &frequency, &adjacency)
+            .with_symmetry_groups(&[group]);
+        Resolver::default()
+            .generate_entrophy(
+                &mut grid,
+                &mut ChaChaRng::seed_from_u64(5),
+                &size.get_all_possible_positions(),
+            )
+            .unwrap();
+
+        let collapsed = grid.retrieve_collapsed();
+        let mut rotations = Vec::new();
+        for tile in collapsed.as_ref().iter_tiles() {
+            let rotation = tile.as_ref().rotation();
+            assert_eq!(group.rotation_of(tile.as_ref().tile_type_id()), rotation);
+            rotations.extend(rotation);
+        }
+        assert!(rotations.contains(&TileRotation::R90));
+        assert!(rotations.contains(&TileRotation::R270));
+    }
+}
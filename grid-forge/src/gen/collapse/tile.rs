@@ -1,13 +1,15 @@
 use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
 use crate::tile::identifiable::IdentifiableTileData;
-use crate::tile::TileData;
+use crate::tile::{TileData, TileRotation};
 
 /// Simple [`TileData`] containing only the `tile_type_id`.
 ///
 /// Identical in most cases to [`BasicIdentTileData`](crate::tile::identifiable::BasicIdentTileData), but used consistently within the
 /// collapse algorithms - both as input for some initial constraints for the generation process, and as an collapse process output.
+#[derive(Clone)]
 pub struct CollapsedTileData {
     tile_type_id: u64,
+    rotation: Option<TileRotation>,
 }
 
 impl TileData for CollapsedTileData {}
@@ -27,7 +29,25 @@ impl ConstructableViaIdentifierTile for CollapsedTileData {
 impl CollapsedTileData {
     #[inline]
     pub fn new(tile_type_id: u64) -> Self {
-        Self { tile_type_id }
+        Self {
+            tile_type_id,
+            rotation: None,
+        }
+    }
+
+    /// Sets the rotation of the tile.
+    pub fn with_rotation(mut self, rotation: TileRotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Returns the clockwise rotation of the tile in relation to the base rotation of its
+    /// [`TileSymmetryGroup`](crate::gen::collapse::singular::TileSymmetryGroup), if it is a part of some.
+    ///
+    /// Rotation is only a metadata for the consumers of the collapse output: tiles are identified solely by their
+    /// `tile_type_id`, so the rotation takes no part in comparing or hashing them.
+    pub fn rotation(&self) -> Option<TileRotation> {
+        self.rotation
     }
 }
 
@@ -65,13 +85,11 @@ pub trait CollapsibleTileData: TileData + private::Sealed {
 pub(crate) mod private {
     use std::collections::HashSet;
 
-    use rand::{
-        distributions::{Distribution, Uniform},
-        Rng,
-    };
-
     use crate::{
-        gen::collapse::option::{PerOptionData, WaysToBeOption},
+        gen::{
+            collapse::option::{PerOptionData, WaysToBeOption},
+            GridRng,
+        },
         map::{GridDir, GridMap2D},
         tile::{self, GridPosition, GridTile},
     };
@@ -92,12 +110,11 @@ pub(crate) mod private {
         ) -> GridTile<Self>;
 
         /// Creates vector of uncollapsed tiles with entrophy noise.
-        fn new_from_frequency_with_entrophy<R: Rng>(
+        fn new_from_frequency_with_entrophy<R: GridRng>(
             rng: &mut R,
             positions: &[GridPosition],
             options_data: &PerOptionData,
         ) -> Vec<GridTile<Self>> {
-            let rng_range = Self::entrophy_uniform();
             let ways_to_be_option = options_data.get_ways_to_become_option();
 
             let (weight_sum, weight_log_sum) = ways_to_be_option
@@ -119,7 +136,7 @@ pub(crate) mod private {
                         ways_to_be_option.clone(),
                         weight_sum,
                         weight_log_sum,
-                        rng_range.sample(rng),
+                        Self::entrophy_noise(rng),
                     )
                 })
                 .collect::<Vec<_>>()
@@ -166,12 +183,12 @@ pub(crate) mod private {
         /// Removes single option from tile.
         fn remove_option(&mut self, weights: (u32, f32));
 
-        /// Range of uniformly distributed data for entrophy noise.
-        fn entrophy_uniform() -> Uniform<f32> {
-            Uniform::<f32>::new(0., 0.00001)
+        /// Uniformly distributed entrophy noise, in `0.0..0.00001` range.
+        fn entrophy_noise<R: GridRng>(rng: &mut R) -> f32 {
+            rng.gen_range_f32(0. ..0.00001)
         }
 
-        fn collapse<R: Rng>(
+        fn collapse<R: GridRng>(
             &mut self,
             rng: &mut R,
             options_data: &PerOptionData,
@@ -182,7 +199,7 @@ pub(crate) mod private {
         fn weight_sum(&self) -> u32;
 
         /// Collapses tile into one of possible options, returning the vector of the removed options.
-        fn collapse_gather_removed<R: Rng>(
+        fn collapse_gather_removed<R: GridRng>(
             &mut self,
             rng: &mut R,
             options_data: &PerOptionData,
@@ -208,7 +225,7 @@ pub(crate) mod private {
         }
 
         /// Collapses tiles into one of possible options.
-        fn collapse_basic<R: Rng>(&mut self, rng: &mut R, options_data: &PerOptionData) {
+        fn collapse_basic<R: GridRng>(&mut self, rng: &mut R, options_data: &PerOptionData) {
             assert!(
                 self.weight_sum() > 0,
                 "weight sum should be positive when collapsing!"
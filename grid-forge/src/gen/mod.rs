@@ -1,4 +1,9 @@
 //! Generative algorithms for procedural generation of gridmaps.
 
 pub mod collapse;
+#[cfg(feature = "noise")]
+pub mod noise;
+mod rng;
 pub mod walker;
+
+pub use rng::GridRng;
@@ -0,0 +1,82 @@
+//! Heightmap-based generation using Perlin noise.
+//!
+//! Noise is sampled for every position of the grid and normalized into `0.0..=1.0` range. Resulting height is then
+//! translated into tile data using provided thresholds.
+
+use noise::{NoiseFn, Perlin};
+
+use crate::{
+    map::{GridMap2D, GridSize},
+    tile::TileData,
+};
+
+/// Scale applied to the grid coordinates before sampling the noise. Perlin noise is always `0.0` at integer
+/// coordinates, so they need to be scaled down to get a smooth heightmap.
+pub const NOISE_SCALE: f64 = 0.1;
+
+/// Generates [`GridMap2D`] of given `size` from Perlin noise heightmap.
+///
+/// For every position the height in `0.0..=1.0` range is sampled, and data from the first of `thresholds` with
+/// threshold value greater or equal to the height is inserted. Positions with height above all thresholds are left
+/// empty, so the last threshold should usually be `1.0`.
+pub fn heightmap_to_map<Data>(
+    size: GridSize,
+    seed: u32,
+    thresholds: &[(f32, Data)],
+) -> GridMap2D<Data>
+where
+    Data: TileData + Clone,
+{
+    let perlin = Perlin::new(seed);
+    let mut map = GridMap2D::new(size);
+
+    for position in size.get_all_possible_positions() {
+        let height = sample_height(&perlin, *position.x() as f64, *position.y() as f64);
+        if let Some((_, data)) = thresholds
+            .iter()
+            .find(|(threshold, _)| height <= *threshold)
+        {
+            map.insert_data(&position, data.clone());
+        }
+    }
+
+    map
+}
+
+fn sample_height(perlin: &Perlin, x: f64, y: f64) -> f32 {
+    let value = perlin.get([x * NOISE_SCALE, y * NOISE_SCALE]);
+    ((value + 1.) / 2.).clamp(0., 1.) as f32
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::GridSize;
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+
+    use super::heightmap_to_map;
+
+    #[test]
+    fn heightmap_proportions() {
+        let size = GridSize::new_xy(64, 64);
+        let thresholds = [
+            (0.4, BasicIdentTileData::tile_new(0)),
+            (0.6, BasicIdentTileData::tile_new(1)),
+            (1.0, BasicIdentTileData::tile_new(2)),
+        ];
+
+        let map = heightmap_to_map(size, 42, &thresholds);
+        let total = map.get_all_positions().len();
+        assert_eq!(64 * 64, total);
+
+        let mut counts = [0usize; 3];
+        for tile in map.iter_tiles() {
+            counts[tile.as_ref().tile_type_id() as usize] += 1;
+        }
+        let proportions = counts.map(|count| count as f32 / total as f32);
+
+        assert!((0.1..0.5).contains(&proportions[0]));
+        assert!((0.2..0.8).contains(&proportions[1]));
+        assert!((0.1..0.5).contains(&proportions[2]));
+    }
+}
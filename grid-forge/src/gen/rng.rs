@@ -0,0 +1,134 @@
+use std::ops::Range;
+
+use rand::distributions::{Distribution, Uniform, WeightedIndex};
+
+/// Source of randomness used by the generative algorithms.
+///
+/// It is implemented for every [`rand::Rng`], so any generator from the `rand` ecosystem can be used directly. Custom
+/// generators (eg. deterministic ones used in tests or replays) need to implement only [`next_u32`](Self::next_u32),
+/// as the rest of the methods have default implementations built on top of it.
+///
+/// Implementation for [`rand::Rng`] samples through the `rand` distributions, so the generators seeded the same way
+/// produce the same output as before the introduction of this trait. The default implementations don't follow them,
+/// so wrapping the `rand` generator in a custom implementation changes the output for the same seed.
+pub trait GridRng {
+    /// Returns next random `u32` value.
+    fn next_u32(&mut self) -> u32;
+
+    /// Returns random value from the `range`.
+    ///
+    /// # Panics
+    /// Panics if the `range` is empty.
+    fn gen_range(&mut self, range: Range<u32>) -> u32 {
+        assert!(!range.is_empty(), "cannot sample from empty range");
+        let len = (range.end - range.start) as u64;
+        range.start + ((self.next_u32() as u64 * len) >> 32) as u32
+    }
+
+    /// Returns random index from the `range`.
+    ///
+    /// # Panics
+    /// Panics if the `range` is empty.
+    fn gen_index(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "cannot sample from empty range");
+        range.start + self.gen_range(0..(range.end - range.start) as u32) as usize
+    }
+
+    /// Returns random index of the `weights`, with probability proportional to the weight.
+    ///
+    /// # Panics
+    /// Panics if the `weights` are empty or all of them are zero.
+    fn gen_weighted_index(&mut self, weights: &[u32]) -> usize {
+        let random = self.gen_range(0..weights.iter().sum());
+        let mut current_sum = 0;
+        for (idx, weight) in weights.iter().enumerate() {
+            current_sum += weight;
+            if random < current_sum {
+                return idx;
+            }
+        }
+        unreachable!("random value should always be lower than the weights sum")
+    }
+
+    /// Returns random value in `0.0..1.0` range.
+    fn gen_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns random value from the `range`.
+    ///
+    /// # Panics
+    /// Panics if the `range` is empty.
+    fn gen_range_f32(&mut self, range: Range<f32>) -> f32 {
+        assert!(!range.is_empty(), "cannot sample from empty range");
+        range.start + self.gen_f32() * (range.end - range.start)
+    }
+
+    /// Returns `true` with probability `p`.
+    fn gen_bool(&mut self, p: f64) -> bool {
+        (self.next_u32() as f64 / (u32::MAX as f64 + 1.)) < p
+    }
+}
+
+impl<R: rand::Rng> GridRng for R {
+    fn next_u32(&mut self) -> u32 {
+        rand::RngCore::next_u32(self)
+    }
+
+    fn gen_range(&mut self, range: Range<u32>) -> u32 {
+        rand::Rng::gen_range(self, range)
+    }
+
+    fn gen_index(&mut self, range: Range<usize>) -> usize {
+        Uniform::new(range.start, range.end).sample(self)
+    }
+
+    fn gen_weighted_index(&mut self, weights: &[u32]) -> usize {
+        WeightedIndex::<u32>::new(weights)
+            .expect("weights should be valid")
+            .sample(self)
+    }
+
+    fn gen_f32(&mut self) -> f32 {
+        rand::Rng::gen(self)
+    }
+
+    fn gen_range_f32(&mut self, range: Range<f32>) -> f32 {
+        rand::Rng::gen_range(self, range)
+    }
+
+    fn gen_bool(&mut self, p: f64) -> bool {
+        rand::Rng::gen_bool(self, p)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::distributions::{Distribution, Uniform, WeightedIndex};
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use super::GridRng;
+
+    #[test]
+    fn rand_generators_sample_through_rand_distributions() {
+        let mut grid_rng = ChaChaRng::seed_from_u64(3);
+        let mut rand_rng = ChaChaRng::seed_from_u64(3);
+        let weights = [1, 0, 5, 2];
+
+        for _ in 0..100 {
+            assert_eq!(
+                Uniform::new(2, 9).sample(&mut rand_rng),
+                grid_rng.gen_index(2..9)
+            );
+            assert_eq!(
+                WeightedIndex::new(weights).unwrap().sample(&mut rand_rng),
+                grid_rng.gen_weighted_index(&weights)
+            );
+            assert_eq!(
+                rand::Rng::gen_range(&mut rand_rng, 0. ..0.5),
+                grid_rng.gen_range_f32(0. ..0.5)
+            );
+        }
+    }
+}
@@ -1,26 +1,31 @@
-use rand::{
-    distributions::{Distribution, Uniform},
-    Rng,
-};
 use std::collections::HashSet;
 
 use crate::{
     error::BuilderError,
-    map::{GridDir, GridMap2D, GridSize},
+    gen::GridRng,
+    map::{DirectionTable, GridDir, GridMap2D, GridSize},
     tile::{GridPosition, GridTile, TileData},
 };
 
+/// Scale of the home bias strength per iteration, set in [`GridWalker2DBuilder::with_home_bias`].
+const HOME_BIAS_SCALE: f32 = 0.01;
+
 /// Struct implementing the random walker algorithm, producing the collection of [`GridPosition`]. To be created with
 /// [`GridWalker2DBuilder`].
 pub struct GridWalker2D<R>
 where
-    R: Rng,
+    R: GridRng,
 {
     current_pos: GridPosition,
     walked: HashSet<GridPosition>,
+    walked_order: Vec<GridPosition>,
     rng: R,
-    dir_rng: Uniform<usize>,
-    step_rng: Option<Uniform<usize>>,
+    dir_weights: Option<DirectionTable<u32>>,
+    step_range: Option<(usize, usize)>,
+    home: GridPosition,
+    home_bias: f32,
+    teleport_on_stuck: bool,
+    no_self_intersection: bool,
     size: GridSize,
     step_size: usize,
     iters: u32,
@@ -28,19 +33,26 @@ where
 
 impl<R> GridWalker2D<R>
 where
-    R: Rng,
+    R: GridRng,
 {
     /// Number of calls to the [Self::walk()] method.
     pub fn current_iters(&self) -> u32 {
         self.iters
     }
 
+    /// Makes single iteration of the walk: picks a [`GridDir`] and steps in it. Returns `false` if the step would leave
+    /// the grid, or enter already walked position if [`GridWalker2DBuilder::with_no_self_intersection`] was set,
+    /// leaving the walker in place.
+    ///
+    /// If the walker is boxed in - no direction allows it to make even the minimum step - and
+    /// [`GridWalker2DBuilder::with_teleport_on_stuck`] was set, it jumps to a random already walked position
+    /// instead, returning `true`.
     pub fn walk(&mut self) -> bool {
         self.iters += 1;
-        let idx: usize = self.dir_rng.sample(&mut self.rng);
+        let idx = self.pick_dir_idx();
 
-        let step_size = if let Some(step_size_rng) = self.step_rng {
-            step_size_rng.sample(&mut self.rng)
+        let step_size = if let Some((min, max)) = self.step_range {
+            self.rng.gen_index(min..max + 1)
         } else {
             self.step_size
         };
@@ -49,17 +61,19 @@ where
         let mut walked = Vec::new();
 
         for _ in 1..step_size {
-            if let Some(pos) = GridDir::ALL_2D[idx].march_step(&current_pos, &self.size) {
+            if let Some(pos) = self.march_step(GridDir::ALL_2D[idx], &current_pos) {
                 current_pos = pos;
                 walked.push(pos);
             } else {
-                return false;
+                return self.teleport_on_stuck && self.teleport_if_boxed_in();
             }
         }
 
         self.current_pos = current_pos;
         for walked_pos in walked.iter() {
-            self.walked.insert(*walked_pos);
+            if self.walked.insert(*walked_pos) {
+                self.walked_order.push(*walked_pos);
+            }
         }
         true
     }
@@ -68,6 +82,11 @@ where
         &self.walked
     }
 
+    /// All walked positions in order of their first visit, without duplicates.
+    pub fn walked_order(&self) -> &[GridPosition] {
+        &self.walked_order
+    }
+
     /// Generate [GridMap2D] out of gathered [GridPosition].
     ///
     /// # Arguments
@@ -85,6 +104,21 @@ where
         map
     }
 
+    /// Inserts tiles into existing [GridMap2D] at every walked [GridPosition], overwriting its content there. Positions
+    /// outside of the `map` size are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// - `func` - function which will generate the tile data for specified positions.
+    pub fn carve_into<Data>(&self, map: &mut GridMap2D<Data>, func: impl Fn(GridPosition) -> Data)
+    where
+        Data: TileData,
+    {
+        for pos in self.walked_order.iter() {
+            map.insert_data(pos, func(*pos));
+        }
+    }
+
     pub fn set_current_pos(&mut self, current_pos: GridPosition) {
         self.current_pos = current_pos;
     }
@@ -96,23 +130,94 @@ where
     pub fn reset(&mut self) {
         self.iters = 0;
         self.walked.clear();
+        self.walked_order.clear();
+    }
+
+    /// Jumps to a random walked position if no direction allows making the minimum step from the current position.
+    fn teleport_if_boxed_in(&mut self) -> bool {
+        let min_step_size = self
+            .step_range
+            .map(|(min, _)| min)
+            .unwrap_or(self.step_size);
+        let boxed_in = GridDir::ALL_2D.iter().all(|direction| {
+            (1..min_step_size)
+                .try_fold(self.current_pos, |pos, _| self.march_step(*direction, &pos))
+                .is_none()
+        });
+        if !boxed_in || self.walked_order.is_empty() {
+            return false;
+        }
+        let idx = self.rng.gen_range(0..self.walked_order.len() as u32) as usize;
+        self.current_pos = self.walked_order[idx];
+        true
+    }
+
+    /// Takes a single step in `direction`, returning `None` if it leads to forbidden position.
+    fn march_step(&self, direction: GridDir, from: &GridPosition) -> Option<GridPosition> {
+        direction
+            .march_step(from, &self.size)
+            .filter(|pos| !(self.no_self_intersection && self.walked.contains(pos)))
+    }
+
+    fn pick_dir_idx(&mut self) -> usize {
+        if let Some(idx) = self.pick_home_dir_idx() {
+            return idx;
+        }
+
+        match &self.dir_weights {
+            Some(weights) => self.rng.gen_weighted_index(weights.inner()),
+            None => self.rng.gen_index(0..GridDir::ALL_2D.len()),
+        }
+    }
+
+    /// Picks the direction towards the starting position with the probability growing with the iterations.
+    fn pick_home_dir_idx(&mut self) -> Option<usize> {
+        if self.home_bias <= 0. {
+            return None;
+        }
+        let probability = 1. - 1. / (1. + self.home_bias * HOME_BIAS_SCALE * self.iters as f32);
+        if self.rng.gen_f32() >= probability {
+            return None;
+        }
+
+        let (x, y) = self.current_pos.xy();
+        let (home_x, home_y) = self.home.xy();
+        let towards_home = GridDir::ALL_2D
+            .iter()
+            .enumerate()
+            .filter(|(_, dir)| match dir {
+                GridDir::UP => home_y < y,
+                GridDir::DOWN => home_y > y,
+                GridDir::LEFT => home_x < x,
+                GridDir::RIGHT => home_x > x,
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        if towards_home.is_empty() {
+            return None;
+        }
+        Some(towards_home[self.rng.gen_range(0..towards_home.len() as u32) as usize])
     }
 }
 
 pub struct GridWalker2DBuilder<R>
 where
-    R: Rng,
+    R: GridRng,
 {
     current_pos: Option<GridPosition>,
     rng: Option<R>,
     size: Option<GridSize>,
     min_step_size: usize,
     max_step_size: usize,
+    direction_weights: Option<DirectionTable<u32>>,
+    home_bias: f32,
+    teleport_on_stuck: bool,
+    no_self_intersection: bool,
 }
 
 impl<R> Default for GridWalker2DBuilder<R>
 where
-    R: Rng,
+    R: GridRng,
 {
     fn default() -> Self {
         Self {
@@ -121,13 +226,17 @@ where
             size: None,
             min_step_size: 1,
             max_step_size: 1,
+            direction_weights: None,
+            home_bias: 0.,
+            teleport_on_stuck: false,
+            no_self_intersection: false,
         }
     }
 }
 
 impl<R> GridWalker2DBuilder<R>
 where
-    R: Rng,
+    R: GridRng,
 {
     /// Set up starting position for the walker algorithm.
     pub fn with_current_pos(mut self, current_pos: GridPosition) -> Self {
@@ -135,7 +244,7 @@ where
         self
     }
 
-    /// Provide the [Rng] for random generation.
+    /// Provide the [GridRng] for random generation.
     pub fn with_rng(mut self, rng: R) -> Self {
         self.rng = Some(rng);
         self
@@ -153,6 +262,37 @@ where
         self
     }
 
+    /// Set up weights for each [GridDir] to be picked at every iteration. Without them, each direction is equally
+    /// probable. Weights summing to zero will result in error during [`build`](Self::build).
+    pub fn with_direction_weights(mut self, weights: DirectionTable<u32>) -> Self {
+        self.direction_weights = Some(weights);
+        self
+    }
+
+    /// Set up the bias towards the starting position, making the walked path tend to close into a loop. At every
+    /// iteration the Walker will step towards its starting position with probability growing with the number of
+    /// iterations, the faster the higher the `strength` is. Negative or non-finite strength will result in error during
+    /// [`build`](Self::build).
+    pub fn with_home_bias(mut self, strength: f32) -> Self {
+        self.home_bias = strength;
+        self
+    }
+
+    /// Set up whether the Walker should jump to a random already walked position when it gets boxed in, with no
+    /// direction allowing it to make the minimum step without leaving the grid or entering forbidden position. Without
+    /// it, the Walker stalls in such position until moved with [`GridWalker2D::set_current_pos`].
+    pub fn with_teleport_on_stuck(mut self, teleport: bool) -> Self {
+        self.teleport_on_stuck = teleport;
+        self
+    }
+
+    /// Set up whether the Walker should refuse to step into already walked positions, producing paths that never
+    /// cross themselves. Steps leading through walked positions fail the same as the ones leaving the grid.
+    pub fn with_no_self_intersection(mut self, enabled: bool) -> Self {
+        self.no_self_intersection = enabled;
+        self
+    }
+
     /// Set up [GridSize] for walker to walk inside.
     pub fn with_size(mut self, size: GridSize) -> Self {
         self.size = Some(size);
@@ -177,10 +317,22 @@ where
             error.add_missing_field("rng");
         }
 
+        let dir_weights_sum = self
+            .direction_weights
+            .as_ref()
+            .map(|weights| weights.inner().iter().sum())
+            .unwrap_or(0);
+        if self.direction_weights.is_some() && dir_weights_sum == 0 {
+            error.add_invalid_field("direction_weights");
+        }
+
+        if !(self.home_bias >= 0. && self.home_bias.is_finite()) {
+            error.add_invalid_field("home_bias");
+        }
+
         error.try_throw()?;
 
-        let dir_rng = rand::distributions::Uniform::new(0, GridDir::ALL_2D.len());
-        let step_rng = self.get_step_rng();
+        let step_range = self.get_step_range();
 
         let mut walked = HashSet::new();
         walked.insert(current_pos);
@@ -188,23 +340,258 @@ where
         Ok(GridWalker2D {
             current_pos,
             walked,
+            walked_order: vec![current_pos],
             rng: self.rng.unwrap(),
             size: self.size.unwrap(),
-            dir_rng,
-            step_rng,
+            dir_weights: self.direction_weights,
+            step_range,
+            home: current_pos,
+            home_bias: self.home_bias,
+            teleport_on_stuck: self.teleport_on_stuck,
+            no_self_intersection: self.no_self_intersection,
             step_size: self.min_step_size,
             iters: 0,
         })
     }
 
-    fn get_step_rng(&self) -> Option<Uniform<usize>> {
+    fn get_step_range(&self) -> Option<(usize, usize)> {
         if self.min_step_size == self.max_step_size {
             return None;
         }
 
-        Some(rand::distributions::Uniform::new(
-            self.min_step_size,
-            self.max_step_size + 1,
-        ))
+        Some((self.min_step_size, self.max_step_size))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::gen::GridRng;
+    use crate::map::{DirectionTable, GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::{GridPosition, TileContainer};
+
+    use super::GridWalker2DBuilder;
+
+    #[test]
+    fn carve_into_preserves_unwalked() {
+        let size = GridSize::new_xy(10, 10);
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(size)
+            .with_current_pos(GridPosition::new_xy(5, 5))
+            .with_rng(ChaChaRng::seed_from_u64(4))
+            .with_min_step_size(1)
+            .with_max_step_size(2)
+            .build()
+            .unwrap();
+        for _ in 0..15 {
+            walker.walk();
+        }
+
+        let mut map = GridMap2D::new(size);
+        map.fill_empty_with(BasicIdentTileData::tile_new(0));
+        walker.carve_into(&mut map, |_| BasicIdentTileData::tile_new(1));
+
+        for tile in map.iter_tiles() {
+            let expected = u64::from(walker.walked().contains(&tile.grid_position()));
+            assert_eq!(expected, tile.as_ref().tile_type_id());
+        }
+        assert_eq!(size.max_tile_count(), map.iter_tiles().count());
+        assert!(walker.walked().len() < size.max_tile_count());
+    }
+
+    #[test]
+    fn walks_only_in_weighted_direction() {
+        let start = GridPosition::new_xy(0, 5);
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(30, 10))
+            .with_current_pos(start)
+            .with_rng(ChaChaRng::seed_from_u64(1))
+            .with_min_step_size(2)
+            .with_max_step_size(2)
+            // UP, DOWN, LEFT, RIGHT
+            .with_direction_weights(DirectionTable::new_array([0, 0, 0, 1]))
+            .build()
+            .unwrap();
+
+        for _ in 0..20 {
+            assert!(walker.walk());
+        }
+
+        assert_eq!(GridPosition::new_xy(20, 5), walker.current_pos());
+        assert!(walker.walked().iter().all(|pos| pos.y() == start.y()));
+        assert_eq!(21, walker.walked().len());
+    }
+
+    #[test]
+    fn zero_direction_weights_fail_build() {
+        let result = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(10, 10))
+            .with_rng(ChaChaRng::seed_from_u64(1))
+            .with_direction_weights(DirectionTable::new_array([0, 0, 0, 0]))
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    /// Deterministic generator cycling through provided values.
+    struct CyclingRng {
+        values: Vec<u32>,
+        idx: usize,
+    }
+
+    impl GridRng for CyclingRng {
+        fn next_u32(&mut self) -> u32 {
+            let value = self.values[self.idx % self.values.len()];
+            self.idx += 1;
+            value
+        }
+    }
+
+    #[test]
+    fn walks_with_custom_rng() {
+        let start = GridPosition::new_xy(5, 5);
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(10, 10))
+            .with_current_pos(start)
+            // Always picks the last direction: RIGHT.
+            .with_rng(CyclingRng {
+                values: vec![u32::MAX],
+                idx: 0,
+            })
+            .with_min_step_size(2)
+            .with_max_step_size(2)
+            .build()
+            .unwrap();
+
+        while walker.walk() {}
+
+        assert_eq!(GridPosition::new_xy(9, 5), walker.current_pos());
+        assert_eq!(5, walker.walked().len());
+        assert!(walker.walked().iter().all(|pos| pos.y() == start.y()));
+    }
+
+    #[test]
+    fn walked_order_starts_at_start_without_duplicates() {
+        let start = GridPosition::new_xy(10, 10);
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(20, 20))
+            .with_current_pos(start)
+            .with_rng(ChaChaRng::seed_from_u64(3))
+            .with_min_step_size(2)
+            .with_max_step_size(4)
+            .build()
+            .unwrap();
+
+        for _ in 0..200 {
+            walker.walk();
+        }
+
+        let order = walker.walked_order();
+        assert_eq!(start, order[0]);
+        assert_eq!(walker.walked().len(), order.len());
+        assert!(order.iter().all(|pos| walker.walked().contains(pos)));
+    }
+
+    #[test]
+    fn home_bias_returns_near_start() {
+        let start = GridPosition::new_xy(15, 15);
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(30, 30))
+            .with_current_pos(start)
+            .with_rng(ChaChaRng::seed_from_u64(5))
+            .with_min_step_size(2)
+            .with_max_step_size(2)
+            .with_home_bias(50.)
+            .build()
+            .unwrap();
+
+        for _ in 0..300 {
+            walker.walk();
+        }
+
+        assert!(walker.current_pos().in_range(&start, 2));
+        assert!(walker.walked().len() > 1);
+    }
+
+    #[test]
+    fn invalid_home_bias_fails_build() {
+        for strength in [-1., f32::NAN, f32::INFINITY] {
+            let result = GridWalker2DBuilder::default()
+                .with_size(GridSize::new_xy(10, 10))
+                .with_rng(ChaChaRng::seed_from_u64(1))
+                .with_home_bias(strength)
+                .build();
+
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn teleport_on_stuck_keeps_progress() {
+        let walker_with = |teleport: bool| {
+            let mut walker = GridWalker2DBuilder::default()
+                .with_size(GridSize::new_xy(7, 7))
+                .with_current_pos(GridPosition::new_xy(0, 0))
+                .with_rng(ChaChaRng::seed_from_u64(9))
+                .with_min_step_size(5)
+                .with_max_step_size(5)
+                .with_teleport_on_stuck(teleport)
+                .build()
+                .unwrap();
+            for _ in 0..20 {
+                walker.walk();
+            }
+            // Every direction from the center requires stepping out of the grid.
+            walker.set_current_pos(GridPosition::new_xy(3, 3));
+            walker
+        };
+
+        let mut stalled = walker_with(false);
+        assert!((0..50).all(|_| !stalled.walk()));
+        assert_eq!(GridPosition::new_xy(3, 3), stalled.current_pos());
+
+        let mut teleporting = walker_with(true);
+        let moved = (0..50).filter(|_| teleporting.walk()).count();
+        assert!(moved > 0);
+        assert_ne!(GridPosition::new_xy(3, 3), teleporting.current_pos());
+    }
+
+    #[test]
+    fn no_self_intersection_never_revisits() {
+        let mut walker = GridWalker2DBuilder::default()
+            .with_size(GridSize::new_xy(12, 12))
+            .with_current_pos(GridPosition::new_xy(6, 6))
+            .with_rng(ChaChaRng::seed_from_u64(4))
+            .with_min_step_size(3)
+            .with_max_step_size(3)
+            .with_no_self_intersection(true)
+            .build()
+            .unwrap();
+
+        let mut successful = 0;
+        for _ in 0..100 {
+            let walked_before = walker.walked().len();
+            let order_before = walker.walked_order().len();
+            if walker.walk() {
+                successful += 1;
+                assert_eq!(walked_before + 2, walker.walked().len());
+                assert_eq!(order_before + 2, walker.walked_order().len());
+            } else {
+                assert_eq!(walked_before, walker.walked().len());
+            }
+        }
+        assert!(successful > 0);
+
+        // Every step continues the path from its previous end.
+        let path = walker.walked_order();
+        for pair in path.windows(2) {
+            let (x0, y0) = pair[0].xy();
+            let (x1, y1) = pair[1].xy();
+            assert_eq!(1, x0.abs_diff(x1) + y0.abs_diff(y1));
+        }
     }
 }
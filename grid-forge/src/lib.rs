@@ -165,6 +165,10 @@
 //!
 //! `IdentifiableTileData` is used by some built-in tile types used by different more specialized functionalities.
 //!
+//! With the `derive` feature flag enabled, the implementation can be derived with `#[derive(IdentifiableTileData)]`,
+//! taking the `tile_type_id` from the `#[tile_type_id(...)]` attribute on the struct or from its field marked with
+//! `#[tile_type_id]`.
+//!
 //! ### Visual representation of tiles
 //!
 //! There are some basic visualization methods provided within the `grid-forge`, lying under the `#[vis]` feature flag. These are mostly
@@ -207,6 +211,11 @@
 //! - basic *Random Walk algorithm* - see `gen_walker` example.
 //! - collapsible tile generation (Model Synthesis/Wave function collapse) - see `gen_collapse_overlap` and `gen_collapse_singular` examples.
 //!
+//! ### Testing
+//!
+//! With the `testing` feature flag enabled, the `testing` module provides helpers for asserting on the produced maps,
+//! eg. for regression tests of the generation code.
+//!
 //! ### Godot integration
 //!
 //! `godot` module contains a collection of structs allowing for easy roundtrips between Godot's and `grid-forge` data structures, using
@@ -236,3 +245,6 @@ pub mod vis;
 
 #[cfg(feature = "gen")]
 pub mod gen;
+
+#[cfg(feature = "testing")]
+pub mod testing;
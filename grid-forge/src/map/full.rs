@@ -0,0 +1,147 @@
+use crate::error::UnfilledMapError;
+use crate::tile::{GridPosition, TileData};
+
+use super::{GridMap2D, GridSize};
+
+/// Wrapper around [`GridMap2D`] guaranteeing that every position within its size is occupied.
+///
+/// As there are no empty positions, the accessors return the tile data directly instead of an [`Option`].
+/// It can be created only from the fully populated map, eg. after calling
+/// [`fill_empty_with`](GridMap2D::fill_empty_with) or [`fill_empty_using`](GridMap2D::fill_empty_using).
+pub struct FullGridMap<Data>
+where
+    Data: TileData,
+{
+    inner: GridMap2D<Data>,
+}
+
+impl<Data> FullGridMap<Data>
+where
+    Data: TileData,
+{
+    /// Wraps provided map. Returns an error listing all empty positions if the map is not fully populated.
+    pub fn new(map: GridMap2D<Data>) -> Result<Self, UnfilledMapError> {
+        let empty_positions = map.get_all_empty_positions();
+        if !empty_positions.is_empty() {
+            return Err(UnfilledMapError::new(empty_positions));
+        }
+        Ok(Self { inner: map })
+    }
+
+    pub fn size(&self) -> &GridSize {
+        self.inner.size()
+    }
+
+    /// Get tile data at specified position.
+    ///
+    /// # Panics
+    /// If the position is not valid within the size of the map.
+    pub fn get(&self, position: &GridPosition) -> &Data {
+        assert!(
+            self.inner.size.is_position_valid(position),
+            "position {position} is out of bounds!"
+        );
+        self.inner
+            .tiles
+            .get(*position.x(), *position.y())
+            .and_then(Option::as_ref)
+            .expect("`FullGridMap` should have all positions filled")
+    }
+
+    /// Get tile data at specified position mutably.
+    ///
+    /// # Panics
+    /// If the position is not valid within the size of the map.
+    pub fn get_mut(&mut self, position: &GridPosition) -> &mut Data {
+        assert!(
+            self.inner.size.is_position_valid(position),
+            "position {position} is out of bounds!"
+        );
+        self.inner
+            .tiles
+            .get_mut(*position.x(), *position.y())
+            .and_then(Option::as_mut)
+            .expect("`FullGridMap` should have all positions filled")
+    }
+
+    /// Replaces tile data at specified position, returning the previous one.
+    ///
+    /// # Panics
+    /// If the position is not valid within the size of the map.
+    pub fn replace(&mut self, position: &GridPosition, data: Data) -> Data {
+        std::mem::replace(self.get_mut(position), data)
+    }
+
+    /// Unwraps the inner [`GridMap2D`].
+    pub fn into_inner(self) -> GridMap2D<Data> {
+        self.inner
+    }
+}
+
+impl<Data> AsRef<GridMap2D<Data>> for FullGridMap<Data>
+where
+    Data: TileData,
+{
+    fn as_ref(&self) -> &GridMap2D<Data> {
+        &self.inner
+    }
+}
+
+impl<Data> TryFrom<GridMap2D<Data>> for FullGridMap<Data>
+where
+    Data: TileData,
+{
+    type Error = UnfilledMapError;
+
+    fn try_from(value: GridMap2D<Data>) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::{GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::GridPosition;
+
+    use super::FullGridMap;
+
+    #[test]
+    fn partially_filled_map_errors() {
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 2));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(1, 1), BasicIdentTileData::tile_new(1));
+
+        let err = FullGridMap::new(map).err().unwrap();
+        let mut empty = err.empty_positions().to_vec();
+        empty.sort();
+        assert_eq!(
+            vec![GridPosition::new_xy(0, 1), GridPosition::new_xy(1, 0)],
+            empty
+        );
+    }
+
+    #[test]
+    fn filled_map_gives_direct_access() {
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 2));
+        map.fill_empty_with(BasicIdentTileData::tile_new(1));
+
+        let mut full = FullGridMap::try_from(map).unwrap();
+        assert_eq!(1, full.get(&GridPosition::new_xy(1, 0)).tile_type_id());
+
+        *full.get_mut(&GridPosition::new_xy(1, 0)) = BasicIdentTileData::tile_new(2);
+        let previous = full.replace(&GridPosition::new_xy(0, 1), BasicIdentTileData::tile_new(3));
+        assert_eq!(1, previous.tile_type_id());
+
+        let map = full.into_inner();
+        assert_eq!(4, map.get_all_positions().len());
+        assert_eq!(
+            2,
+            map.get_tile_at_position(&GridPosition::new_xy(1, 0))
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::tile::{GridPosition, TileData};
+
+use super::{GridMap2D, GridSize};
+
+/// Stack of [`GridMap2D`] layers of the same size, accessible by their names.
+///
+/// Layers are indexed by their `z` coordinate in order of addition, but can be accessed by names for semantically
+/// different layers, eg. floor, decorations and entities.
+pub struct LayeredGridMap<Data>
+where
+    Data: TileData,
+{
+    size: GridSize,
+    names: Vec<String>,
+    layers: Vec<GridMap2D<Data>>,
+}
+
+impl<Data> LayeredGridMap<Data>
+where
+    Data: TileData,
+{
+    /// Creates new map without any layers. Every added layer will have provided `size`.
+    pub fn new(size: GridSize) -> Self {
+        Self {
+            size,
+            names: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> &GridSize {
+        &self.size
+    }
+
+    /// Adds new empty layer on top of existing ones, returning its `z` index. If the layer with provided `name`
+    /// already exists, its index is returned instead.
+    pub fn add_layer(&mut self, name: &str) -> u32 {
+        if let Some(z) = self.layer_index(name) {
+            return z;
+        }
+        self.names.push(name.to_owned());
+        self.layers.push(GridMap2D::new(self.size));
+        (self.layers.len() - 1) as u32
+    }
+
+    /// Returns the `z` index of the layer with provided `name`.
+    pub fn layer_index(&self, name: &str) -> Option<u32> {
+        self.names
+            .iter()
+            .position(|layer_name| layer_name == name)
+            .map(|z| z as u32)
+    }
+
+    /// Returns the name of the layer at provided `z` index.
+    pub fn layer_name(&self, z: u32) -> Option<&str> {
+        self.names.get(z as usize).map(String::as_str)
+    }
+
+    /// Get layer with provided `name`.
+    pub fn layer(&self, name: &str) -> Option<&GridMap2D<Data>> {
+        self.layers.get(self.layer_index(name)? as usize)
+    }
+
+    /// Get layer with provided `name` mutably.
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut GridMap2D<Data>> {
+        let z = self.layer_index(name)?;
+        self.layers.get_mut(z as usize)
+    }
+
+    /// Inserts data at the two-dimensional `position` of the layer with provided `name`. Returns `false` if there is
+    /// no such layer or the position is not valid within the map size.
+    pub fn insert_on_layer(&mut self, name: &str, position: &GridPosition, data: Data) -> bool {
+        self.layer_mut(name)
+            .is_some_and(|layer| layer.insert_data(position, data))
+    }
+
+    /// Iterates over all layers from the bottom, alongside their names.
+    pub fn iter_layers(&self) -> impl Iterator<Item = (&str, &GridMap2D<Data>)> {
+        self.names
+            .iter()
+            .map(String::as_str)
+            .zip(self.layers.iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::GridSize;
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::GridPosition;
+
+    use super::LayeredGridMap;
+
+    #[test]
+    fn insert_and_read_named_layers() {
+        let mut map = LayeredGridMap::new(GridSize::new_xy(4, 4));
+        assert_eq!(0, map.add_layer("floor"));
+        assert_eq!(1, map.add_layer("entities"));
+        assert_eq!(0, map.add_layer("floor"));
+
+        let position = GridPosition::new_xy(1, 2);
+        assert!(map.insert_on_layer("floor", &position, BasicIdentTileData::tile_new(1)));
+        assert!(map.insert_on_layer("entities", &position, BasicIdentTileData::tile_new(7)));
+        assert!(!map.insert_on_layer("decoration", &position, BasicIdentTileData::tile_new(3)));
+        assert!(!map.insert_on_layer(
+            "floor",
+            &GridPosition::new_xy(4, 0),
+            BasicIdentTileData::tile_new(1)
+        ));
+
+        let floor = map.layer("floor").unwrap();
+        assert_eq!(
+            1,
+            floor
+                .get_tile_at_position(&position)
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        );
+        assert_eq!(1, floor.iter_tiles().count());
+        let entities = map.layer("entities").unwrap();
+        assert_eq!(
+            7,
+            entities
+                .get_tile_at_position(&position)
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        );
+        assert!(map.layer("decoration").is_none());
+        assert_eq!(Some("entities"), map.layer_name(1));
+    }
+}
@@ -1,9 +1,24 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 
 use grid::Grid;
 
+use crate::error::SizeError;
+use crate::tile::identifiable::builders::{IdentTileBuilder, TileBuilderError};
+use crate::tile::identifiable::IdentifiableTileData;
 use crate::tile::{GridPosition, GridTile, GridTileRef, GridTileRefMut, TileContainer, TileData};
 
+mod full;
+mod layered;
+mod moore;
+mod sparse;
+
+pub use full::*;
+pub use layered::*;
+pub use moore::*;
+pub use sparse::*;
+
 #[repr(u8)]
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
 pub enum GridDir {
@@ -18,6 +33,9 @@ impl GridDir {
     pub const ALL_2D: &'static [GridDir; 4] =
         &[GridDir::UP, GridDir::DOWN, GridDir::LEFT, GridDir::RIGHT];
 
+    /// Directions covering every pair of neighbouring tiles exactly once, when stepped in from each tile.
+    pub const PRIMARY_2D: &'static [GridDir; 2] = &[GridDir::DOWN, GridDir::RIGHT];
+
     /// Take a step in specified direction from position within the contains of specified [GridSize].
     ///
     /// # Returns
@@ -73,6 +91,45 @@ impl GridDir {
         }
     }
 
+    /// Take a step in specified direction from position within the contains of specified [GridSize], wrapping around
+    /// to the opposite edge at the borders, as on a torus.
+    ///
+    /// # Returns
+    /// - resulting [GridPosition] after the step, or [None] if starting position is not valid within the specified size.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::{GridDir, GridPosition, GridSize};
+    ///
+    /// let size = GridSize::new_xy(3, 3);
+    /// let position = GridPosition::new_xy(0, 1);
+    ///
+    /// assert_eq!(Some(GridPosition::new_xy(2, 1)), GridDir::LEFT.march_step_wrapping(&position, &size));
+    /// assert_eq!(Some(GridPosition::new_xy(1, 1)), GridDir::RIGHT.march_step_wrapping(&position, &size));
+    /// ```
+    pub fn march_step_wrapping(
+        &self,
+        from: &GridPosition,
+        size: &GridSize,
+    ) -> Option<GridPosition> {
+        if !size.is_position_valid(from) {
+            return None;
+        }
+        let (x, y) = from.xy();
+        let (x, y) = match self {
+            GridDir::UP => (x, (y + size.y() - 1) % size.y()),
+            GridDir::DOWN => (x, (y + 1) % size.y()),
+            GridDir::LEFT => ((x + size.x() - 1) % size.x(), y),
+            GridDir::RIGHT => ((x + 1) % size.x(), y),
+        };
+
+        if let Some(z) = from.z() {
+            Some(GridPosition::new_xyz(x, y, *z))
+        } else {
+            Some(GridPosition::new_xy(x, y))
+        }
+    }
+
     /// Get opposite direction.
     ///
     /// # Examples
@@ -90,6 +147,42 @@ impl GridDir {
             GridDir::RIGHT => GridDir::LEFT,
         }
     }
+
+    /// Get direction rotated by 90 degrees clockwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::GridDir;
+    ///
+    /// assert_eq!(GridDir::RIGHT, GridDir::UP.rotate_cw())
+    /// ```
+    #[inline]
+    pub fn rotate_cw(&self) -> Self {
+        match self {
+            GridDir::UP => GridDir::RIGHT,
+            GridDir::RIGHT => GridDir::DOWN,
+            GridDir::DOWN => GridDir::LEFT,
+            GridDir::LEFT => GridDir::UP,
+        }
+    }
+
+    /// Get direction rotated by 90 degrees counter-clockwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::GridDir;
+    ///
+    /// assert_eq!(GridDir::LEFT, GridDir::UP.rotate_ccw())
+    /// ```
+    #[inline]
+    pub fn rotate_ccw(&self) -> Self {
+        match self {
+            GridDir::UP => GridDir::LEFT,
+            GridDir::LEFT => GridDir::DOWN,
+            GridDir::DOWN => GridDir::RIGHT,
+            GridDir::RIGHT => GridDir::UP,
+        }
+    }
 }
 
 /// Stores type in relation to each direction.
@@ -106,6 +199,13 @@ impl<T> DirectionTable<T> {
     pub(crate) fn inner(&self) -> &[T] {
         &self.table
     }
+
+    /// Creates new table by applying the function to the value stored for each direction.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> DirectionTable<U> {
+        DirectionTable {
+            table: self.table.each_ref().map(f),
+        }
+    }
 }
 
 impl<T: Default> Default for DirectionTable<T> {
@@ -130,7 +230,7 @@ impl<T> Index<GridDir> for DirectionTable<T> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GridSize {
     x: u32,
     y: u32,
@@ -159,6 +259,57 @@ impl GridSize {
         }
     }
 
+    /// Fallible version of [`new_xy`](Self::new_xy). Returns error if the number of positions within the size
+    /// doesn't fit in `usize`.
+    pub fn try_new_xy(x: u32, y: u32) -> Result<Self, SizeError> {
+        (x as usize)
+            .checked_mul(y as usize)
+            .ok_or_else(|| SizeError::new(x, y, None))?;
+        Ok(Self::new_xy(x, y))
+    }
+
+    /// Fallible version of [`new_xyz`](Self::new_xyz). Returns error if the number of positions within the size
+    /// doesn't fit in `usize`.
+    pub fn try_new_xyz(x: u32, y: u32, z: u32) -> Result<Self, SizeError> {
+        (x as usize)
+            .checked_mul(y as usize)
+            .and_then(|xy| xy.checked_mul(z as usize))
+            .ok_or_else(|| SizeError::new(x, y, Some(z)))?;
+        Ok(Self::new_xyz(x, y, z))
+    }
+
+    /// Creates square 2D size with both dimensions equal to `n`.
+    pub fn square(n: u32) -> Self {
+        Self::new_xy(n, n)
+    }
+
+    /// Creates cubic 3D size with all dimensions equal to `n`.
+    pub fn cube(n: u32) -> Self {
+        Self::new_xyz(n, n, n)
+    }
+
+    /// Returns new size with `x` dimension changed.
+    pub fn with_x(&self, x: u32) -> Self {
+        self.with_xy(x, self.y)
+    }
+
+    /// Returns new size with `y` dimension changed.
+    pub fn with_y(&self, y: u32) -> Self {
+        self.with_xy(self.x, y)
+    }
+
+    fn with_xy(&self, x: u32, y: u32) -> Self {
+        match self.z {
+            Some(z) => Self::new_xyz(x, y, z),
+            None => Self::new_xy(x, y),
+        }
+    }
+
+    /// Ratio of `x` to `y` dimension.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.x as f32 / self.y as f32
+    }
+
     pub fn x(&self) -> u32 {
         self.x
     }
@@ -167,6 +318,10 @@ impl GridSize {
         self.y
     }
 
+    pub fn z(&self) -> Option<u32> {
+        self.z
+    }
+
     pub fn center(&self) -> (u32, u32) {
         self.center
     }
@@ -180,6 +335,20 @@ impl GridSize {
         self.x <= other.x && self.y <= other.y && self.z <= other.z
     }
 
+    /// Checks if the region of `size` placed with its upper-left corner at `origin` stays within the bounds of `self`.
+    /// Regions touching the edges of `self` still fit. Missing `z` dimension of either size is treated as depth of
+    /// `1`, and missing `z` coordinate of `origin` as `0`.
+    pub fn can_place(&self, origin: &GridPosition, size: &Self) -> bool {
+        let fits = |origin: u32, size: u32, bound: u32| origin as u64 + size as u64 <= bound as u64;
+        fits(*origin.x(), size.x, self.x)
+            && fits(*origin.y(), size.y, self.y)
+            && fits(
+                origin.z().unwrap_or(0),
+                size.z.unwrap_or(1),
+                self.z.unwrap_or(1),
+            )
+    }
+
     pub fn get_all_possible_positions(&self) -> Vec<GridPosition> {
         let mut out = Vec::new();
 
@@ -202,6 +371,61 @@ impl GridSize {
         out
     }
 
+    /// Number of all positions within the size.
+    ///
+    /// # Panics
+    /// Can overflow for sizes created with unchecked constructors, which panics in debug builds. Use
+    /// [`try_new_xy`](Self::try_new_xy) or [`try_new_xyz`](Self::try_new_xyz) to rule it out upfront.
+    pub fn max_tile_count(&self) -> usize {
+        self.x as usize * self.y as usize * self.z.unwrap_or(1) as usize
+    }
+
+    /// Index of the `position` in the storage backing the [`GridMap2D`]: positions are laid out in rows along the `y`
+    /// axis, for consecutive `x` coordinates, with layers for consecutive `z` coordinates.
+    ///
+    /// Returns `None` if position is not valid within the size.
+    pub fn offset(&self, position: &GridPosition) -> Option<usize> {
+        if !self.is_position_valid(position) {
+            return None;
+        }
+        let layer = match (self.z, position.z()) {
+            (Some(z_size), Some(z)) if *z < z_size => *z as usize,
+            (None, None) => 0,
+            _ => return None,
+        };
+        let (x, y) = position.xy();
+        Some((layer * self.x as usize + x as usize) * self.y as usize + y as usize)
+    }
+
+    /// Position at the `offset` in the storage backing the [`GridMap2D`], reverse of [`offset`](Self::offset).
+    ///
+    /// Returns `None` if the offset is not lesser than [`max_tile_count`](Self::max_tile_count).
+    pub fn pos_from_offset(&self, offset: usize) -> Option<GridPosition> {
+        if offset >= self.max_tile_count() {
+            return None;
+        }
+        let (x_size, y_size) = (self.x as usize, self.y as usize);
+        let (x, y) = ((offset / y_size % x_size) as u32, (offset % y_size) as u32);
+        Some(match self.z {
+            Some(_) => GridPosition::new_xyz(x, y, (offset / (x_size * y_size)) as u32),
+            None => GridPosition::new_xy(x, y),
+        })
+    }
+
+    /// Iterates over all positions within the size in order of their [`offset`](Self::offset), matching the layout of
+    /// the storage backing the [`GridMap2D`].
+    pub fn iter_positions_offset_order(&self) -> impl Iterator<Item = GridPosition> {
+        let (x_size, y_size, z_size) = (self.x, self.y, self.z);
+        (0..z_size.unwrap_or(1)).flat_map(move |z| {
+            (0..x_size).flat_map(move |x| {
+                (0..y_size).map(move |y| match z_size {
+                    Some(_) => GridPosition::new_xyz(x, y, z),
+                    None => GridPosition::new_xy(x, y),
+                })
+            })
+        })
+    }
+
     /// Get Position distance from border
     pub fn distance_from_border(&self, position: &GridPosition) -> u32 {
         *[
@@ -229,6 +453,124 @@ impl GridSize {
         })
     }
 
+    /// Returns all positions ordered from the center outwards: by the distance of the square ring around the center
+    /// they lie on, with ties broken by the [`GridPosition`] order for determinism. Feeding them to the
+    /// position-based generation makes it grow from the middle of the map.
+    ///
+    /// Contrary to [`distance_from_center`](Self::distance_from_center), which is the distance from the central row or
+    /// column, the ring distance is the greater of the distances along both axes.
+    pub fn positions_center_out(&self) -> Vec<GridPosition> {
+        let mut positions = self.get_all_possible_positions();
+        positions.sort_by_key(|position| {
+            let ring = position
+                .x()
+                .abs_diff(self.center.0)
+                .max(position.y().abs_diff(self.center.1));
+            (ring, *position)
+        });
+        positions
+    }
+
+    /// Returns the corner positions of the grid: 4 for 2D sizes and 8 for 3D ones, listed layer by layer in reading
+    /// order. Corners shared by degenerate sizes (eg. one tile wide) are listed once, and empty sizes have none.
+    pub fn corners(&self) -> Vec<GridPosition> {
+        self.anchor_positions(
+            |x, y| [(0, 0), (x, 0), (0, y), (x, y)].to_vec(),
+            |z| vec![0, z],
+        )
+    }
+
+    /// Returns the positions in the middle of each edge of the grid, with the middle taken the same way as
+    /// [`center`](Self::center). For 2D sizes these are the 4 midpoints of the rectangle sides; for 3D sizes the 12
+    /// midpoints of the box edges, listed layer by layer in reading order. Duplicates are listed once.
+    pub fn edge_midpoints(&self) -> Vec<GridPosition> {
+        let (cx, cy) = self.center;
+        let mut out = self.anchor_positions(
+            |x, y| [(cx, 0), (0, cy), (x, cy), (cx, y)].to_vec(),
+            |z| vec![0, z],
+        );
+        if let Some(z) = self.z {
+            for position in self.anchor_positions(
+                |x, y| [(0, 0), (x, 0), (0, y), (x, y)].to_vec(),
+                |_| vec![z / 2],
+            ) {
+                if !out.contains(&position) {
+                    out.push(position);
+                }
+            }
+            out.sort_by_key(|position| position.z().unwrap_or_default());
+        }
+        out
+    }
+
+    /// Builds the positions from `xy` pairs, produced for the maximum `x` and `y` coordinates, on each layer returned
+    /// for the maximum `z` coordinate by `layers`, skipping duplicates.
+    fn anchor_positions(
+        &self,
+        xy: impl Fn(u32, u32) -> Vec<(u32, u32)>,
+        layers: impl Fn(u32) -> Vec<u32>,
+    ) -> Vec<GridPosition> {
+        let mut out = Vec::new();
+        if self.max_tile_count() == 0 {
+            return out;
+        }
+        let pairs = xy(self.x - 1, self.y - 1);
+        let layers = match self.z {
+            Some(z) => layers(z - 1).into_iter().map(Some).collect(),
+            None => vec![None],
+        };
+        for z in layers {
+            for (x, y) in pairs.iter().copied() {
+                let position = match z {
+                    Some(z) => GridPosition::new_xyz(x, y, z),
+                    None => GridPosition::new_xy(x, y),
+                };
+                if !out.contains(&position) {
+                    out.push(position);
+                }
+            }
+        }
+        out
+    }
+
+    /// Calculates the overlapping region of two boxes: one of `a_size` placed at `a_origin` and another of `b_size`
+    /// placed at `b_origin`. Returns the origin and size of the intersection, or `None` if the boxes are disjoint.
+    ///
+    /// The `z` axis is taken into account only if both sizes have it specified - the missing `z` origin is then
+    /// treated as `0`.
+    pub fn rect_intersection(
+        a_origin: &GridPosition,
+        a_size: &GridSize,
+        b_origin: &GridPosition,
+        b_size: &GridSize,
+    ) -> Option<(GridPosition, GridSize)> {
+        let axis = |a_start: u32, a_len: u32, b_start: u32, b_len: u32| {
+            let start = a_start.max(b_start);
+            // Computed in `u64`, as the boxes can reach past `u32::MAX`. The overlap length can't exceed either of
+            // the lengths, so it fits back in `u32`.
+            let end = (a_start as u64 + a_len as u64).min(b_start as u64 + b_len as u64);
+            ((start as u64) < end).then_some((start, (end - start as u64) as u32))
+        };
+
+        let (x, x_len) = axis(*a_origin.x(), a_size.x, *b_origin.x(), b_size.x)?;
+        let (y, y_len) = axis(*a_origin.y(), a_size.y, *b_origin.y(), b_size.y)?;
+
+        if let (Some(a_z_len), Some(b_z_len)) = (a_size.z, b_size.z) {
+            let (z, z_len) = axis(
+                a_origin.z().unwrap_or_default(),
+                a_z_len,
+                b_origin.z().unwrap_or_default(),
+                b_z_len,
+            )?;
+            return Some((
+                GridPosition::new_xyz(x, y, z),
+                GridSize::new_xyz(x_len, y_len, z_len),
+            ));
+        }
+
+        Some((GridPosition::new_xy(x, y), GridSize::new_xy(x_len, y_len)))
+    }
+
     fn calc_center_approx(x: u32, y: u32) -> (u32, u32) {
         (x / 2, y / 2)
     }
@@ -315,6 +657,26 @@ impl<Data: TileData> GridMap2D<Data> {
         true
     }
 
+    /// Inserts data at `position` like [`insert_data`](Self::insert_data), but if the position is already occupied,
+    /// the stored data is computed by `merge` out of the existing and the inserted data instead of overwriting.
+    pub fn insert_data_merge(
+        &mut self,
+        position: &GridPosition,
+        data: Data,
+        merge: impl Fn(&Data, Data) -> Data,
+    ) -> bool {
+        if !self.size.is_position_valid(position) {
+            return false;
+        }
+        let (x, y) = position.xy();
+        let t = self.tiles.get_mut(x, y).unwrap();
+        *t = Some(match t.take() {
+            Some(existing) => merge(&existing, data),
+            None => data,
+        });
+        true
+    }
+
     pub fn remove_tile_at_position(&mut self, position: &GridPosition) -> bool {
         if !self.size.is_position_valid(position) {
             return false;
@@ -349,6 +711,113 @@ impl<Data: TileData> GridMap2D<Data> {
         None
     }
 
+    /// Get tiles neighbouring the specified position, wrapping around the map edges. See
+    /// [`GridDir::march_step_wrapping`].
+    pub fn get_neighbours_wrapping(&self, position: &GridPosition) -> Vec<GridTileRef<Data>> {
+        GridDir::ALL_2D
+            .iter()
+            .filter_map(|direction| self.get_neighbour_wrapping(position, direction))
+            .collect::<Vec<_>>()
+    }
+
+    /// Get tile neighbouring the specified position at specified direction, wrapping around the map edges. See
+    /// [`GridDir::march_step_wrapping`].
+    pub fn get_neighbour_wrapping(
+        &self,
+        position: &GridPosition,
+        direction: &GridDir,
+    ) -> Option<GridTileRef<Data>> {
+        let position = direction.march_step_wrapping(position, &self.size)?;
+        self.get_tile_at_position(&position)
+    }
+
+    /// Provides mutable access to the tile data at specified position alongside immutable access to its occupied
+    /// neighbours, which is not possible with the single-tile accessors.
+    ///
+    /// Returns `None` without calling `f` if the position is empty or out of bounds.
+    pub fn with_neighbourhood_mut<R>(
+        &mut self,
+        position: &GridPosition,
+        f: impl FnOnce(&mut Data, &[(GridDir, &Data)]) -> R,
+    ) -> Option<R> {
+        if !self.size.is_position_valid(position) {
+            return None;
+        }
+        let (x, y) = position.xy();
+        // Center is taken out of the grid for the duration of the call, so the neighbours can be borrowed.
+        let mut center = self.tiles.get_mut(x, y).unwrap().take()?;
+
+        let neighbours = GridDir::ALL_2D
+            .iter()
+            .filter_map(|direction| {
+                let neighbour = direction.march_step(position, &self.size)?;
+                self.tiles
+                    .get(*neighbour.x(), *neighbour.y())
+                    .unwrap()
+                    .as_ref()
+                    .map(|data| (*direction, data))
+            })
+            .collect::<Vec<_>>();
+        let result = f(&mut center, &neighbours);
+
+        *self.tiles.get_mut(x, y).unwrap() = Some(center);
+        Some(result)
+    }
+
+    /// Creates a new map of the same size, where each position holds the result of `f` called with the data at the
+    /// same position of this map and the data of its neighbours, making it easy to implement single steps of cellular
+    /// automata. Neighbours are passed in the [`GridDir::ALL_2D`] order, with `None` for the empty and out of bounds
+    /// positions.
+    pub fn transform_with_neighbours(
+        &self,
+        f: impl Fn(Option<&Data>, &[Option<&Data>]) -> Option<Data>,
+    ) -> Self {
+        let mut out = Self::new(self.size);
+        let mut neighbours = Vec::with_capacity(GridDir::ALL_2D.len());
+
+        for position in self.size.get_all_possible_positions() {
+            neighbours.clear();
+            neighbours.extend(GridDir::ALL_2D.iter().map(|direction| {
+                let neighbour = direction.march_step(&position, &self.size)?;
+                self.tiles
+                    .get(*neighbour.x(), *neighbour.y())
+                    .and_then(Option::as_ref)
+            }));
+            let current = self
+                .tiles
+                .get(*position.x(), *position.y())
+                .and_then(Option::as_ref);
+            if let Some(data) = f(current, &neighbours) {
+                out.insert_data(&position, data);
+            }
+        }
+        out
+    }
+
+    /// Get bitmask of occupied neighbours of the specified position. Bit `1 << (direction as u8)` is set if the
+    /// neighbour in given [`GridDir`] is occupied.
+    ///
+    /// Can be used in place of [`get_neighbours`](Self::get_neighbours) in tight loops, as it doesn't allocate.
+    pub fn occupied_neighbour_mask(&self, position: &GridPosition) -> u8 {
+        let mut mask = 0;
+        for direction in GridDir::ALL_2D {
+            if self.get_neighbour_at(position, direction).is_some() {
+                mask |= 1 << (*direction as u8);
+            }
+        }
+        mask
+    }
+
+    /// Get number of occupied neighbours of the specified position, without allocating.
+    ///
+    /// For the *Moore neighbourhood* see [`occupied_moore_neighbour_count`](Self::occupied_moore_neighbour_count).
+    pub fn occupied_neighbour_count(&self, position: &GridPosition) -> usize {
+        GridDir::ALL_2D
+            .iter()
+            .filter(|direction| self.get_neighbour_at(position, direction).is_some())
+            .count()
+    }
+
     pub fn get_mut_neighbour_at(
         &mut self,
         position: &GridPosition,
@@ -404,6 +873,17 @@ impl<Data: TileData> GridMap2D<Data> {
             .collect::<Vec<GridPosition>>()
     }
 
+    /// Get positions of all tiles which data satisfies the provided predicate.
+    pub fn positions_where(&self, pred: impl Fn(&Data) -> bool) -> Vec<GridPosition> {
+        self.tiles
+            .indexed_iter()
+            .filter_map(|(pos, t)| match t {
+                Some(data) if pred(data) => Some(GridPosition::new_xy(pos.0 as u32, pos.1 as u32)),
+                _ => None,
+            })
+            .collect::<Vec<GridPosition>>()
+    }
+
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Option<Data>> {
         self.tiles.iter_mut()
     }
@@ -415,6 +895,55 @@ impl<Data: TileData> GridMap2D<Data> {
         })
     }
 
+    /// Iterates over all tiles in parallel, using [`rayon`] over the internal storage. Order of the tiles is not
+    /// guaranteed.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_tiles(&self) -> impl rayon::iter::ParallelIterator<Item = GridTileRef<'_, Data>>
+    where
+        Data: Sync,
+    {
+        use rayon::prelude::*;
+
+        // Internal grid is stored in row-major order, with rows along the `x` axis.
+        let cols = self.size.y() as usize;
+        self.tiles
+            .iter()
+            .as_slice()
+            .par_iter()
+            .enumerate()
+            .filter_map(move |(idx, data)| {
+                data.as_ref().map(|d| {
+                    GridTileRef::new(
+                        GridPosition::new_xy((idx / cols) as u32, (idx % cols) as u32),
+                        d,
+                    )
+                })
+            })
+    }
+
+    /// Iterates over all tiles in ascending [`GridPosition`] order, providing canonical order independent of the
+    /// internal storage layout.
+    pub fn iter_tiles_sorted(&self) -> impl Iterator<Item = GridTileRef<Data>> {
+        let mut tiles = self.iter_tiles().collect::<Vec<_>>();
+        tiles.sort_by_key(|tile| tile.grid_position());
+        tiles.into_iter()
+    }
+
+    /// Iterates over every pair of adjacent tiles exactly once, alongside the direction from the first tile to the
+    /// second one, which is always one of the [`GridDir::PRIMARY_2D`].
+    pub fn adjacent_pairs(
+        &self,
+    ) -> impl Iterator<Item = (GridTileRef<'_, Data>, GridDir, GridTileRef<'_, Data>)> {
+        self.iter_tiles().flat_map(move |tile| {
+            let position = tile.grid_position();
+            GridDir::PRIMARY_2D.iter().filter_map(move |direction| {
+                let neighbour = self.get_neighbour_at(&position, direction)?;
+                let tile = self.get_tile_at_position(&position)?;
+                Some((tile, *direction, neighbour))
+            })
+        })
+    }
+
     pub fn iter_mut_tiles(&mut self) -> impl Iterator<Item = GridTileRefMut<Data>> {
         self.tiles.indexed_iter_mut().filter_map(|(pos, data)| {
             data.as_mut()
@@ -422,6 +951,167 @@ impl<Data: TileData> GridMap2D<Data> {
         })
     }
 
+    /// Iterates mutably over the tiles within the rectangle spanned between `from` and `to` (inclusive), clipped to
+    /// the map size. Only the slots inside the region are visited, so it is cheap for small regions of large maps.
+    pub fn iter_mut_region(
+        &mut self,
+        from: &GridPosition,
+        to: &GridPosition,
+    ) -> impl Iterator<Item = GridTileRefMut<Data>> {
+        // Ranges are clipped to the map size on both ends, so regions reaching outside the map stay valid.
+        let clip = |from: u32, to: u32, bound: u32| {
+            let start = from.min(to).min(bound);
+            start..from.max(to).saturating_add(1).clamp(start, bound)
+        };
+        let x_range = clip(*from.x(), *to.x(), self.size.x());
+        let y_range = clip(*from.y(), *to.y(), self.size.y());
+        let cols = self.size.y() as usize;
+
+        // Internal grid is stored in row-major order, with rows along the `x` axis.
+        self.tiles
+            .iter_mut()
+            .into_slice()
+            .chunks_mut(cols.max(1))
+            .enumerate()
+            .skip(x_range.start as usize)
+            .take(x_range.len())
+            .flat_map(move |(x, row)| {
+                let y_start = y_range.start;
+                row[y_range.start as usize..y_range.end as usize]
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(move |(y_offset, data)| {
+                        data.as_mut().map(|d| {
+                            GridTileRefMut::new(
+                                GridPosition::new_xy(x as u32, y_start + y_offset as u32),
+                                d,
+                            )
+                        })
+                    })
+            })
+    }
+
+    /// Calculates the distance from each reachable position to the nearest of `sources`, using the multi-source
+    /// breadth-first search through the tiles for which `passable` returns `true`. Empty positions are never passable.
+    ///
+    /// Sources always have distance of `0`, while the empty ones and these out of the map bounds are ignored.
+    /// Unreachable positions are omitted.
+    pub fn distance_field(
+        &self,
+        sources: &[GridPosition],
+        passable: impl Fn(&Data) -> bool,
+    ) -> HashMap<GridPosition, u32> {
+        self.bfs_from(sources, |_, _, _, next| passable(next))
+            .collect()
+    }
+
+    /// Lazily traverses the filled tiles reachable from `seeds` in breadth-first order, yielding each position with
+    /// its distance to the nearest seed.
+    ///
+    /// Step from the current tile to the neighbouring one is taken only if `step_allowed` returns `true`, receiving
+    /// the position and data of both the current and the candidate tile. Empty positions are never visited, and
+    /// neither are seeds which are empty or out of the map bounds.
+    pub fn bfs_from<'a>(
+        &'a self,
+        seeds: &[GridPosition],
+        step_allowed: impl Fn(&GridPosition, &Data, &GridPosition, &Data) -> bool + 'a,
+    ) -> impl Iterator<Item = (GridPosition, u32)> + 'a {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        for seed in seeds {
+            if self.get_tile_at_position(seed).is_some() && visited.insert(*seed) {
+                queue.push_back((*seed, 0));
+            }
+        }
+
+        std::iter::from_fn(move || {
+            let (position, distance) = queue.pop_front()?;
+            let current = self.get_tile_at_position(&position)?;
+            for direction in GridDir::ALL_2D {
+                let Some(next) = direction.march_step(&position, &self.size) else {
+                    continue;
+                };
+                if visited.contains(&next) {
+                    continue;
+                }
+                let Some(candidate) = self.get_tile_at_position(&next) else {
+                    continue;
+                };
+                if step_allowed(&position, current.as_ref(), &next, candidate.as_ref()) {
+                    visited.insert(next);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+            Some((position, distance))
+        })
+    }
+
+    /// Checks if all tiles for which `passable` returns `true` form a single region, connected through their
+    /// orthogonal neighbours. Map without passable tiles is deemed connected.
+    pub fn is_connected(&self, passable: impl Fn(&Data) -> bool) -> bool {
+        let mut passable_tiles = self.iter_tiles().filter(|tile| passable(tile.as_ref()));
+        let Some(first) = passable_tiles.next() else {
+            return true;
+        };
+        let reached = self
+            .bfs_from(&[first.grid_position()], |_, _, _, next| passable(next))
+            .count();
+        reached == passable_tiles.count() + 1
+    }
+
+    /// Returns positions of the largest region of tiles for which `passable` returns `true`, connected through their
+    /// orthogonal neighbours. Positions are in breadth-first order, and if there are multiple largest regions the
+    /// first encountered one is returned.
+    pub fn largest_connected_region(&self, passable: impl Fn(&Data) -> bool) -> Vec<GridPosition> {
+        let mut visited = HashSet::new();
+        let mut largest = Vec::new();
+
+        for tile in self.iter_tiles() {
+            if !passable(tile.as_ref()) || visited.contains(&tile.grid_position()) {
+                continue;
+            }
+            let region = self
+                .bfs_from(&[tile.grid_position()], |_, _, _, next| passable(next))
+                .map(|(position, _)| position)
+                .collect::<Vec<_>>();
+            visited.extend(region.iter().copied());
+            if region.len() > largest.len() {
+                largest = region;
+            }
+        }
+
+        largest
+    }
+
+    /// Iterates over the rows of the map top-to-bottom, each yielding its slots left-to-right.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Option<&Data>>> {
+        (0..self.size.y())
+            .map(move |y| (0..self.size.x()).map(move |x| self.tiles.get(x, y).unwrap().as_ref()))
+    }
+
+    /// Iterates over the columns of the map left-to-right, each yielding its slots top-to-bottom.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Option<&Data>>> {
+        (0..self.size.x())
+            .map(move |x| (0..self.size.y()).map(move |y| self.tiles.get(x, y).unwrap().as_ref()))
+    }
+
+    /// Renders the map as text, mapping each cell to a character with `f`, which receives `None` for empty cells.
+    /// Rows are rendered top-to-bottom and separated by newlines.
+    ///
+    /// Useful for quick debugging in the terminal.
+    pub fn to_ascii(&self, f: impl Fn(Option<&Data>) -> char) -> String {
+        let mut rows = Vec::with_capacity(self.size.y() as usize);
+        for y in 0..self.size.y() {
+            rows.push(
+                (0..self.size.x())
+                    .map(|x| f(self.tiles.get(x, y).unwrap().as_ref()))
+                    .collect::<String>(),
+            );
+        }
+        rows.join("\n")
+    }
+
     /// Destroys the GridMap, returning all tiles with their position adjusted in relation to the `anchor_pos`.
     pub fn drain_remapped(mut self, anchor_pos: GridPosition) -> Vec<GridTile<Data>> {
         self.get_all_positions()
@@ -450,27 +1140,428 @@ impl<Data: TileData> GridMap2D<Data> {
             .collect()
     }
 
+    /// Takes all tiles within the rectangle spanned between `from` and `to` (inclusive), clipped to the map size,
+    /// out of the map. Positions within the region are left empty, while the rest of the map is intact.
+    pub fn drain_region(&mut self, from: &GridPosition, to: &GridPosition) -> Vec<GridTile<Data>> {
+        let x_range =
+            *from.x().min(to.x())..from.x().max(to.x()).saturating_add(1).min(self.size.x());
+        let y_range =
+            *from.y().min(to.y())..from.y().max(to.y()).saturating_add(1).min(self.size.y());
+
+        let mut out = Vec::new();
+        for x in x_range {
+            for y in y_range.clone() {
+                if let Some(data) = self.tiles.get_mut(x, y).and_then(Option::take) {
+                    out.push(GridTile::new(GridPosition::new_xy(x, y), data));
+                }
+            }
+        }
+        out
+    }
+
     /// Fills empty positions using constructor function.
     pub fn fill_empty_using(&mut self, func: fn(GridPosition) -> GridTile<Data>) {
         for position in self.get_all_empty_positions() {
             self.insert_tile(func(position));
         }
     }
-}
 
-impl<Data: TileData + Default> GridMap2D<Data> {
-    pub fn fill_empty_with_default(&mut self) {
-        for pos in self.get_all_empty_positions() {
-            self.insert_data(&pos, Data::default());
+    /// Fills all positions with [`distance_from_border`](GridSize::distance_from_border) within `inner..=outer`
+    /// using constructor function, overwriting existing tiles. Useful for arena borders and moats.
+    pub fn fill_annulus(&mut self, inner: u32, outer: u32, func: impl Fn(GridPosition) -> Data) {
+        for position in self.size.get_all_possible_positions() {
+            if (inner..=outer).contains(&self.size.distance_from_border(&position)) {
+                self.insert_data(&position, func(position));
+            }
         }
     }
-}
 
-impl<Data: TileData + Clone> GridMap2D<Data> {
-    pub fn fill_empty_with(&mut self, tile: Data) {
-        for pos in self.get_all_empty_positions() {
-            self.insert_data(&pos, tile.clone());
-        }
+    /// Pastes provided `tiles` into the map, with their positions shifted by `offset`. Tiles which would land out
+    /// of bounds are skipped, and already occupied positions are overwritten only if `overwrite` is `true`.
+    ///
+    /// Returns the number of inserted tiles.
+    pub fn paste(
+        &mut self,
+        tiles: impl IntoIterator<Item = (GridPosition, Data)>,
+        offset: GridPosition,
+        overwrite: bool,
+    ) -> usize {
+        let mut inserted = 0;
+        for (position, data) in tiles {
+            let position = position + offset;
+            if !self.size.is_position_valid(&position) {
+                continue;
+            }
+            let (x, y) = position.xy();
+            let t = self.tiles.get_mut(x, y).unwrap();
+            if t.is_some() && !overwrite {
+                continue;
+            }
+            *t = Some(data);
+            inserted += 1;
+        }
+        inserted
+    }
+
+    /// Rotates the map by 90 degrees clockwise in place, moving the tiles in cycles of four without allocating
+    /// second map. Tile at `(x, y)` lands at `(size - 1 - y, x)`.
+    ///
+    /// # Panics
+    /// Panics if the map is not square, as its size would change with the rotation.
+    pub fn rotate_90_in_place(&mut self) {
+        assert_eq!(
+            self.size.x, self.size.y,
+            "only square maps can be rotated in place"
+        );
+        let n = self.size.x;
+
+        for y in 0..n / 2 {
+            for x in y..n - 1 - y {
+                let cycle = [
+                    (x, y),
+                    (n - 1 - y, x),
+                    (n - 1 - x, n - 1 - y),
+                    (y, n - 1 - x),
+                ];
+                let mut carried = self.tiles.get_mut(x, y).unwrap().take();
+                for (cx, cy) in cycle.into_iter().skip(1).chain([(x, y)]) {
+                    let t = self.tiles.get_mut(cx, cy).unwrap();
+                    carried = std::mem::replace(t, carried);
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of the [`GridMap2D::flood_fill_bounded`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FloodResult {
+    /// Number of tiles changed by the fill.
+    pub changed: usize,
+    /// Whether the fill was stopped by the cap while there were still matching tiles to change.
+    pub capped: bool,
+}
+
+impl<Data: TileData + Hash> GridMap2D<Data> {
+    /// Calculates deterministic hash of the map content: its size and all tiles alongside their positions. It is
+    /// independent of the internal storage layout, so equal maps will always produce the same hash.
+    ///
+    /// The hash is calculated with the 64-bit FNV-1a algorithm, with integers fed in little-endian byte order, so it is
+    /// stable across Rust releases and platforms and can be persisted, eg. in snapshots. The stability is limited
+    /// only by the [`Hash`] implementation of the `Data` itself.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1aHasher::default();
+        let write_xyz = |hasher: &mut Fnv1aHasher, x: u32, y: u32, z: Option<u32>| {
+            hasher.write_u32(x);
+            hasher.write_u32(y);
+            hasher.write_u8(z.is_some() as u8);
+            hasher.write_u32(z.unwrap_or_default());
+        };
+        write_xyz(&mut hasher, self.size.x, self.size.y, self.size.z);
+        for tile in self.iter_tiles_sorted() {
+            let position = tile.grid_position();
+            write_xyz(&mut hasher, *position.x(), *position.y(), *position.z());
+            tile.as_ref().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Stable 64-bit FNV-1a [`Hasher`] used by [`GridMap2D::content_hash`]. Integers are written in little-endian byte
+/// order, with `usize` and `isize` widened to 64 bits, so the results don't depend on the platform.
+struct Fnv1aHasher(u64);
+
+impl Fnv1aHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+}
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
+
+impl<Data: IdentifiableTileData> GridMap2D<Data> {
+    /// Counts occurences of every distinct pattern of `tile_type_id` within the `window` slid over the map. Patterns
+    /// are listed row by row, and windows containing any empty position are skipped.
+    ///
+    /// Useful for comparing the local pattern distribution of the generated maps with the samples.
+    pub fn pattern_histogram(&self, window: GridSize) -> HashMap<Vec<u64>, u32> {
+        let mut histogram = HashMap::new();
+        if window.x == 0 || window.y == 0 || window.x > self.size.x || window.y > self.size.y {
+            return histogram;
+        }
+
+        for origin_y in 0..=self.size.y - window.y {
+            for origin_x in 0..=self.size.x - window.x {
+                let pattern = (origin_y..origin_y + window.y)
+                    .flat_map(|y| (origin_x..origin_x + window.x).map(move |x| (x, y)))
+                    .map(|(x, y)| {
+                        self.tiles
+                            .get(x, y)
+                            .and_then(Option::as_ref)
+                            .map(|data| data.tile_type_id())
+                    })
+                    .collect::<Option<Vec<_>>>();
+                if let Some(pattern) = pattern {
+                    *histogram.entry(pattern).or_default() += 1;
+                }
+            }
+        }
+        histogram
+    }
+
+    /// Replaces every tile which `tile_type_id` is a key in the `mapping` with a clone of the mapped data. Tiles with
+    /// unmapped `tile_type_id` are left untouched.
+    ///
+    /// Returns the number of replaced tiles.
+    pub fn remap_types(&mut self, mapping: &HashMap<u64, Data>) -> usize
+    where
+        Data: Clone,
+    {
+        let mut replaced = 0;
+        for data in self.tiles.iter_mut().flatten() {
+            if let Some(new_data) = mapping.get(&data.tile_type_id()) {
+                *data = new_data.clone();
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+
+    /// Creates a new map of the same size, with every tile rebuilt by the `builder` from its `tile_type_id`.
+    ///
+    /// All `tile_type_id`s present in the map are checked with [`IdentTileBuilder::check_missing_ids`] before any tile
+    /// is built, so the conversion fails fast without producing partially filled map.
+    pub fn rebuild_with<Out, B>(&self, builder: &B) -> Result<GridMap2D<Out>, TileBuilderError>
+    where
+        Out: IdentifiableTileData,
+        B: IdentTileBuilder<Out>,
+    {
+        let tile_type_ids = self
+            .iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        builder.check_missing_ids(&tile_type_ids)?;
+
+        let mut rebuilt = GridMap2D::new(self.size);
+        for tile in self.iter_tiles() {
+            rebuilt.insert_tile(
+                builder.build_tile_unchecked(tile.grid_position(), tile.as_ref().tile_type_id()),
+            );
+        }
+        Ok(rebuilt)
+    }
+}
+
+impl<Data: TileData + Default> GridMap2D<Data> {
+    pub fn fill_empty_with_default(&mut self) {
+        for pos in self.get_all_empty_positions() {
+            self.insert_data(&pos, Data::default());
+        }
+    }
+}
+
+impl<Data: TileData + Clone> GridMap2D<Data> {
+    /// Creates new map of given `size` filled with checkerboard pattern: `a` at positions with even sum of
+    /// coordinates, and `b` at the remaining ones.
+    pub fn checkerboard(size: GridSize, a: Data, b: Data) -> Self {
+        let mut map = Self::new(size);
+        for position in size.get_all_possible_positions() {
+            let (x, y) = position.xy();
+            let data = if (x + y).is_multiple_of(2) { &a } else { &b };
+            map.insert_data(&position, data.clone());
+        }
+        map
+    }
+
+    /// Creates new map of given `size` filled with vertical stripes, each `period` columns wide, alternating between
+    /// `a` and `b` starting with `a` at the left border.
+    ///
+    /// # Panics
+    /// Panics if `period` is `0`.
+    pub fn stripes(size: GridSize, period: u32, a: Data, b: Data) -> Self {
+        assert!(period > 0, "period of stripes should be greater than 0");
+        let mut map = Self::new(size);
+        for position in size.get_all_possible_positions() {
+            let data = if (position.x() / period).is_multiple_of(2) {
+                &a
+            } else {
+                &b
+            };
+            map.insert_data(&position, data.clone());
+        }
+        map
+    }
+
+    pub fn fill_empty_with(&mut self, tile: Data) {
+        for pos in self.get_all_empty_positions() {
+            self.insert_data(&pos, tile.clone());
+        }
+    }
+
+    /// Fills every empty position with the clone of data randomly chosen from `weighted` prototypes, with the chance of
+    /// each being proportional to its weight. Already filled positions are left untouched.
+    ///
+    /// # Panics
+    /// Panics if the total weight of the prototypes is `0` or doesn't fit in `u32`.
+    #[cfg(feature = "gen")]
+    pub fn fill_empty_random<R: crate::gen::GridRng>(
+        &mut self,
+        rng: &mut R,
+        weighted: &[(Data, u32)],
+    ) {
+        let total = weighted
+            .iter()
+            .try_fold(0u32, |sum, (_, weight)| sum.checked_add(*weight))
+            .expect("total weight of prototypes should fit in `u32`");
+        assert!(
+            total > 0,
+            "total weight of prototypes should be greater than 0"
+        );
+
+        for pos in self.get_all_empty_positions() {
+            let mut roll = rng.gen_range(0..total);
+            let (data, _) = weighted
+                .iter()
+                .find(|(_, weight)| {
+                    if roll < *weight {
+                        return true;
+                    }
+                    roll -= weight;
+                    false
+                })
+                .expect("roll should be within the total weight");
+            self.insert_data(&pos, data.clone());
+        }
+    }
+
+    /// Creates new map of `new_size`, copying all tiles which positions are still valid within it. Tiles placed
+    /// outside of the new bounds are dropped, so the map can be both grown and shrunk.
+    pub fn resized(&self, new_size: GridSize) -> Self {
+        let mut resized = Self::new(new_size);
+        for tile in self.iter_tiles() {
+            resized.insert_data(&tile.grid_position(), tile.as_ref().clone());
+        }
+        resized
+    }
+
+    /// Replaces the tiles connected with `start` for which `matches` returns `true` with clones of `new`, visiting them
+    /// in breadth-first order. Filling stops after `max_cells` tiles were changed, keeping the filled region compact
+    /// around the `start`.
+    pub fn flood_fill_bounded(
+        &mut self,
+        start: &GridPosition,
+        matches: impl Fn(&Data) -> bool,
+        new: Data,
+        max_cells: usize,
+    ) -> FloodResult {
+        let positions = match self.get_tile_at_position(start) {
+            Some(tile) if matches(tile.as_ref()) => self
+                .bfs_from(&[*start], |_, _, _, next| matches(next))
+                .take(max_cells.saturating_add(1))
+                .map(|(position, _)| position)
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+
+        let result = FloodResult {
+            changed: positions.len().min(max_cells),
+            capped: positions.len() > max_cells,
+        };
+        for position in positions.iter().take(max_cells) {
+            if let Some(mut tile) = self.get_mut_tile_at_position(position) {
+                *tile.as_mut() = new.clone();
+            }
+        }
+
+        result
+    }
+
+    /// Splits the map into four quadrants as separate maps, in order: upper-left, upper-right, lower-left and
+    /// lower-right. Maps are split at the [`GridSize::center`], so for odd dimensions the right and lower quadrants
+    /// are larger. Positions of tiles within quadrants are relative to their upper-left corner.
+    pub fn quadrants(&self) -> [Self; 4] {
+        let (center_x, center_y) = self.size.center();
+        let (size_x, size_y) = (self.size.x(), self.size.y());
+
+        [
+            (0, 0, center_x, center_y),
+            (center_x, 0, size_x - center_x, center_y),
+            (0, center_y, center_x, size_y - center_y),
+            (center_x, center_y, size_x - center_x, size_y - center_y),
+        ]
+        .map(|(offset_x, offset_y, x, y)| {
+            let mut quadrant = Self::new(GridSize::new_xy(x, y));
+            for tile in self.iter_tiles() {
+                let (tile_x, tile_y) = tile.grid_position().xy();
+                if (offset_x..offset_x + x).contains(&tile_x)
+                    && (offset_y..offset_y + y).contains(&tile_y)
+                {
+                    quadrant.insert_data(
+                        &GridPosition::new_xy(tile_x - offset_x, tile_y - offset_y),
+                        tile.as_ref().clone(),
+                    );
+                }
+            }
+            quadrant
+        })
     }
 
     /// Get all tiles with their positions remapped according to `anchor_pos`, which is the `left-top` position.
@@ -489,3 +1580,1214 @@ impl<Data: TileData + Clone> GridMap2D<Data> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::tile::identifiable::builders::{
+        ConstructableViaIdentifierTile, IdentTileCloneBuilder,
+    };
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::{GridPosition, TileContainer, TileData};
+
+    use super::{DirectionTable, FloodResult, GridDir, GridMap2D, GridSize};
+
+    struct IntTile(u32);
+
+    impl TileData for IntTile {}
+
+    fn test_map() -> GridMap2D<BasicIdentTileData> {
+        let size = GridSize::new_xy(5, 4);
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            if pos.x() == pos.y() {
+                continue;
+            }
+            map.insert_data(
+                &pos,
+                BasicIdentTileData::tile_new(((pos.x() * 3 + pos.y()) % 4) as u64),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn positions_where_matches_manual_scan() {
+        let map = test_map();
+
+        let mut expected = Vec::new();
+        for tile in map.iter_tiles() {
+            if tile.as_ref().tile_type_id() == 2 {
+                expected.push(tile.grid_position());
+            }
+        }
+
+        let mut found = map.positions_where(|data| data.tile_type_id() == 2);
+        found.sort();
+        expected.sort();
+
+        assert!(!found.is_empty());
+        assert_eq!(expected, found);
+    }
+
+    #[test]
+    fn direction_table_map() {
+        let counts = DirectionTable::new_array([0usize, 3, 0, 1]);
+        let nonzero = counts.map(|count| *count > 0);
+
+        assert!(!nonzero[GridDir::UP]);
+        assert!(nonzero[GridDir::DOWN]);
+        assert!(!nonzero[GridDir::LEFT]);
+        assert!(nonzero[GridDir::RIGHT]);
+    }
+
+    #[test]
+    fn iter_tiles_sorted_is_monotonic() {
+        let map = test_map();
+
+        let positions = map
+            .iter_tiles_sorted()
+            .map(|tile| tile.grid_position())
+            .collect::<Vec<_>>();
+
+        assert_eq!(map.get_all_positions().len(), positions.len());
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[derive(Clone, Hash)]
+    struct HashableTileData(u64);
+
+    impl TileData for HashableTileData {}
+
+    #[test]
+    fn content_hash_equal_for_equal_maps() {
+        let size = GridSize::new_xy(4, 3);
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            map.insert_data(&pos, HashableTileData((pos.x() * 7 + pos.y()) as u64));
+        }
+        let cloned = map.clone();
+        let mut changed = map.clone();
+        changed.insert_data(&GridPosition::new_xy(2, 1), HashableTileData(100));
+
+        assert_eq!(map.content_hash(), cloned.content_hash());
+        assert_ne!(map.content_hash(), changed.content_hash());
+        // The algorithm is fixed, so the hash must not change between releases.
+        assert_eq!(3429510729331046038, map.content_hash());
+    }
+
+    #[test]
+    fn rect_intersection_2d() {
+        let size = GridSize::new_xy(4, 3);
+
+        let (origin, overlap) = GridSize::rect_intersection(
+            &GridPosition::new_xy(2, 2),
+            &size,
+            &GridPosition::new_xy(2, 2),
+            &size,
+        )
+        .unwrap();
+        assert_eq!(GridPosition::new_xy(2, 2), origin);
+        assert_eq!((4, 3, None), (overlap.x(), overlap.y(), overlap.z()));
+
+        let (origin, overlap) = GridSize::rect_intersection(
+            &GridPosition::new_xy(0, 0),
+            &size,
+            &GridPosition::new_xy(2, 1),
+            &GridSize::new_xy(5, 5),
+        )
+        .unwrap();
+        assert_eq!(GridPosition::new_xy(2, 1), origin);
+        assert_eq!((2, 2, None), (overlap.x(), overlap.y(), overlap.z()));
+
+        assert!(GridSize::rect_intersection(
+            &GridPosition::new_xy(0, 0),
+            &size,
+            &GridPosition::new_xy(4, 0),
+            &size,
+        )
+        .is_none());
+
+        let (origin, overlap) = GridSize::rect_intersection(
+            &GridPosition::new_xy(u32::MAX - 1, 0),
+            &size,
+            &GridPosition::new_xy(u32::MAX - 2, 0),
+            &size,
+        )
+        .unwrap();
+        assert_eq!(GridPosition::new_xy(u32::MAX - 1, 0), origin);
+        assert_eq!((3, 3), (overlap.x(), overlap.y()));
+    }
+
+    #[test]
+    fn rect_intersection_3d() {
+        let size = GridSize::new_xyz(3, 3, 3);
+
+        let (origin, overlap) = GridSize::rect_intersection(
+            &GridPosition::new_xyz(1, 1, 1),
+            &size,
+            &GridPosition::new_xyz(1, 1, 1),
+            &size,
+        )
+        .unwrap();
+        assert_eq!(GridPosition::new_xyz(1, 1, 1), origin);
+        assert_eq!((3, 3, Some(3)), (overlap.x(), overlap.y(), overlap.z()));
+
+        let (origin, overlap) = GridSize::rect_intersection(
+            &GridPosition::new_xyz(0, 0, 0),
+            &size,
+            &GridPosition::new_xyz(1, 2, 2),
+            &size,
+        )
+        .unwrap();
+        assert_eq!(GridPosition::new_xyz(1, 2, 2), origin);
+        assert_eq!((2, 1, Some(1)), (overlap.x(), overlap.y(), overlap.z()));
+
+        assert!(GridSize::rect_intersection(
+            &GridPosition::new_xyz(0, 0, 0),
+            &size,
+            &GridPosition::new_xyz(0, 0, 3),
+            &size,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn resized_grows_preserving_content() {
+        let size = GridSize::new_xy(5, 5);
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            map.insert_data(
+                &pos,
+                BasicIdentTileData::tile_new((pos.x() + pos.y() * 5) as u64),
+            );
+        }
+
+        let grown = map.resized(GridSize::new_xy(10, 10));
+
+        assert_eq!(10, grown.size().x());
+        assert_eq!(10, grown.size().y());
+        assert_eq!(25, grown.get_all_positions().len());
+        for tile in map.iter_tiles() {
+            let copied = grown.get_tile_at_position(&tile.grid_position()).unwrap();
+            assert_eq!(tile.as_ref().tile_type_id(), copied.as_ref().tile_type_id());
+        }
+    }
+
+    #[test]
+    fn resized_shrinks_dropping_out_of_range() {
+        let size = GridSize::new_xy(5, 5);
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            map.insert_data(
+                &pos,
+                BasicIdentTileData::tile_new((pos.x() + pos.y() * 5) as u64),
+            );
+        }
+
+        let shrunk = map.resized(GridSize::new_xy(3, 3));
+
+        assert_eq!(9, shrunk.get_all_positions().len());
+        for tile in shrunk.iter_tiles() {
+            let original = map.get_tile_at_position(&tile.grid_position()).unwrap();
+            assert_eq!(
+                original.as_ref().tile_type_id(),
+                tile.as_ref().tile_type_id()
+            );
+        }
+        assert!(shrunk
+            .get_tile_at_position(&GridPosition::new_xy(3, 3))
+            .is_none());
+    }
+
+    #[test]
+    fn paste_drained_with_offset() {
+        let mut source = GridMap2D::new(GridSize::new_xy(3, 3));
+        for pos in [(0, 0), (1, 0), (2, 2)] {
+            source.insert_data(
+                &GridPosition::new_xy(pos.0, pos.1),
+                BasicIdentTileData::tile_new((pos.0 + pos.1 * 3) as u64),
+            );
+        }
+        let drained = source
+            .drain()
+            .into_iter()
+            .map(|tile| (tile.grid_position(), tile.into_inner()))
+            .collect::<Vec<_>>();
+
+        let mut target = GridMap2D::new(GridSize::new_xy(4, 4));
+        target.insert_data(
+            &GridPosition::new_xy(2, 1),
+            BasicIdentTileData::tile_new(99),
+        );
+
+        let id_at = |map: &GridMap2D<BasicIdentTileData>, x: u32, y: u32| {
+            map.get_tile_at_position(&GridPosition::new_xy(x, y))
+                .map(|t| t.as_ref().tile_type_id())
+        };
+
+        // (2, 2) lands out of bounds at (4, 3) and (0, 0) collides with the existing tile.
+        let inserted = target.paste(drained.clone(), GridPosition::new_xy(2, 1), false);
+        assert_eq!(1, inserted);
+        assert_eq!(Some(99), id_at(&target, 2, 1));
+        assert_eq!(Some(1), id_at(&target, 3, 1));
+
+        let inserted = target.paste(drained, GridPosition::new_xy(1, 1), true);
+        assert_eq!(3, inserted);
+        assert_eq!(Some(0), id_at(&target, 1, 1));
+        assert_eq!(Some(1), id_at(&target, 2, 1));
+        assert_eq!(Some(8), id_at(&target, 3, 3));
+        assert_eq!(4, target.get_all_positions().len());
+    }
+
+    #[test]
+    fn occupied_neighbour_count_three() {
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 3));
+        for (x, y) in [(1, 1), (1, 0), (0, 1), (1, 2), (0, 0), (2, 2)] {
+            map.insert_data(&GridPosition::new_xy(x, y), BasicIdentTileData::tile_new(0));
+        }
+
+        assert_eq!(3, map.occupied_neighbour_count(&GridPosition::new_xy(1, 1)));
+        assert_eq!(2, map.occupied_neighbour_count(&GridPosition::new_xy(0, 0)));
+        assert_eq!(2, map.occupied_neighbour_count(&GridPosition::new_xy(2, 1)));
+    }
+
+    #[test]
+    fn occupied_neighbour_mask_bits() {
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 3));
+        map.insert_data(&GridPosition::new_xy(1, 1), BasicIdentTileData::tile_new(0));
+        map.insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(2, 1), BasicIdentTileData::tile_new(2));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(3));
+
+        let mask = map.occupied_neighbour_mask(&GridPosition::new_xy(1, 1));
+        assert_eq!((1 << GridDir::UP as u8) | (1 << GridDir::RIGHT as u8), mask);
+        assert_eq!(0b1001, mask);
+        assert_eq!(
+            0b0001,
+            map.occupied_neighbour_mask(&GridPosition::new_xy(2, 2))
+        );
+    }
+
+    #[test]
+    fn to_ascii_renders_rows() {
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 2));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(2, 0), BasicIdentTileData::tile_new(2));
+        map.insert_data(&GridPosition::new_xy(1, 1), BasicIdentTileData::tile_new(1));
+
+        let ascii = map.to_ascii(|data| match data.map(|d| d.tile_type_id()) {
+            Some(1) => '#',
+            Some(_) => '~',
+            None => '.',
+        });
+        assert_eq!("#.~\n.#.", ascii);
+    }
+
+    #[test]
+    fn distance_field_blocked_by_walls() {
+        const WALL: u64 = 1;
+        let size = GridSize::new_xy(4, 4);
+        let mut map = GridMap2D::new(size);
+        map.fill_empty_with(BasicIdentTileData::tile_new(0));
+        // Vertical wall at x == 2, with the gap at the bottom row.
+        for y in 0..3 {
+            map.insert_data(
+                &GridPosition::new_xy(2, y),
+                BasicIdentTileData::tile_new(WALL),
+            );
+        }
+
+        let field = map.distance_field(&[GridPosition::new_xy(0, 0)], |data| {
+            data.tile_type_id() != WALL
+        });
+
+        assert_eq!(Some(&0), field.get(&GridPosition::new_xy(0, 0)));
+        assert_eq!(Some(&2), field.get(&GridPosition::new_xy(1, 1)));
+        assert_eq!(Some(&5), field.get(&GridPosition::new_xy(2, 3)));
+        assert_eq!(Some(&9), field.get(&GridPosition::new_xy(3, 0)));
+        assert!(!field.contains_key(&GridPosition::new_xy(2, 0)));
+        assert_eq!(13, field.len());
+    }
+
+    #[test]
+    fn bfs_from_ordered_by_distance() {
+        const WALL: u64 = 1;
+        let mut map = GridMap2D::new(GridSize::new_xy(4, 4));
+        map.fill_empty_with(BasicIdentTileData::tile_new(0));
+        for y in 0..3 {
+            map.insert_data(
+                &GridPosition::new_xy(2, y),
+                BasicIdentTileData::tile_new(WALL),
+            );
+        }
+        map.remove_tile_at_position(&GridPosition::new_xy(0, 3));
+
+        let visited = map
+            .bfs_from(&[GridPosition::new_xy(0, 0)], |_, _, _, next| {
+                next.tile_type_id() != WALL
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!((GridPosition::new_xy(0, 0), 0), visited[0]);
+        assert!(visited.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert!(visited.contains(&(GridPosition::new_xy(3, 0), 9)));
+        assert!(!visited
+            .iter()
+            .any(|(pos, _)| *pos == GridPosition::new_xy(2, 0)));
+        assert!(!visited
+            .iter()
+            .any(|(pos, _)| *pos == GridPosition::new_xy(0, 3)));
+        assert_eq!(12, visited.len());
+    }
+
+    #[test]
+    fn bfs_from_gated_by_step_predicate() {
+        // Tile ids increase left-to-right: 0 1 2 3
+        let mut map = GridMap2D::new(GridSize::new_xy(4, 1));
+        for x in 0..4 {
+            map.insert_data(
+                &GridPosition::new_xy(x, 0),
+                BasicIdentTileData::tile_new(x as u64),
+            );
+        }
+
+        let ascending = |_: &GridPosition,
+                         current: &BasicIdentTileData,
+                         _: &GridPosition,
+                         next: &BasicIdentTileData| {
+            next.tile_type_id() > current.tile_type_id()
+        };
+
+        let from_start = map
+            .bfs_from(&[GridPosition::new_xy(1, 0)], ascending)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (GridPosition::new_xy(1, 0), 0),
+                (GridPosition::new_xy(2, 0), 1),
+                (GridPosition::new_xy(3, 0), 2)
+            ],
+            from_start
+        );
+
+        let from_end = map
+            .bfs_from(&[GridPosition::new_xy(3, 0)], ascending)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(GridPosition::new_xy(3, 0), 0)], from_end);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_iter_tiles_matches_sequential() {
+        use rayon::prelude::*;
+
+        let map = test_map();
+        let sequential = map
+            .iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id() * (tile.grid_position().x() + 1) as u64)
+            .sum::<u64>();
+        let parallel = map
+            .par_iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id() * (tile.grid_position().x() + 1) as u64)
+            .sum::<u64>();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(map.iter_tiles().count(), map.par_iter_tiles().count());
+    }
+
+    #[cfg(feature = "gen")]
+    #[test]
+    fn fill_empty_random_distribution() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaChaRng;
+
+        let size = GridSize::new_xy(50, 40);
+        let mut map = GridMap2D::new(size);
+        for x in 0..size.x() {
+            map.insert_data(&GridPosition::new_xy(x, 0), BasicIdentTileData::tile_new(9));
+        }
+
+        map.fill_empty_random(
+            &mut ChaChaRng::seed_from_u64(3),
+            &[
+                (BasicIdentTileData::tile_new(0), 1),
+                (BasicIdentTileData::tile_new(1), 3),
+                (BasicIdentTileData::tile_new(2), 0),
+            ],
+        );
+
+        let mut counts = [0usize; 10];
+        for tile in map.iter_tiles() {
+            counts[tile.as_ref().tile_type_id() as usize] += 1;
+        }
+        assert!(map.get_all_empty_positions().is_empty());
+        assert_eq!(50, counts[9]);
+        assert_eq!(0, counts[2]);
+        assert_eq!(1950, counts[0] + counts[1]);
+        let ratio = counts[1] as f32 / counts[0] as f32;
+        assert!((2.5..3.5).contains(&ratio), "ratio: {ratio}");
+        assert!((0..size.x()).all(|x| map
+            .get_tile_at_position(&GridPosition::new_xy(x, 0))
+            .is_some_and(|tile| tile.as_ref().tile_type_id() == 9)));
+    }
+
+    #[cfg(feature = "gen")]
+    #[test]
+    #[should_panic(expected = "total weight of prototypes should be greater than 0")]
+    fn fill_empty_random_zero_weight() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaChaRng;
+
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 2));
+        map.fill_empty_random(
+            &mut ChaChaRng::seed_from_u64(3),
+            &[(BasicIdentTileData::tile_new(0), 0)],
+        );
+    }
+
+    fn map_from_ascii(rows: &[&str]) -> GridMap2D<BasicIdentTileData> {
+        let mut map = GridMap2D::new(GridSize::new_xy(rows[0].len() as u32, rows.len() as u32));
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                map.insert_data(
+                    &GridPosition::new_xy(x as u32, y as u32),
+                    BasicIdentTileData::tile_new((c == '#') as u64),
+                );
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn connected_single_blob() {
+        let map = map_from_ascii(&["#..#", "....", ".##."]);
+        let floor = |data: &BasicIdentTileData| data.tile_type_id() == 0;
+
+        assert!(map.is_connected(floor));
+        assert_eq!(8, map.largest_connected_region(floor).len());
+    }
+
+    #[test]
+    fn not_connected_separate_blobs() {
+        let map = map_from_ascii(&["..#.", "..#.", "###.", "...#"]);
+        let floor = |data: &BasicIdentTileData| data.tile_type_id() == 0;
+
+        assert!(!map.is_connected(floor));
+        let mut region = map.largest_connected_region(floor);
+        region.sort();
+        let mut expected = (0..2)
+            .flat_map(|y| (0..2).map(move |x| GridPosition::new_xy(x, y)))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(expected, region);
+    }
+
+    #[test]
+    fn rows_and_columns_sums() {
+        let size = GridSize::new_xy(3, 2);
+        let mut map = GridMap2D::new(size);
+        // 1 2 3
+        // 4 . 6
+        for pos in size.get_all_possible_positions() {
+            if pos == GridPosition::new_xy(1, 1) {
+                continue;
+            }
+            map.insert_data(&pos, IntTile(pos.x() + pos.y() * 3 + 1));
+        }
+
+        let sum = |line: &mut dyn Iterator<Item = Option<&IntTile>>| {
+            line.map(|slot| slot.map_or(0, |tile| tile.0)).sum::<u32>()
+        };
+        let row_sums = map.rows().map(|mut row| sum(&mut row)).collect::<Vec<_>>();
+        let column_sums = map
+            .columns()
+            .map(|mut column| sum(&mut column))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![6, 10], row_sums);
+        assert_eq!(vec![5, 2, 9], column_sums);
+        assert!(map.rows().all(|row| row.count() == 3));
+    }
+
+    #[test]
+    fn insert_data_merge_sums_on_collision() {
+        let sum = |existing: &IntTile, new: IntTile| IntTile(existing.0 + new.0);
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 2));
+        let position = GridPosition::new_xy(1, 0);
+
+        assert!(map.insert_data_merge(&position, IntTile(3), sum));
+        assert!(map.insert_data_merge(&position, IntTile(4), sum));
+        assert!(map.insert_data_merge(&GridPosition::new_xy(0, 1), IntTile(5), sum));
+        assert!(!map.insert_data_merge(&GridPosition::new_xy(2, 0), IntTile(1), sum));
+
+        assert_eq!(7, map.get_tile_at_position(&position).unwrap().as_ref().0);
+        assert_eq!(
+            5,
+            map.get_tile_at_position(&GridPosition::new_xy(0, 1))
+                .unwrap()
+                .as_ref()
+                .0
+        );
+        assert_eq!(2, map.iter_tiles().count());
+    }
+
+    #[test]
+    fn with_neighbourhood_mut_increments_by_neighbours() {
+        struct CountTile(u32);
+        impl TileData for CountTile {}
+
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 3));
+        for (x, y, count) in [(1, 1, 10), (1, 0, 1), (0, 1, 2), (2, 2, 3)] {
+            map.insert_data(&GridPosition::new_xy(x, y), CountTile(count));
+        }
+
+        let center = GridPosition::new_xy(1, 1);
+        let directions = map.with_neighbourhood_mut(&center, |data, neighbours| {
+            data.0 += neighbours.len() as u32;
+            neighbours.iter().map(|(dir, _)| *dir).collect::<Vec<_>>()
+        });
+        assert_eq!(Some(vec![GridDir::UP, GridDir::LEFT]), directions);
+        assert_eq!(12, map.get_tile_at_position(&center).unwrap().as_ref().0);
+
+        assert!(map
+            .with_neighbourhood_mut(&GridPosition::new_xy(0, 0), |_, _| ())
+            .is_none());
+        assert!(map
+            .with_neighbourhood_mut(&GridPosition::new_xy(5, 5), |_, _| ())
+            .is_none());
+    }
+
+    #[test]
+    fn size_factories_and_modifiers() {
+        assert_eq!(GridSize::new_xy(5, 5), GridSize::square(5));
+        assert_eq!(GridSize::new_xyz(3, 3, 3), GridSize::cube(3));
+        assert_eq!(2.0, GridSize::new_xy(20, 10).aspect_ratio());
+
+        let size = GridSize::square(5).with_x(8);
+        assert_eq!(GridSize::new_xy(8, 5), size);
+        assert_eq!(size.center(), GridSize::new_xy(8, 5).center());
+        assert_eq!(GridSize::new_xyz(3, 7, 3), GridSize::cube(3).with_y(7));
+    }
+
+    #[test]
+    fn flood_fill_bounded_stops_at_cap() {
+        let size = GridSize::square(50);
+        let mut map = GridMap2D::new(size);
+        map.fill_empty_with(BasicIdentTileData::tile_new(0));
+
+        let start = GridPosition::new_xy(25, 25);
+        let result = map.flood_fill_bounded(
+            &start,
+            |t| t.tile_type_id() == 0,
+            BasicIdentTileData::tile_new(1),
+            30,
+        );
+        assert_eq!(
+            FloodResult {
+                changed: 30,
+                capped: true
+            },
+            result
+        );
+
+        let filled = map.positions_where(|t| t.tile_type_id() == 1);
+        assert_eq!(30, filled.len());
+        assert!(filled.iter().all(|pos| pos.in_range(&start, 4)));
+
+        let result = map.flood_fill_bounded(
+            &start,
+            |t| t.tile_type_id() == 1,
+            BasicIdentTileData::tile_new(2),
+            100,
+        );
+        assert_eq!(
+            FloodResult {
+                changed: 30,
+                capped: false
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn drain_region_empties_corner() {
+        let mut map = test_map();
+        let original = map.clone();
+
+        // Position (3, 3) on the diagonal is empty.
+        let drained = map.drain_region(&GridPosition::new_xy(4, 3), &GridPosition::new_xy(3, 2));
+        assert_eq!(3, drained.len());
+        for tile in drained.iter() {
+            let (x, y) = tile.grid_position().xy();
+            assert!((3..=4).contains(&x) && (2..=3).contains(&y));
+            assert_eq!(((x * 3 + y) % 4) as u64, tile.as_ref().tile_type_id());
+            assert!(map.get_tile_at_position(&tile.grid_position()).is_none());
+        }
+        assert_eq!(original.iter_tiles().count() - 3, map.iter_tiles().count());
+
+        // Clipped to the map size, skipping already empty positions.
+        let drained = map.drain_region(&GridPosition::new_xy(2, 2), &GridPosition::new_xy(10, 10));
+        assert_eq!(1, drained.len());
+        assert_eq!(GridPosition::new_xy(2, 3), drained[0].grid_position());
+
+        // Region reaching the end of the coordinates range.
+        let drained = map.drain_region(
+            &GridPosition::new_xy(4, 0),
+            &GridPosition::new_xy(u32::MAX, u32::MAX),
+        );
+        assert_eq!(2, drained.len());
+        assert!(map
+            .drain_region(
+                &GridPosition::new_xy(u32::MAX, u32::MAX),
+                &GridPosition::new_xy(u32::MAX, u32::MAX)
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn iter_mut_region_changes_only_region() {
+        let mut map = test_map();
+        let original = map.clone();
+
+        let changed = map
+            .iter_mut_region(&GridPosition::new_xy(3, 2), &GridPosition::new_xy(1, 1))
+            .map(|mut tile| *tile.as_mut() = BasicIdentTileData::tile_new(100))
+            .count();
+        // Positions on the diagonal are empty.
+        assert_eq!(4, changed);
+
+        for tile in map.iter_tiles() {
+            let pos = tile.grid_position();
+            let inside = (1..=3).contains(pos.x()) && (1..=2).contains(pos.y());
+            let expected = if inside {
+                100
+            } else {
+                original
+                    .get_tile_at_position(&pos)
+                    .unwrap()
+                    .as_ref()
+                    .tile_type_id()
+            };
+            assert_eq!(expected, tile.as_ref().tile_type_id());
+        }
+
+        let clipped = map
+            .iter_mut_region(&GridPosition::new_xy(4, 3), &GridPosition::new_xy(10, 10))
+            .map(|tile| tile.grid_position())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![GridPosition::new_xy(4, 3)], clipped);
+        assert_eq!(
+            0,
+            map.iter_mut_region(&GridPosition::new_xy(7, 7), &GridPosition::new_xy(9, 9))
+                .count()
+        );
+        assert_eq!(
+            0,
+            map.iter_mut_region(&GridPosition::new_xy(0, 6), &GridPosition::new_xy(0, 9))
+                .count()
+        );
+        let clipped_y = map
+            .iter_mut_region(
+                &GridPosition::new_xy(1, 3),
+                &GridPosition::new_xy(1, u32::MAX),
+            )
+            .map(|tile| tile.grid_position())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![GridPosition::new_xy(1, 3)], clipped_y);
+    }
+
+    #[test]
+    fn neighbours_wrapping_around_edges() {
+        let map = test_map();
+
+        let left = map
+            .get_neighbour_wrapping(&GridPosition::new_xy(0, 1), &GridDir::LEFT)
+            .unwrap();
+        assert_eq!(GridPosition::new_xy(4, 1), left.grid_position());
+        assert_eq!(1, left.as_ref().tile_type_id());
+
+        let mut neighbours = map
+            .get_neighbours_wrapping(&GridPosition::new_xy(0, 0))
+            .iter()
+            .map(|tile| tile.grid_position())
+            .collect::<Vec<_>>();
+        neighbours.sort();
+        assert_eq!(
+            vec![
+                GridPosition::new_xy(0, 1),
+                GridPosition::new_xy(0, 3),
+                GridPosition::new_xy(1, 0),
+                GridPosition::new_xy(4, 0),
+            ],
+            neighbours
+        );
+        assert!(map
+            .get_neighbour_wrapping(&GridPosition::new_xy(5, 0), &GridDir::LEFT)
+            .is_none());
+    }
+
+    #[test]
+    fn quadrants_reassemble_into_original() {
+        let base = test_map();
+        for size in [GridSize::new_xy(4, 4), GridSize::new_xy(5, 3)] {
+            let map = base.resized(size);
+
+            let quadrants = map.quadrants();
+            let (center_x, center_y) = size.center();
+            let offsets = [(0, 0), (center_x, 0), (0, center_y), (center_x, center_y)];
+
+            let mut reassembled = GridMap2D::new(size);
+            for (quadrant, (x, y)) in quadrants.iter().zip(offsets) {
+                let inserted = reassembled.paste(
+                    quadrant
+                        .iter_tiles()
+                        .map(|tile| (tile.grid_position(), *tile.as_ref())),
+                    GridPosition::new_xy(x, y),
+                    false,
+                );
+                assert_eq!(quadrant.iter_tiles().count(), inserted);
+            }
+
+            assert_eq!(
+                GridSize::new_xy(size.x() - center_x, size.y() - center_y),
+                *quadrants[3].size()
+            );
+            let ids = |map: &GridMap2D<BasicIdentTileData>| {
+                map.iter_tiles_sorted()
+                    .map(|tile| (tile.grid_position(), tile.as_ref().tile_type_id()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(ids(&map), ids(&reassembled));
+        }
+    }
+
+    #[test]
+    fn positions_in_offset_order() {
+        for size in [GridSize::new_xy(4, 3), GridSize::new_xyz(2, 3, 2)] {
+            let offsets = size
+                .iter_positions_offset_order()
+                .map(|pos| size.offset(&pos).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!((0..size.max_tile_count()).collect::<Vec<_>>(), offsets);
+        }
+
+        let size = GridSize::new_xy(4, 3);
+        let mut map = GridMap2D::new(size);
+        for pos in size.iter_positions_offset_order() {
+            map.insert_data(
+                &pos,
+                BasicIdentTileData::tile_new(size.offset(&pos).unwrap() as u64),
+            );
+        }
+        let stored = map
+            .iter_tiles()
+            .map(|tile| tile.as_ref().tile_type_id())
+            .collect::<Vec<_>>();
+        assert_eq!((0..12).collect::<Vec<_>>(), stored);
+        assert_eq!(None, size.offset(&GridPosition::new_xy(4, 0)));
+        assert_eq!(None, size.offset(&GridPosition::new_xyz(0, 0, 0)));
+    }
+
+    #[test]
+    fn rotate_90_in_place_cycles() {
+        let size = GridSize::new_xy(4, 4);
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            if pos.xy() == (1, 2) {
+                continue;
+            }
+            map.insert_data(
+                &pos,
+                BasicIdentTileData::tile_new((pos.x() * 4 + pos.y()) as u64),
+            );
+        }
+        let original = map.to_ascii(|data| {
+            data.map_or('.', |data| {
+                char::from_digit(data.tile_type_id() as u32, 16).unwrap()
+            })
+        });
+
+        map.rotate_90_in_place();
+        assert_eq!(
+            3,
+            map.get_tile_at_position(&GridPosition::new_xy(0, 0))
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        );
+        assert!(map
+            .get_tile_at_position(&GridPosition::new_xy(1, 1))
+            .is_none());
+
+        for _ in 0..3 {
+            map.rotate_90_in_place();
+        }
+        let rotated = map.to_ascii(|data| {
+            data.map_or('.', |data| {
+                char::from_digit(data.tile_type_id() as u32, 16).unwrap()
+            })
+        });
+        assert_eq!(original, rotated);
+    }
+
+    #[test]
+    #[should_panic(expected = "only square maps can be rotated in place")]
+    fn rotate_90_in_place_non_square() {
+        test_map().rotate_90_in_place();
+    }
+
+    #[test]
+    fn checkerboard_cells() {
+        let map = GridMap2D::checkerboard(
+            GridSize::new_xy(2, 2),
+            BasicIdentTileData::tile_new(1),
+            BasicIdentTileData::tile_new(2),
+        );
+
+        assert_eq!(
+            "12\n21",
+            map.to_ascii(|data| {
+                char::from_digit(data.unwrap().tile_type_id() as u32, 10).unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn stripes_period() {
+        let map = GridMap2D::stripes(
+            GridSize::new_xy(7, 2),
+            2,
+            BasicIdentTileData::tile_new(1),
+            BasicIdentTileData::tile_new(2),
+        );
+
+        assert_eq!(
+            "1122112\n1122112",
+            map.to_ascii(|data| {
+                char::from_digit(data.unwrap().tile_type_id() as u32, 10).unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn can_place_2d() {
+        let size = GridSize::new_xy(8, 6);
+        let region = GridSize::new_xy(3, 2);
+
+        assert!(size.can_place(&GridPosition::new_xy(2, 1), &region));
+        assert!(size.can_place(&GridPosition::new_xy(5, 4), &region));
+        assert!(!size.can_place(&GridPosition::new_xy(6, 0), &region));
+        assert!(!size.can_place(&GridPosition::new_xy(0, 5), &region));
+        assert!(!size.can_place(&GridPosition::new_xy(u32::MAX, 0), &region));
+        assert!(size.can_place(&GridPosition::new_xy(0, 0), &size));
+    }
+
+    #[test]
+    fn can_place_3d() {
+        let size = GridSize::cube(4);
+        let region = GridSize::new_xyz(2, 2, 3);
+
+        assert!(size.can_place(&GridPosition::new_xyz(0, 1, 0), &region));
+        assert!(size.can_place(&GridPosition::new_xyz(2, 2, 1), &region));
+        assert!(!size.can_place(&GridPosition::new_xyz(2, 2, 2), &region));
+        assert!(!size.can_place(&GridPosition::new_xyz(3, 0, 0), &region));
+        assert!(size.can_place(&GridPosition::new_xy(1, 1), &GridSize::new_xy(2, 2)));
+        assert!(!GridSize::new_xy(4, 4).can_place(&GridPosition::new_xy(0, 0), &region));
+    }
+
+    #[test]
+    fn remap_types_leaves_unmapped() {
+        let mut map = test_map();
+        let original = map.clone();
+        let mapping = [
+            (0, BasicIdentTileData::tile_new(10)),
+            (2, BasicIdentTileData::tile_new(12)),
+        ]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        let expected_replaced = original
+            .iter_tiles()
+            .filter(|tile| [0, 2].contains(&tile.as_ref().tile_type_id()))
+            .count();
+        assert_eq!(expected_replaced, map.remap_types(&mapping));
+
+        for tile in original.iter_tiles() {
+            let remapped = map.get_tile_at_position(&tile.grid_position()).unwrap();
+            let expected = match tile.as_ref().tile_type_id() {
+                0 => 10,
+                2 => 12,
+                id => id,
+            };
+            assert_eq!(expected, remapped.as_ref().tile_type_id());
+        }
+        assert_eq!(
+            original.get_all_empty_positions(),
+            map.get_all_empty_positions()
+        );
+    }
+
+    #[test]
+    fn transform_with_neighbours_majority_vote() {
+        let mut map = GridMap2D::new(GridSize::new_xy(4, 3));
+        map.fill_empty_with(BasicIdentTileData::tile_new(0));
+        map.insert_data(&GridPosition::new_xy(1, 1), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(3, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(3, 1), BasicIdentTileData::tile_new(1));
+        map.remove_tile_at_position(&GridPosition::new_xy(0, 2));
+
+        let smoothed = map.transform_with_neighbours(|current, neighbours| {
+            let current = current?.tile_type_id();
+            let ones = neighbours
+                .iter()
+                .flatten()
+                .filter(|data| data.tile_type_id() == 1)
+                .count();
+            let present = neighbours.iter().flatten().count();
+            let majority = match ones * 2 {
+                count if count > present => 1,
+                count if count < present => 0,
+                _ => current,
+            };
+            Some(BasicIdentTileData::tile_new(majority))
+        });
+
+        let id_at = |x, y| {
+            smoothed
+                .get_tile_at_position(&GridPosition::new_xy(x, y))
+                .map(|tile| tile.as_ref().tile_type_id())
+        };
+        // Isolated tile flips, while the tie keeps the current type.
+        assert_eq!(Some(0), id_at(1, 1));
+        assert_eq!(Some(1), id_at(3, 0));
+        assert_eq!(Some(0), id_at(3, 1));
+        assert_eq!(None, id_at(0, 2));
+        assert_eq!(11, smoothed.iter_tiles().count());
+    }
+
+    #[test]
+    fn dir_rotation_cycles() {
+        for dir in GridDir::ALL_2D {
+            let mut rotated = *dir;
+            for _ in 0..4 {
+                rotated = rotated.rotate_cw();
+            }
+            assert_eq!(*dir, rotated);
+            assert_eq!(dir.opposite(), dir.rotate_cw().rotate_cw());
+            assert_eq!(*dir, dir.rotate_cw().rotate_ccw());
+        }
+        assert_eq!(
+            vec![GridDir::RIGHT, GridDir::DOWN, GridDir::LEFT, GridDir::UP],
+            std::iter::successors(Some(GridDir::UP), |dir| Some(dir.rotate_cw()))
+                .skip(1)
+                .take(4)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn positions_center_out_order() {
+        let size = GridSize::new_xy(5, 5);
+        let positions = size.positions_center_out();
+
+        assert_eq!(size.max_tile_count(), positions.len());
+        assert_eq!(GridPosition::new_xy(2, 2), positions[0]);
+        assert_eq!(GridPosition::new_xy(4, 4), positions[24]);
+        assert!(positions[1..9]
+            .iter()
+            .all(|pos| pos.x().abs_diff(2) <= 1 && pos.y().abs_diff(2) <= 1));
+    }
+
+    #[test]
+    fn fill_annulus_ring() {
+        let size = GridSize::new_xy(6, 5);
+        let mut map = GridMap2D::new(size);
+        map.fill_annulus(0, 10, |_| BasicIdentTileData::tile_new(0));
+        map.fill_annulus(1, 1, |_| BasicIdentTileData::tile_new(1));
+
+        for tile in map.iter_tiles() {
+            let (x, y) = tile.grid_position().xy();
+            let inside_ring = (1..=4).contains(&x) && (1..=3).contains(&y);
+            let inside_hole = (2..=3).contains(&x) && y == 2;
+            let on_ring = inside_ring && !inside_hole;
+            let expected = if on_ring { 1 } else { 0 };
+            assert_eq!(expected, tile.as_ref().tile_type_id(), "at ({x}, {y})");
+        }
+        assert_eq!(30, map.iter_tiles().count());
+        assert_eq!(
+            10,
+            map.iter_tiles()
+                .filter(|tile| tile.as_ref().tile_type_id() == 1)
+                .count()
+        );
+    }
+
+    #[test]
+    fn corners_and_edge_midpoints() {
+        let size = GridSize::new_xy(10, 10);
+        assert_eq!(
+            vec![
+                GridPosition::new_xy(0, 0),
+                GridPosition::new_xy(9, 0),
+                GridPosition::new_xy(0, 9),
+                GridPosition::new_xy(9, 9)
+            ],
+            size.corners()
+        );
+        assert_eq!(
+            vec![
+                GridPosition::new_xy(5, 0),
+                GridPosition::new_xy(0, 5),
+                GridPosition::new_xy(9, 5),
+                GridPosition::new_xy(5, 9)
+            ],
+            size.edge_midpoints()
+        );
+
+        let cube = GridSize::cube(3);
+        assert_eq!(8, cube.corners().len());
+        assert!(cube.corners().contains(&GridPosition::new_xyz(2, 2, 2)));
+        assert_eq!(12, cube.edge_midpoints().len());
+        assert!(cube
+            .edge_midpoints()
+            .contains(&GridPosition::new_xyz(0, 2, 1)));
+
+        assert_eq!(
+            vec![GridPosition::new_xy(0, 0)],
+            GridSize::square(1).corners()
+        );
+        assert!(GridSize::new_xy(0, 4).corners().is_empty());
+    }
+
+    #[test]
+    fn try_new_rejects_overflow() {
+        assert_eq!(
+            Some(GridSize::new_xyz(2, 3, 4)),
+            GridSize::try_new_xyz(2, 3, 4).ok()
+        );
+        assert_eq!(
+            Some(GridSize::new_xy(2, 3)),
+            GridSize::try_new_xy(2, 3).ok()
+        );
+
+        let err = GridSize::try_new_xyz(u32::MAX, u32::MAX, u32::MAX).unwrap_err();
+        assert_eq!((u32::MAX, u32::MAX, Some(u32::MAX)), err.dimensions());
+        #[cfg(target_pointer_width = "32")]
+        assert!(GridSize::try_new_xy(u32::MAX, 2).is_err());
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            u32::MAX as usize * u32::MAX as usize,
+            GridSize::try_new_xy(u32::MAX, u32::MAX)
+                .unwrap()
+                .max_tile_count()
+        );
+    }
+
+    #[test]
+    fn adjacent_pairs_yielded_once() {
+        let mut map = GridMap2D::new(GridSize::new_xy(2, 1));
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(1));
+        map.insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(2));
+
+        let pairs = map
+            .adjacent_pairs()
+            .map(|(tile, direction, neighbour)| {
+                (
+                    tile.as_ref().tile_type_id(),
+                    direction,
+                    neighbour.as_ref().tile_type_id(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(1, GridDir::RIGHT, 2)], pairs);
+
+        let map = test_map();
+        let expected = map
+            .iter_tiles()
+            .map(|tile| map.get_neighbours(&tile.grid_position()).len())
+            .sum::<usize>();
+        assert_eq!(expected, map.adjacent_pairs().count() * 2);
+    }
+
+    #[test]
+    fn pattern_histogram_of_checkerboard() {
+        let map = GridMap2D::checkerboard(
+            GridSize::new_xy(4, 3),
+            BasicIdentTileData::tile_new(1),
+            BasicIdentTileData::tile_new(2),
+        );
+
+        let histogram = map.pattern_histogram(GridSize::new_xy(2, 1));
+        assert_eq!(2, histogram.len());
+        assert_eq!(Some(&5), histogram.get(&vec![1, 2]));
+        assert_eq!(Some(&4), histogram.get(&vec![2, 1]));
+
+        let mut sparse = map.clone();
+        sparse.remove_tile_at_position(&GridPosition::new_xy(0, 0));
+        let histogram = sparse.pattern_histogram(GridSize::new_xy(2, 1));
+        assert_eq!(Some(&4), histogram.get(&vec![1, 2]));
+        assert!(map.pattern_histogram(GridSize::new_xy(5, 1)).is_empty());
+    }
+
+    #[derive(Clone)]
+    struct NamedTile {
+        tile_type_id: u64,
+        name: &'static str,
+    }
+
+    impl TileData for NamedTile {}
+
+    impl IdentifiableTileData for NamedTile {
+        fn tile_type_id(&self) -> u64 {
+            self.tile_type_id
+        }
+    }
+
+    #[test]
+    fn rebuild_with_clone_builder() {
+        let map = test_map();
+        let mut builder = IdentTileCloneBuilder::default();
+        builder.add_tiles(
+            &[
+                NamedTile {
+                    tile_type_id: 0,
+                    name: "zero",
+                },
+                NamedTile {
+                    tile_type_id: 1,
+                    name: "one",
+                },
+                NamedTile {
+                    tile_type_id: 2,
+                    name: "two",
+                },
+            ],
+            false,
+        );
+
+        let err = map.rebuild_with(&builder).err().expect("id 3 is missing");
+        assert_eq!(&[3], err.get_missing_tile_type_ids());
+
+        builder.add_tiles(
+            &[NamedTile {
+                tile_type_id: 3,
+                name: "three",
+            }],
+            false,
+        );
+        let rebuilt = map.rebuild_with(&builder).unwrap();
+        assert_eq!(map.size(), rebuilt.size());
+        assert_eq!(map.iter_tiles().count(), rebuilt.iter_tiles().count());
+        for tile in map.iter_tiles() {
+            let other = rebuilt.get_tile_at_position(&tile.grid_position()).unwrap();
+            assert_eq!(tile.as_ref().tile_type_id(), other.as_ref().tile_type_id);
+            let name = ["zero", "one", "two", "three"][tile.as_ref().tile_type_id() as usize];
+            assert_eq!(name, other.as_ref().name);
+        }
+        assert!(rebuilt
+            .get_tile_at_position(&GridPosition::new_xy(1, 1))
+            .is_none());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::tile::{GridPosition, TileData};
+
+use super::{GridMap2D, GridSize};
+
+/// Direction towards one of the 26 cells of the three-dimensional *Moore neighbourhood*: 6 sharing a face, 12 sharing
+/// an edge and 8 sharing a corner with the source cell.
+///
+/// Directions with zero `z` offset form the 8-cell neighbourhood of two-dimensional positions.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Direction3D26 {
+    x: i8,
+    y: i8,
+    z: i8,
+}
+
+impl Direction3D26 {
+    /// All 26 directions, ordered by their [`as_idx`](Self::as_idx).
+    pub const ALL: [Direction3D26; 26] = Self::generate_all();
+
+    const fn generate_all() -> [Direction3D26; 26] {
+        let mut out = [Direction3D26 { x: 0, y: 0, z: 0 }; 26];
+        let mut idx = 0;
+        let mut n = 0;
+        while n < 27 {
+            // Skipping the center: offset (0, 0, 0).
+            if n != 13 {
+                out[idx] = Direction3D26 {
+                    x: (n % 3) as i8 - 1,
+                    y: ((n / 3) % 3) as i8 - 1,
+                    z: (n / 9) as i8 - 1,
+                };
+                idx += 1;
+            }
+            n += 1;
+        }
+        out
+    }
+
+    /// Offset of the direction along `x`, `y` and `z` axes.
+    pub fn offset(&self) -> (i8, i8, i8) {
+        (self.x, self.y, self.z)
+    }
+
+    /// Index of the direction within [`ALL`](Self::ALL).
+    pub fn as_idx(&self) -> usize {
+        let n = (self.x + 1) as usize + (self.y + 1) as usize * 3 + (self.z + 1) as usize * 9;
+        if n > 13 {
+            n - 1
+        } else {
+            n
+        }
+    }
+
+    /// Get opposite direction.
+    pub fn opposite(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// Checks if the direction lies within the `xy` plane.
+    pub fn is_planar(&self) -> bool {
+        self.z == 0
+    }
+
+    /// Take a step in the direction from position within the contains of specified [`GridSize`].
+    ///
+    /// Steps changing the `z` coordinate are valid only for positions and sizes with `z` coordinate.
+    ///
+    /// # Returns
+    /// - resulting [`GridPosition`] after the step, or [`None`] if it is not valid within the specified size.
+    pub fn march_step(&self, from: &GridPosition, size: &GridSize) -> Option<GridPosition> {
+        let position = match (from.z(), size.z()) {
+            (Some(z), Some(z_size)) => {
+                let z = z
+                    .checked_add_signed(self.z as i32)
+                    .filter(|z| *z < z_size)?;
+                let moved = from.checked_offset(&[self.x as i32, self.y as i32])?;
+                GridPosition::new_xyz(*moved.x(), *moved.y(), z)
+            }
+            _ if self.z != 0 => return None,
+            _ => from.checked_offset(&[self.x as i32, self.y as i32])?,
+        };
+        size.is_position_valid(&position).then_some(position)
+    }
+}
+
+impl<Data: TileData> GridMap2D<Data> {
+    /// Get positions of the *Moore neighbourhood* of the specified position within the map size: up to 8 positions
+    /// sharing an edge or a corner with it.
+    pub fn get_moore_neighbour_positions(&self, position: &GridPosition) -> Vec<GridPosition> {
+        Direction3D26::ALL
+            .iter()
+            .filter(|direction| direction.is_planar())
+            .filter_map(|direction| direction.march_step(position, &self.size))
+            .collect()
+    }
+
+    /// Get number of occupied positions in the *Moore neighbourhood* of the specified position, without allocating.
+    pub fn occupied_moore_neighbour_count(&self, position: &GridPosition) -> usize {
+        Direction3D26::ALL
+            .iter()
+            .filter(|direction| direction.is_planar())
+            .filter_map(|direction| direction.march_step(position, &self.size))
+            .filter(|neighbour| self.get_tile_at_position(neighbour).is_some())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::{GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::BasicIdentTileData;
+    use crate::tile::GridPosition;
+
+    use super::Direction3D26;
+
+    fn neighbours(position: &GridPosition, size: &GridSize) -> Vec<GridPosition> {
+        Direction3D26::ALL
+            .iter()
+            .filter_map(|direction| direction.march_step(position, size))
+            .collect()
+    }
+
+    #[test]
+    fn moore_neighbourhood_3d() {
+        let size = GridSize::new_xyz(4, 4, 4);
+
+        let interior = neighbours(&GridPosition::new_xyz(1, 2, 1), &size);
+        assert_eq!(26, interior.len());
+        assert!(!interior.contains(&GridPosition::new_xyz(1, 2, 1)));
+
+        let corner = neighbours(&GridPosition::new_xyz(0, 0, 0), &size);
+        assert_eq!(7, corner.len());
+        assert!(corner.contains(&GridPosition::new_xyz(1, 1, 1)));
+
+        assert_eq!(
+            8,
+            neighbours(&GridPosition::new_xy(1, 1), &GridSize::new_xy(3, 3)).len()
+        );
+    }
+
+    #[test]
+    fn direction_indices_and_opposites() {
+        for (idx, direction) in Direction3D26::ALL.iter().enumerate() {
+            assert_eq!(idx, direction.as_idx());
+            assert_ne!(*direction, direction.opposite());
+            assert_eq!(*direction, direction.opposite().opposite());
+            assert_eq!(25 - idx, direction.opposite().as_idx());
+        }
+    }
+
+    #[test]
+    fn map_moore_neighbour_positions() {
+        let map = GridMap2D::<BasicIdentTileData>::new(GridSize::new_xy(5, 5));
+        assert_eq!(
+            8,
+            map.get_moore_neighbour_positions(&GridPosition::new_xy(2, 2))
+                .len()
+        );
+        assert_eq!(
+            3,
+            map.get_moore_neighbour_positions(&GridPosition::new_xy(4, 0))
+                .len()
+        );
+    }
+
+    #[test]
+    fn map_occupied_moore_neighbour_count() {
+        let mut map = GridMap2D::new(GridSize::new_xy(3, 3));
+        for (x, y) in [(1, 1), (1, 0), (0, 0), (2, 2)] {
+            map.insert_data(&GridPosition::new_xy(x, y), BasicIdentTileData::tile_new(0));
+        }
+
+        assert_eq!(
+            3,
+            map.occupied_moore_neighbour_count(&GridPosition::new_xy(1, 1))
+        );
+        assert_eq!(1, map.occupied_neighbour_count(&GridPosition::new_xy(1, 1)));
+        assert_eq!(
+            3,
+            map.occupied_moore_neighbour_count(&GridPosition::new_xy(0, 1))
+        );
+    }
+}
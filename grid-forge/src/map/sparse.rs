@@ -0,0 +1,217 @@
+use crate::error::OutOfBoundsError;
+use crate::tile::{GridPosition, TileContainer, TileData};
+
+use super::{GridMap2D, GridSize};
+
+/// Sparse representation of the [`GridMap2D`], holding only its size and the occupied positions alongside their
+/// data.
+///
+/// Useful for storing mostly empty maps, like the output of the [`GridWalker2D`](crate::gen::walker::GridWalker2D),
+/// where the dense representation would consist mostly of empty positions.
+///
+/// With the `serde` feature enabled it can be serialized and deserialized, as long as its `Data` can. Positions are
+/// stored as `(x, y)` pairs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGridMap<Data>
+where
+    Data: TileData,
+{
+    size: GridSize,
+    tiles: Vec<(GridPosition, Data)>,
+}
+
+impl<Data> SparseGridMap<Data>
+where
+    Data: TileData,
+{
+    /// Creates new sparse map out of its `size` and occupied positions. Positions are not validated until
+    /// conversion into [`GridMap2D`] with [`GridMap2D::from_sparse`].
+    pub fn new(size: GridSize, tiles: Vec<(GridPosition, Data)>) -> Self {
+        Self { size, tiles }
+    }
+
+    pub fn size(&self) -> &GridSize {
+        &self.size
+    }
+
+    /// All occupied positions alongside their data.
+    pub fn tiles(&self) -> &[(GridPosition, Data)] {
+        &self.tiles
+    }
+}
+
+impl<Data> GridMap2D<Data>
+where
+    Data: TileData + Clone,
+{
+    /// Creates [`SparseGridMap`] containing only the occupied positions of the map, in ascending
+    /// [`GridPosition`] order.
+    pub fn to_sparse(&self) -> SparseGridMap<Data> {
+        SparseGridMap::new(
+            self.size,
+            self.iter_tiles_sorted()
+                .map(|tile| (tile.grid_position(), tile.as_ref().clone()))
+                .collect(),
+        )
+    }
+}
+
+impl<Data> GridMap2D<Data>
+where
+    Data: TileData,
+{
+    /// Creates the map out of [`SparseGridMap`]. Returns an error listing all positions out of bounds of its size,
+    /// if there are any.
+    pub fn from_sparse(sparse: SparseGridMap<Data>) -> Result<Self, OutOfBoundsError> {
+        let out_of_bounds = sparse
+            .tiles
+            .iter()
+            .map(|(position, _)| *position)
+            .filter(|position| !sparse.size.is_position_valid(position))
+            .collect::<Vec<_>>();
+        if !out_of_bounds.is_empty() {
+            return Err(OutOfBoundsError::new(out_of_bounds));
+        }
+
+        let mut map = Self::new(sparse.size);
+        for (position, data) in sparse.tiles {
+            map.insert_data(&position, data);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct SparseReprRef<'a, Data> {
+    size: (u32, u32),
+    tiles: Vec<((u32, u32), &'a Data)>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SparseRepr<Data> {
+    size: (u32, u32),
+    tiles: Vec<((u32, u32), Data)>,
+}
+
+#[cfg(feature = "serde")]
+impl<Data> serde::Serialize for SparseGridMap<Data>
+where
+    Data: TileData + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let repr = SparseReprRef {
+            size: (self.size.x(), self.size.y()),
+            tiles: self
+                .tiles
+                .iter()
+                .map(|(position, data)| (position.xy(), data))
+                .collect(),
+        };
+        serde::Serialize::serialize(&repr, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Data> serde::Deserialize<'de> for SparseGridMap<Data>
+where
+    Data: TileData + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let repr = <SparseRepr<Data> as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(Self::new(
+            GridSize::new_xy(repr.size.0, repr.size.1),
+            repr.tiles
+                .into_iter()
+                .map(|((x, y), data)| (GridPosition::new_xy(x, y), data))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::{GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::GridPosition;
+
+    use super::SparseGridMap;
+
+    /// Mostly empty map with only 5 occupied positions.
+    fn scattered_map() -> GridMap2D<BasicIdentTileData> {
+        let mut map = GridMap2D::new(GridSize::new_xy(40, 25));
+        for (x, y) in [(0, 0), (39, 24), (7, 3), (12, 20), (30, 1)] {
+            map.insert_data(
+                &GridPosition::new_xy(x, y),
+                BasicIdentTileData::tile_new((x + y) as u64),
+            );
+        }
+        map
+    }
+
+    fn ids(sparse: &SparseGridMap<BasicIdentTileData>) -> Vec<(GridPosition, u64)> {
+        sparse
+            .tiles()
+            .iter()
+            .map(|(position, data)| (*position, data.tile_type_id()))
+            .collect()
+    }
+
+    #[test]
+    fn sparse_round_trip() {
+        let map = scattered_map();
+        let size = *map.size();
+
+        let sparse = map.to_sparse();
+        assert_eq!(1000, size.max_tile_count());
+        assert_eq!(5, sparse.tiles().len());
+        assert_eq!(&size, sparse.size());
+
+        let restored = GridMap2D::from_sparse(sparse.clone()).unwrap();
+        assert_eq!(ids(&sparse), ids(&restored.to_sparse()));
+        assert_eq!(
+            63,
+            restored
+                .get_tile_at_position(&GridPosition::new_xy(39, 24))
+                .unwrap()
+                .as_ref()
+                .tile_type_id()
+        );
+    }
+
+    #[test]
+    fn sparse_out_of_bounds_rejected() {
+        let sparse = SparseGridMap::new(
+            GridSize::new_xy(4, 4),
+            vec![
+                (GridPosition::new_xy(3, 3), BasicIdentTileData::tile_new(1)),
+                (GridPosition::new_xy(4, 0), BasicIdentTileData::tile_new(2)),
+            ],
+        );
+
+        let err = GridMap2D::from_sparse(sparse).unwrap_err();
+        assert_eq!(&[GridPosition::new_xy(4, 0)], err.positions());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn sparse_serde_round_trip() {
+        let map = scattered_map();
+
+        let json = serde_json::to_string(&map.to_sparse()).unwrap();
+        let sparse: SparseGridMap<BasicIdentTileData> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map.size(), sparse.size());
+        assert_eq!(5, sparse.tiles().len());
+
+        let restored = GridMap2D::from_sparse(sparse).unwrap();
+        assert_eq!(ids(&map.to_sparse()), ids(&restored.to_sparse()));
+    }
+}
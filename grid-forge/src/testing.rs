@@ -0,0 +1,87 @@
+//! Helpers for writing tests against the maps produced by `grid-forge`, eg. for checking that changes to the generation
+//! code affected the output only in expected places.
+//!
+//! Available with the `testing` feature flag enabled.
+
+use crate::map::GridMap2D;
+use crate::tile::identifiable::IdentifiableTileData;
+use crate::tile::GridPosition;
+
+/// Asserts that the maps are equal, except for the `allowed` positions. Tiles are compared by their `tile_type_id`,
+/// while the empty positions are equal only to the other empty positions.
+///
+/// # Panics
+/// If the maps have different sizes, or there are differing positions not contained in `allowed` - listing all of
+/// them in the message.
+pub fn assert_maps_eq_except<Data: IdentifiableTileData>(
+    a: &GridMap2D<Data>,
+    b: &GridMap2D<Data>,
+    allowed: &[GridPosition],
+) {
+    assert_eq!(a.size(), b.size(), "compared maps have different sizes");
+
+    let tile_type_id_at = |map: &GridMap2D<Data>, position: &GridPosition| {
+        map.get_tile_at_position(position)
+            .map(|tile| tile.as_ref().tile_type_id())
+    };
+    let differing = a
+        .size()
+        .get_all_possible_positions()
+        .into_iter()
+        .filter(|position| {
+            !allowed.contains(position)
+                && tile_type_id_at(a, position) != tile_type_id_at(b, position)
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        differing.is_empty(),
+        "maps differ at {count} unexpected positions: {positions}",
+        count = differing.len(),
+        positions = differing
+            .iter()
+            .map(|position| position.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use crate::map::{GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::BasicIdentTileData;
+    use crate::tile::GridPosition;
+
+    use super::assert_maps_eq_except;
+
+    fn maps() -> (GridMap2D<BasicIdentTileData>, GridMap2D<BasicIdentTileData>) {
+        let a = GridMap2D::checkerboard(
+            GridSize::new_xy(4, 4),
+            BasicIdentTileData::tile_new(0),
+            BasicIdentTileData::tile_new(1),
+        );
+        let mut b = a.clone();
+        b.insert_data(&GridPosition::new_xy(1, 2), BasicIdentTileData::tile_new(5));
+        b.remove_tile_at_position(&GridPosition::new_xy(3, 3));
+        (a, b)
+    }
+
+    #[test]
+    fn passes_with_allowed_differences() {
+        let (a, b) = maps();
+        assert_maps_eq_except(&a, &a, &[]);
+        assert_maps_eq_except(
+            &a,
+            &b,
+            &[GridPosition::new_xy(3, 3), GridPosition::new_xy(1, 2)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "maps differ at 1 unexpected positions")]
+    fn fails_with_unexpected_difference() {
+        let (a, b) = maps();
+        assert_maps_eq_except(&a, &b, &[GridPosition::new_xy(1, 2)]);
+    }
+}
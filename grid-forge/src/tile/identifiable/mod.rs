@@ -17,6 +17,11 @@ use super::TileData;
 pub mod builders;
 pub mod collection;
 
+/// Derive macro generating [`TileData`] and [`IdentifiableTileData`] implementations. See the documentation of the
+/// `grid-forge-derive` crate for details.
+#[cfg(feature = "derive")]
+pub use grid_forge_derive::IdentifiableTileData;
+
 /// Its implementation makes the specific tile identifiable and discernable from other tile instances in regards to tile
 /// type. For the generative algorithms using this trait to match and select tiles, general rules of the tile identity
 /// when implementing this trait manually should be:
@@ -34,6 +39,7 @@ where
 
 /// Basic tile struct that implements [`IdentifiableTileData`], holding only the most basic information.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BasicIdentTileData {
     tile_type_id: u64,
 }
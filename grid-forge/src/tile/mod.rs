@@ -1,5 +1,8 @@
+use std::fmt::Display;
 use std::ops::{Add, AddAssign, Sub};
 
+use crate::map::GridSize;
+
 pub mod identifiable;
 
 #[derive(Debug)]
@@ -230,12 +233,143 @@ impl GridPosition {
         out
     }
 
+    /// Applies signed per-axis `delta` to the position, in order: `x`, `y`, `z`. Missing deltas are treated as `0`,
+    /// and the `z` delta is ignored for positions without `z` coordinate.
+    ///
+    /// Returns `None` if any coordinate would become negative or overflow.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::GridPosition;
+    ///
+    /// let position = GridPosition::new_xy(2, 3);
+    ///
+    /// assert_eq!(Some(GridPosition::new_xy(1, 5)), position.checked_offset(&[-1, 2]));
+    /// assert_eq!(None, position.checked_offset(&[-3, 0]));
+    /// ```
+    pub fn checked_offset(&self, delta: &[i32]) -> Option<Self> {
+        let delta_at = |idx: usize| delta.get(idx).copied().unwrap_or(0);
+
+        let x = self.x.checked_add_signed(delta_at(0))?;
+        let y = self.y.checked_add_signed(delta_at(1))?;
+        match self.z {
+            Some(z) => Some(Self::new_xyz(x, y, z.checked_add_signed(delta_at(2))?)),
+            None => Some(Self::new_xy(x, y)),
+        }
+    }
+
+    /// Flat index of the position within the `size`, as in [`GridSize::offset`].
+    ///
+    /// Returns `None` if the position is not valid within the `size`.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::{GridPosition, GridSize};
+    ///
+    /// let size = GridSize::new_xy(4, 3);
+    /// let position = GridPosition::new_xy(2, 1);
+    ///
+    /// assert_eq!(Some(7), position.to_index(&size));
+    /// assert_eq!(Some(position), GridPosition::from_index(&size, 7));
+    /// ```
+    pub fn to_index(&self, size: &GridSize) -> Option<usize> {
+        size.offset(self)
+    }
+
+    /// Position at the flat `index` within the `size`, as in [`GridSize::pos_from_offset`].
+    ///
+    /// Returns `None` if the index is out of the `size` bounds.
+    pub fn from_index(size: &GridSize, index: usize) -> Option<Self> {
+        size.pos_from_offset(index)
+    }
+
+    /// Iterates over positions valid within the `size` in the expanding rings around the `center`, starting with the
+    /// `center` itself. For 2D positions each ring is traversed clockwise as a square spiral, beginning at its upper
+    /// left corner. For 3D positions the rings are the cube shells, traversed layer by layer.
+    ///
+    /// Positions are yielded in non-decreasing Chebyshev distance from the `center`.
+    ///
+    /// # Examples
+    /// ```
+    /// use grid_forge::{GridPosition, GridSize};
+    ///
+    /// let size = GridSize::new_xy(3, 3);
+    /// let spiral = GridPosition::spiral_from(GridPosition::new_xy(1, 1), &size).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(GridPosition::new_xy(1, 1), spiral[0]);
+    /// assert_eq!(GridPosition::new_xy(0, 0), spiral[1]);
+    /// assert_eq!(GridPosition::new_xy(1, 0), spiral[2]);
+    /// assert_eq!(9, spiral.len());
+    /// ```
+    pub fn spiral_from(center: GridPosition, size: &GridSize) -> impl Iterator<Item = Self> {
+        let size = *size;
+        let max_distance =
+            |coord: u32, len: u32| coord.max(len.saturating_sub(1).saturating_sub(coord));
+        let max_ring = max_distance(center.x, size.x())
+            .max(max_distance(center.y, size.y()))
+            .max(match (center.z, size.z()) {
+                (Some(z), Some(len)) => max_distance(z, len),
+                _ => 0,
+            })
+            .min(i32::MAX as u32) as i32;
+        let layered = center.z.is_some();
+
+        (0..=max_ring)
+            .flat_map(move |ring| {
+                let layers = if layered { -ring..=ring } else { 0..=0 };
+                layers.flat_map(move |dz| {
+                    let square = if dz.abs() == ring {
+                        Self::square_offsets(ring)
+                    } else {
+                        Self::ring_offsets(ring)
+                    };
+                    square.into_iter().map(move |(dx, dy)| [dx, dy, dz])
+                })
+            })
+            .filter_map(move |delta| center.checked_offset(&delta))
+            .filter(move |position| {
+                size.is_position_valid(position)
+                    && match (position.z, size.z()) {
+                        (Some(z), Some(len)) => z < len,
+                        _ => true,
+                    }
+            })
+    }
+
+    /// Offsets of the positions on the square ring of given Chebyshev `distance`, clockwise from the upper left corner.
+    fn ring_offsets(distance: i32) -> Vec<(i32, i32)> {
+        if distance == 0 {
+            return vec![(0, 0)];
+        }
+        let top = (-distance..=distance).map(|dx| (dx, -distance));
+        let right = (-distance + 1..=distance).map(|dy| (distance, dy));
+        let bottom = (-distance..distance).rev().map(|dx| (dx, distance));
+        let left = (-distance + 1..distance).rev().map(|dy| (-distance, dy));
+        top.chain(right).chain(bottom).chain(left).collect()
+    }
+
+    /// Offsets of all positions within the square of given Chebyshev `distance`, row by row.
+    fn square_offsets(distance: i32) -> Vec<(i32, i32)> {
+        (-distance..=distance)
+            .flat_map(|dy| (-distance..=distance).map(move |dx| (dx, dy)))
+            .collect()
+    }
+
     /// Filter the `pos` vector, removing from it all positions contained within `to_filter`.
     pub fn filter_positions(pos: &mut Vec<GridPosition>, to_filter: &[GridPosition]) {
         pos.retain(|p| !to_filter.contains(p));
     }
 }
 
+impl Display for GridPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.z {
+            Some(z) => write!(f, "({}, {}, {})", self.x, self.y, z),
+            None => write!(f, "({}, {})", self.x, self.y),
+        }
+    }
+}
+
 impl Add for GridPosition {
     type Output = Self;
 
@@ -272,9 +406,186 @@ impl AddAssign for GridPosition {
     }
 }
 
+/// Clockwise rotation of the tile, in quarter turns.
+///
+/// Shared between the generative algorithms, where it describes the rotation of the tile in relation to the base
+/// rotation of its [`TileSymmetryGroup`](crate::gen::collapse::singular::TileSymmetryGroup), and the visualisation,
+/// rotating the tile pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TileRotation {
+    #[default]
+    None,
+    R90,
+    R180,
+    R270,
+}
+
+impl TileRotation {
+    /// All rotations, in order of the clockwise quarter turns.
+    pub const ALL: [TileRotation; 4] = [Self::None, Self::R90, Self::R180, Self::R270];
+
+    /// Returns the pixels rotated clockwise. Rotations by `90` and `270` degrees are possible only for square tiles,
+    /// returning `None` otherwise.
+    pub fn rotate_pixels<P: Copy, const WIDTH: usize, const HEIGHT: usize>(
+        &self,
+        pixels: &[[P; WIDTH]; HEIGHT],
+    ) -> Option<[[P; WIDTH]; HEIGHT]> {
+        if matches!(self, TileRotation::R90 | TileRotation::R270) && WIDTH != HEIGHT {
+            return None;
+        }
+        let mut rotated = *pixels;
+        for (y, row) in rotated.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = match self {
+                    TileRotation::None => pixels[y][x],
+                    TileRotation::R90 => pixels[WIDTH - 1 - x][y],
+                    TileRotation::R180 => pixels[HEIGHT - 1 - y][WIDTH - 1 - x],
+                    TileRotation::R270 => pixels[x][HEIGHT - 1 - y],
+                };
+            }
+        }
+        Some(rotated)
+    }
+}
+
 /// Trait gathering the containers for [`TileData`] outside of the [`GridMap2D`](crate::map::GridMap2D).
 ///
 /// Allows accessing all the data not contained within tile data, making sense only in context of the grid map.
 pub trait TileContainer {
     fn grid_position(&self) -> GridPosition;
 }
+
+#[cfg(test)]
+mod test {
+    use crate::map::GridSize;
+
+    use super::GridPosition;
+
+    fn chebyshev(a: &GridPosition, b: &GridPosition) -> u32 {
+        let z_distance = match (a.z(), b.z()) {
+            (Some(za), Some(zb)) => za.abs_diff(*zb),
+            _ => 0,
+        };
+        a.x()
+            .abs_diff(*b.x())
+            .max(a.y().abs_diff(*b.y()))
+            .max(z_distance)
+    }
+
+    #[test]
+    fn spiral_expands_from_center() {
+        let size = GridSize::new_xy(6, 4);
+        let center = GridPosition::new_xy(1, 2);
+        let spiral = GridPosition::spiral_from(center, &size).collect::<Vec<_>>();
+
+        assert_eq!(center, spiral[0]);
+        assert_eq!(size.max_tile_count(), spiral.len());
+        let mut distinct = spiral.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(spiral.len(), distinct.len());
+        assert!(spiral
+            .windows(2)
+            .all(|pair| chebyshev(&center, &pair[0]) <= chebyshev(&center, &pair[1])));
+        // Neighbouring positions on the ring are adjacent.
+        assert!(spiral[1..9]
+            .windows(2)
+            .all(|pair| chebyshev(&pair[0], &pair[1]) == 1));
+    }
+
+    #[test]
+    fn spiral_expands_in_shells() {
+        let size = GridSize::new_xyz(3, 4, 5);
+        let center = GridPosition::new_xyz(1, 1, 3);
+        let spiral = GridPosition::spiral_from(center, &size).collect::<Vec<_>>();
+
+        assert_eq!(center, spiral[0]);
+        assert_eq!(size.max_tile_count(), spiral.len());
+        assert_eq!(
+            27,
+            spiral
+                .iter()
+                .filter(|pos| chebyshev(&center, pos) <= 1)
+                .count()
+        );
+        assert!(spiral
+            .windows(2)
+            .all(|pair| chebyshev(&center, &pair[0]) <= chebyshev(&center, &pair[1])));
+    }
+
+    #[test]
+    fn checked_offset_2d() {
+        let position = GridPosition::new_xy(2, 3);
+
+        assert_eq!(
+            Some(GridPosition::new_xy(5, 4)),
+            position.checked_offset(&[3, 1])
+        );
+        assert_eq!(
+            Some(GridPosition::new_xy(0, 1)),
+            position.checked_offset(&[-2, -2])
+        );
+        assert_eq!(None, position.checked_offset(&[0, -4]));
+        assert_eq!(
+            Some(GridPosition::new_xy(2, 3)),
+            position.checked_offset(&[0, 0, -10])
+        );
+    }
+
+    #[test]
+    fn index_round_trip_2d() {
+        let size = GridSize::new_xy(5, 3);
+        for position in [
+            GridPosition::new_xy(0, 0),
+            GridPosition::new_xy(4, 2),
+            GridPosition::new_xy(1, 2),
+            GridPosition::new_xy(3, 0),
+        ] {
+            let index = position.to_index(&size).unwrap();
+            assert!(index < size.max_tile_count());
+            assert_eq!(Some(position), GridPosition::from_index(&size, index));
+        }
+        assert_eq!(None, GridPosition::new_xy(5, 0).to_index(&size));
+        assert_eq!(None, GridPosition::from_index(&size, 15));
+    }
+
+    #[test]
+    fn index_round_trip_3d() {
+        let size = GridSize::new_xyz(3, 4, 2);
+        for position in [
+            GridPosition::new_xyz(0, 0, 0),
+            GridPosition::new_xyz(2, 3, 1),
+            GridPosition::new_xyz(1, 2, 1),
+            GridPosition::new_xyz(2, 0, 0),
+        ] {
+            let index = position.to_index(&size).unwrap();
+            assert_eq!(Some(position), GridPosition::from_index(&size, index));
+        }
+        let indices = size
+            .iter_positions_offset_order()
+            .map(|position| position.to_index(&size).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!((0..24).collect::<Vec<_>>(), indices);
+        assert_eq!(None, GridPosition::new_xy(0, 0).to_index(&size));
+        assert_eq!(None, GridPosition::from_index(&size, 24));
+    }
+
+    #[test]
+    fn checked_offset_3d() {
+        let position = GridPosition::new_xyz(2, 3, 1);
+
+        assert_eq!(
+            Some(GridPosition::new_xyz(3, 5, 4)),
+            position.checked_offset(&[1, 2, 3])
+        );
+        assert_eq!(
+            Some(GridPosition::new_xyz(0, 0, 0)),
+            position.checked_offset(&[-2, -3, -1])
+        );
+        assert_eq!(None, position.checked_offset(&[0, 0, -2]));
+        assert_eq!(
+            Some(GridPosition::new_xyz(4, 3, 1)),
+            position.checked_offset(&[2])
+        );
+    }
+}
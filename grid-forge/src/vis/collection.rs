@@ -5,7 +5,7 @@ use image::{ImageBuffer, Pixel};
 
 use crate::map::{GridMap2D, GridSize};
 use crate::tile::identifiable::IdentifiableTileData;
-use crate::tile::{GridPosition, TileContainer};
+use crate::tile::{GridPosition, TileContainer, TileRotation};
 
 use super::error::VisError;
 use super::ops::create_tile_id_from_pixels;
@@ -45,6 +45,10 @@ where
     rev: HashMap<u64, u64>,
     /// Optional specification for pixels which should be omitted during load.
     empty: Option<EmptyTile<P, WIDTH, HEIGHT>>,
+    /// If `true`, rotated variants of registered pixels are mapped to the same `type_id`.
+    dedup_rotations: bool,
+    /// Rotation applied to the registered pixels to get the pixels with the created `type_id` of the key.
+    rotations: HashMap<u64, TileRotation>,
 }
 
 impl<P, const WIDTH: usize, const HEIGHT: usize> Default for VisCollection<P, WIDTH, HEIGHT>
@@ -56,6 +60,8 @@ where
             inner: Default::default(),
             rev: Default::default(),
             empty: None,
+            dedup_rotations: false,
+            rotations: Default::default(),
         }
     }
 }
@@ -78,6 +84,18 @@ where
         }
     }
 
+    /// Sets if the rotated variants of registered pixels should be treated as the same tile.
+    ///
+    /// When enabled, each registered pixels have also their clockwise rotations mapped to the same `tile_type_id`,
+    /// so [`load_gridmap_identifiable_auto`](super::ops::load_gridmap_identifiable_auto) won't register rotated tiles
+    /// as new ones, and the applied rotation can be retrieved with [`Self::get_rotation_by_pixels`]. Only pixels
+    /// registered after enabling it are affected. For non-square tiles only the rotation by `180` degrees is considered.
+    ///
+    /// Tiles are always drawn with the pixels registered for their `tile_type_id`, so the rotation is lost on output.
+    pub fn set_dedup_rotations(&mut self, dedup: bool) {
+        self.dedup_rotations = dedup;
+    }
+
     /// Add pixels for [`IdentifiableTileData`]-implementing [`VisTile2D`] if none were collected yet.
     ///
     /// # Returns
@@ -111,6 +129,7 @@ where
             if let Some(derived_id) = to_remove {
                 rev.remove(&derived_id);
             }
+            self.insert_rotated(&pix, tile.as_ref().tile_type_id());
             return VisCollectionOutcome::Added;
         }
         VisCollectionOutcome::Existing
@@ -140,6 +159,7 @@ where
                 create_tile_id_from_pixels(&pix),
                 tile.as_ref().tile_type_id(),
             );
+            self.insert_rotated(&pix, tile_id);
             match self.inner.insert(tile_id, pix) {
                 Some(pixels) => VisCollectionOutcome::Replaced(pixels),
                 None => VisCollectionOutcome::Added,
@@ -184,6 +204,7 @@ where
                 create_tile_id_from_pixels(&pixels),
                 tile.as_ref().tile_type_id(),
             );
+            self.insert_rotated(&pixels, tile.as_ref().tile_type_id());
             return Ok(VisCollectionOutcome::Added);
         }
         Ok(VisCollectionOutcome::Existing)
@@ -237,6 +258,7 @@ where
             e.insert(pixels);
             self.rev
                 .insert(create_tile_id_from_pixels(&pixels), tile_id);
+            self.insert_rotated(&pixels, tile_id);
             return VisCollectionOutcome::Added;
         }
         VisCollectionOutcome::Existing
@@ -252,17 +274,74 @@ where
         }
         self.rev
             .insert(create_tile_id_from_pixels(&pixels), tile_id);
+        self.insert_rotated(&pixels, tile_id);
         match self.inner.insert(tile_id, pixels) {
             Some(pixels) => VisCollectionOutcome::Replaced(pixels),
             None => VisCollectionOutcome::Added,
         }
     }
 
+    /// Registers pixels of all tiles from the tileset sheet, with `margin` pixels around the whole sheet and
+    /// `spacing` pixels between the neighbouring tiles. Each tile gets the `tile_type_id` equal to its index in the
+    /// sheet, counting left-to-right and top-to-bottom, and is added as with [`Self::add_tile_pixels_manual`].
+    ///
+    /// Incomplete tiles on the right and bottom edges of the sheet are skipped.
+    ///
+    /// # Returns
+    /// - `tile_type_id`s of the tiles which were added.
+    pub fn load_sheet(
+        &mut self,
+        buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
+        margin: u32,
+        spacing: u32,
+    ) -> Result<Vec<u64>, VisError<WIDTH, HEIGHT>> {
+        let count = |image_len: u32, tile_len: u32| {
+            (image_len.saturating_sub(2 * margin) + spacing) / (tile_len + spacing)
+        };
+        let cols = count(buffer.width(), WIDTH as u32);
+        let rows = count(buffer.height(), HEIGHT as u32);
+
+        let mut added = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let x_pos = margin + col * (WIDTH as u32 + spacing);
+                let y_pos = margin + row * (HEIGHT as u32 + spacing);
+                let mut pixels = [[P::pix_default(); WIDTH]; HEIGHT];
+                for (y, pixels_row) in pixels.iter_mut().enumerate() {
+                    for (x, pixel) in pixels_row.iter_mut().enumerate() {
+                        let pixel_pos = (x_pos + x as u32, y_pos + y as u32);
+                        *pixel = *buffer
+                            .get_pixel_checked(pixel_pos.0, pixel_pos.1)
+                            .ok_or_else(|| {
+                                VisError::new_io(true, GridPosition::new_xy(col, row), pixel_pos)
+                            })?;
+                    }
+                }
+                let tile_id = (row * cols + col) as u64;
+                if let VisCollectionOutcome::Added = self.add_tile_pixels_manual(tile_id, pixels) {
+                    added.push(tile_id);
+                }
+            }
+        }
+        Ok(added)
+    }
+
     //----- Output -----//
     pub fn get_tile_id_by_pixels(&self, pixels: &[[P; WIDTH]; HEIGHT]) -> Option<&u64> {
         self.rev.get(&create_tile_id_from_pixels(pixels))
     }
 
+    /// Returns the rotation which needs to be applied to the pixels registered for the `tile_type_id` retrieved by
+    /// [`Self::get_tile_id_by_pixels`] to get the provided `pixels`.
+    ///
+    /// Returns `None` if the pixels are not registered, or were registered before enabling
+    /// [`Self::set_dedup_rotations`].
+    pub fn get_rotation_by_pixels(&self, pixels: &[[P; WIDTH]; HEIGHT]) -> Option<TileRotation> {
+        self.rotations
+            .get(&create_tile_id_from_pixels(pixels))
+            .copied()
+    }
+
     pub fn is_empty(&self, check_pixels: &[[P; WIDTH]; HEIGHT]) -> bool {
         if let Some(EmptyTile { tile_id: _, pixels }) = self.empty {
             &pixels == check_pixels
@@ -317,6 +396,34 @@ where
         Ok(pixels)
     }
 
+    /// Returns the `tile_type_id` for pixels read during automatic loading: their created `type_id`, or the one
+    /// already registered for their rotated variant if rotations are deduplicated.
+    pub(crate) fn auto_tile_id(&self, pixels: &[[P; WIDTH]; HEIGHT]) -> u64 {
+        let created = create_tile_id_from_pixels(pixels);
+        if !self.dedup_rotations {
+            return created;
+        }
+        self.rev.get(&created).copied().unwrap_or(created)
+    }
+
+    fn insert_rotated(&mut self, pixels: &[[P; WIDTH]; HEIGHT], tile_id: u64) {
+        if !self.dedup_rotations {
+            return;
+        }
+        self.rotations
+            .insert(create_tile_id_from_pixels(pixels), TileRotation::None);
+        for rotation in &TileRotation::ALL[1..] {
+            let Some(rotated) = rotation.rotate_pixels(pixels) else {
+                continue;
+            };
+            let rotated_id = create_tile_id_from_pixels(&rotated);
+            if let Entry::Vacant(e) = self.rev.entry(rotated_id) {
+                e.insert(tile_id);
+                self.rotations.insert(rotated_id, *rotation);
+            }
+        }
+    }
+
     fn check_empty_id(empty_tile: &Option<EmptyTile<P, WIDTH, HEIGHT>>, tile_id: u64) -> bool {
         if let Some(empty) = empty_tile {
             return empty.tile_id == tile_id;
@@ -334,3 +441,126 @@ where
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::map::GridSize;
+    use crate::tile::identifiable::builders::IdentTileTraitBuilder;
+    use crate::tile::identifiable::{BasicIdentTileData, IdentifiableTileData};
+    use crate::tile::GridPosition;
+    use crate::vis::ops::{init_map_image_buffer, load_gridmap_identifiable_auto};
+    use crate::vis::{write_tile, DefaultVisPixel};
+
+    use super::{TileRotation, VisCollection};
+
+    fn corner_tile() -> [[DefaultVisPixel; 4]; 4] {
+        let mut pixels = [[DefaultVisPixel::from([0, 0, 0]); 4]; 4];
+        pixels[0][0] = DefaultVisPixel::from([255, 0, 0]);
+        pixels[0][1] = DefaultVisPixel::from([0, 255, 0]);
+        pixels
+    }
+
+    fn load_rotated(dedup: bool) -> (VisCollection<DefaultVisPixel, 4, 4>, Vec<u64>) {
+        let size = GridSize::new_xy(4, 1);
+        let mut buffer = init_map_image_buffer::<DefaultVisPixel, 4, 4>(&size);
+        for (x, rotation) in TileRotation::ALL.iter().enumerate() {
+            let pixels = rotation.rotate_pixels(&corner_tile()).unwrap();
+            write_tile(&mut buffer, GridPosition::new_xy(x as u32, 0), &pixels).unwrap();
+        }
+
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        collection.set_dedup_rotations(dedup);
+        let map = load_gridmap_identifiable_auto::<BasicIdentTileData, _, _, 4, 4>(
+            &buffer,
+            &mut collection,
+            &IdentTileTraitBuilder::default(),
+        )
+        .unwrap();
+
+        let ids = map
+            .iter_tiles_sorted()
+            .map(|tile| tile.as_ref().tile_type_id())
+            .collect();
+        (collection, ids)
+    }
+
+    #[test]
+    fn rotate_pixels_full_turn() {
+        let pixels = corner_tile();
+        let r90 = TileRotation::R90.rotate_pixels(&pixels).unwrap();
+        assert_eq!(DefaultVisPixel::from([255, 0, 0]), r90[0][3]);
+        assert_eq!(DefaultVisPixel::from([0, 255, 0]), r90[1][3]);
+        assert_eq!(
+            TileRotation::R180.rotate_pixels(&pixels),
+            TileRotation::R90.rotate_pixels(&r90)
+        );
+        assert_eq!(Some(pixels), TileRotation::R270.rotate_pixels(&r90));
+        assert!(TileRotation::R90
+            .rotate_pixels(&[[DefaultVisPixel::from([0, 0, 0]); 4]; 2])
+            .is_none());
+    }
+
+    #[test]
+    fn rotated_tiles_deduplicated() {
+        let (collection, ids) = load_rotated(true);
+        assert_eq!(4, ids.len());
+        assert!(ids.iter().all(|id| *id == ids[0]));
+
+        for rotation in TileRotation::ALL {
+            let pixels = rotation.rotate_pixels(&corner_tile()).unwrap();
+            assert_eq!(Some(&ids[0]), collection.get_tile_id_by_pixels(&pixels));
+            assert_eq!(Some(rotation), collection.get_rotation_by_pixels(&pixels));
+        }
+
+        let (_, ids) = load_rotated(false);
+        let mut distinct = ids.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(4, distinct.len());
+    }
+
+    #[test]
+    fn sheet_with_margin_and_spacing() {
+        let separator = DefaultVisPixel::from([255, 0, 255]);
+        let tile = |id: u8| [[DefaultVisPixel::from([id, id, id]); 2]; 2];
+        // 3 x 2 tiles of 2 x 2 pixels, with 1px margin and spacing.
+        let mut buffer = image::ImageBuffer::from_pixel(10, 7, separator);
+        for (id, (col, row)) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+            .into_iter()
+            .enumerate()
+        {
+            for (y, pixels_row) in tile(id as u8 * 10).iter().enumerate() {
+                for (x, pixel) in pixels_row.iter().enumerate() {
+                    buffer.put_pixel(1 + col * 3 + x as u32, 1 + row * 3 + y as u32, *pixel);
+                }
+            }
+        }
+
+        let mut collection = VisCollection::<DefaultVisPixel, 2, 2>::default();
+        collection.set_empty_tile_pixels(Some(tile(40)));
+        let added = collection.load_sheet(&buffer, 1, 1).unwrap();
+
+        assert_eq!(vec![0, 1, 2, 3, 5], added);
+        for id in [0, 1, 2, 3, 5] {
+            assert_eq!(
+                Some(&id),
+                collection.get_tile_id_by_pixels(&tile(id as u8 * 10))
+            );
+        }
+        assert!(collection.get_tile_id_by_pixels(&tile(40)).is_none());
+    }
+
+    #[test]
+    fn symmetric_tile_single_rotation() {
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        collection.set_dedup_rotations(true);
+        let pixels = [[DefaultVisPixel::from([10, 20, 30]); 4]; 4];
+        collection.add_tile_pixels_manual(7, pixels);
+
+        assert_eq!(Some(&7), collection.get_tile_id_by_pixels(&pixels));
+        assert_eq!(
+            Some(TileRotation::None),
+            collection.get_rotation_by_pixels(&pixels)
+        );
+    }
+}
@@ -99,6 +99,65 @@ where
     Ok(())
 }
 
+/// Derives [`AdjacencyRules`](crate::gen::collapse::singular::AdjacencyRules) from the tileset atlas with color-coded
+/// tile edges, as in *Wang* edge tilesets.
+///
+/// Atlas is split into tiles of `WIDTH` x `HEIGHT` pixels, each of them getting the `tile_type_id` equal to its index
+/// in the atlas, counting left-to-right and top-to-bottom. Tiles are deemed adjacent in given direction if the
+/// pixel strip on the edge of the tile is the same as the strip on the opposite edge of the neighbour.
+///
+/// Results in [`VisError`] if the atlas size is not a multiple of the tile size.
+#[cfg(feature = "gen")]
+pub fn rules_from_edge_atlas<Data, P, const WIDTH: usize, const HEIGHT: usize>(
+    image_buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
+) -> Result<crate::gen::collapse::singular::AdjacencyRules<Data>, VisError<WIDTH, HEIGHT>>
+where
+    Data: crate::tile::identifiable::IdentifiableTileData,
+    P: PixelWithDefault,
+{
+    use crate::map::GridDir;
+
+    if image_buffer.width() % WIDTH as u32 != 0 || image_buffer.height() % HEIGHT as u32 != 0 {
+        return Err(VisError::new_grid_load(
+            image_buffer.width(),
+            image_buffer.height(),
+        ));
+    }
+    let cols = image_buffer.width() / WIDTH as u32;
+    let rows = image_buffer.height() / HEIGHT as u32;
+
+    let mut tiles = Vec::new();
+    for y in 0..rows {
+        for x in 0..cols {
+            let mut pixels = [[P::pix_default(); WIDTH]; HEIGHT];
+            read_tile(&mut pixels, image_buffer, &GridPosition::new_xy(x, y))?;
+            tiles.push(pixels);
+        }
+    }
+
+    let edge = |pixels: &[[P; WIDTH]; HEIGHT], direction: GridDir| -> Vec<P> {
+        match direction {
+            GridDir::UP => pixels[0].to_vec(),
+            GridDir::DOWN => pixels[HEIGHT - 1].to_vec(),
+            GridDir::LEFT => pixels.iter().map(|row| row[0]).collect(),
+            GridDir::RIGHT => pixels.iter().map(|row| row[WIDTH - 1]).collect(),
+        }
+    };
+
+    let mut rules = crate::gen::collapse::singular::AdjacencyRules::default();
+    for (tile_id, tile) in tiles.iter().enumerate() {
+        for (adjacent_id, adjacent) in tiles.iter().enumerate() {
+            for direction in GridDir::ALL_2D {
+                if edge(tile, *direction) == edge(adjacent, direction.opposite()) {
+                    rules.add_adjacency_raw(tile_id as u64, adjacent_id as u64, *direction);
+                }
+            }
+        }
+    }
+
+    Ok(rules)
+}
+
 /// Default pixel type used by `grid_forge`.
 pub type DefaultVisPixel = Rgb<u8>;
 
@@ -264,6 +323,95 @@ where
     Ok(())
 }
 
+/// Exports the [`CollapsedGrid`](crate::gen::collapse::CollapsedGrid) as a map in JSON format of the *Tiled* map
+/// editor, with a single tile layer and tiles of `WIDTH` x `HEIGHT` pixels.
+///
+/// Each tile is given the global id of `tile_type_id + tileset_firstgid` within the external tileset at
+/// `tileset_source`, while the empty positions are given the id of `0`.
+///
+/// Results in [`std::io::Error`] of [`InvalidInput`](std::io::ErrorKind::InvalidInput) kind if the global id of any
+/// tile would exceed the range supported by *Tiled*.
+#[cfg(feature = "gen")]
+pub fn export_tiled<const WIDTH: usize, const HEIGHT: usize>(
+    grid: &crate::gen::collapse::CollapsedGrid,
+    tileset_firstgid: u32,
+    tileset_source: &str,
+    out: &mut impl std::io::Write,
+) -> std::io::Result<()> {
+    use crate::tile::identifiable::IdentifiableTileData;
+
+    /// Upper bits of the global tile id are reserved by *Tiled* for flipping flags.
+    const MAX_GID: u64 = (1 << 29) - 1;
+
+    let map = grid.as_ref();
+    let (width, height) = (map.size().x(), map.size().y());
+
+    let mut gids = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let gid = match map.get_tile_at_position(&GridPosition::new_xy(x, y)) {
+                Some(tile) => tile
+                    .as_ref()
+                    .tile_type_id()
+                    .checked_add(tileset_firstgid as u64)
+                    .filter(|gid| *gid <= MAX_GID)
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!(
+                                "global id of tile at position ({x}, {y}) with type id {} exceeds {MAX_GID}",
+                                tile.as_ref().tile_type_id()
+                            ),
+                        )
+                    })?,
+                None => 0,
+            };
+            gids.push(gid.to_string());
+        }
+    }
+
+    write!(
+        out,
+        concat!(
+            "{{\"type\":\"map\",\"version\":\"1.10\",\"orientation\":\"orthogonal\",",
+            "\"renderorder\":\"right-down\",\"infinite\":false,",
+            "\"width\":{width},\"height\":{height},\"tilewidth\":{tile_width},\"tileheight\":{tile_height},",
+            "\"nextlayerid\":2,\"nextobjectid\":1,",
+            "\"layers\":[{{\"id\":1,\"name\":\"Tile Layer 1\",\"type\":\"tilelayer\",",
+            "\"x\":0,\"y\":0,\"width\":{width},\"height\":{height},\"opacity\":1,\"visible\":true,",
+            "\"data\":[{data}]}}],",
+            "\"tilesets\":[{{\"firstgid\":{firstgid},\"source\":{source}}}]}}"
+        ),
+        width = width,
+        height = height,
+        tile_width = WIDTH,
+        tile_height = HEIGHT,
+        data = gids.join(","),
+        firstgid = tileset_firstgid,
+        source = json_string(tileset_source),
+    )
+}
+
+/// Quotes and escapes the string to be used as a JSON string value.
+#[cfg(feature = "gen")]
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 #[cfg(test)]
 mod test {
     use image::{ImageBuffer, Pixel, Rgb};
@@ -382,4 +530,92 @@ mod test {
             }
         }
     }
+
+    #[cfg(feature = "gen")]
+    #[test]
+    fn export_tiled_json() {
+        use crate::gen::collapse::{CollapsedGrid, CollapsedTileData};
+        use crate::map::GridSize;
+
+        let mut grid = CollapsedGrid::new(GridSize::new_xy(3, 2));
+        grid.insert_data(&GridPosition::new_xy(0, 0), CollapsedTileData::new(0));
+        grid.insert_data(&GridPosition::new_xy(2, 0), CollapsedTileData::new(4));
+        grid.insert_data(&GridPosition::new_xy(1, 1), CollapsedTileData::new(2));
+
+        let mut out = Vec::new();
+        super::export_tiled::<16, 16>(&grid, 1, "tiles \"a\\b\"\n\u{1}.tsx", &mut out).unwrap();
+        let json = serde_json::from_slice::<serde_json::Value>(&out).unwrap();
+
+        assert_eq!(3, json["width"].as_u64().unwrap());
+        assert_eq!(2, json["height"].as_u64().unwrap());
+        assert_eq!(16, json["tilewidth"].as_u64().unwrap());
+        assert_eq!(16, json["tileheight"].as_u64().unwrap());
+        assert_eq!(1, json["tilesets"][0]["firstgid"].as_u64().unwrap());
+        assert_eq!(
+            "tiles \"a\\b\"\n\u{1}.tsx",
+            json["tilesets"][0]["source"].as_str().unwrap()
+        );
+
+        let layers = json["layers"].as_array().unwrap();
+        assert_eq!(1, layers.len());
+        assert_eq!("tilelayer", layers[0]["type"].as_str().unwrap());
+        let gids = layers[0]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|gid| gid.as_u64().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 0, 5, 0, 3, 0], gids);
+
+        let mut overflowing = CollapsedGrid::new(GridSize::new_xy(1, 1));
+        overflowing.insert_data(
+            &GridPosition::new_xy(0, 0),
+            CollapsedTileData::new(u64::MAX / 2),
+        );
+        assert!(
+            super::export_tiled::<16, 16>(&overflowing, 1, "tiles.tsx", &mut Vec::new()).is_err()
+        );
+        overflowing.insert_data(
+            &GridPosition::new_xy(0, 0),
+            CollapsedTileData::new(u64::MAX),
+        );
+        assert!(
+            super::export_tiled::<16, 16>(&overflowing, 1, "tiles.tsx", &mut Vec::new()).is_err()
+        );
+    }
+
+    #[cfg(feature = "gen")]
+    #[test]
+    fn edge_atlas_rules() {
+        use crate::map::GridDir;
+        use crate::tile::identifiable::BasicIdentTileData;
+
+        let red = DefaultVisPixel::from([255, 0, 0]);
+        let blue = DefaultVisPixel::from([0, 0, 255]);
+
+        // Two 3x3 tiles: first with blue right edge, second with blue left edge.
+        let mut atlas = ImageBuffer::from_pixel(6, 3, red);
+        for y in 0..3 {
+            atlas.put_pixel(2, y, blue);
+            atlas.put_pixel(3, y, blue);
+        }
+
+        let rules =
+            super::rules_from_edge_atlas::<BasicIdentTileData, DefaultVisPixel, 3, 3>(&atlas)
+                .unwrap();
+
+        assert!(rules.is_valid_at_dir(0, GridDir::RIGHT, 1));
+        assert!(rules.is_valid_at_dir(1, GridDir::LEFT, 0));
+        assert!(rules.is_valid_at_dir(1, GridDir::RIGHT, 0));
+        assert!(!rules.is_valid_at_dir(0, GridDir::RIGHT, 0));
+        assert!(!rules.is_valid_at_dir(1, GridDir::RIGHT, 1));
+        assert!(rules.is_valid_at_dir(0, GridDir::UP, 0));
+        assert!(!rules.is_valid_at_dir(0, GridDir::UP, 1));
+
+        let wrong_size = ImageBuffer::from_pixel(5, 3, red);
+        assert!(
+            super::rules_from_edge_atlas::<BasicIdentTileData, DefaultVisPixel, 3, 3>(&wrong_size)
+                .is_err()
+        );
+    }
 }
@@ -2,11 +2,13 @@
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 
-use image::{ImageBuffer, Pixel};
+use image::{ImageBuffer, Pixel, Primitive};
+use num_traits::NumCast;
 
 use crate::map::{GridMap2D, GridSize};
 use crate::tile::identifiable::builders::IdentTileBuilder;
 use crate::tile::identifiable::IdentifiableTileData;
+use crate::tile::TileContainer;
 
 use super::collection::VisCollection;
 use super::error::VisError;
@@ -25,6 +27,9 @@ use super::{PixelWithDefault, VisTile2D, VisTileData};
 /// # Warning
 /// As the `tile_type_id` **is automatically calculated** with this function on basis of pixels, it won't work with specific,
 /// manually declared identifiers. In this case, you need to use [`load_gridmap_identifiable_manual`].
+///
+/// If [`VisCollection::set_dedup_rotations`] is enabled, rotated variants of already registered tiles are loaded with
+/// their `tile_type_id`.
 pub fn load_gridmap_identifiable_auto<Data, P, B, const WIDTH: usize, const HEIGHT: usize>(
     image_buffer: &ImageBuffer<P, Vec<P::Subpixel>>,
     collection: &mut VisCollection<P, WIDTH, HEIGHT>,
@@ -43,7 +48,7 @@ where
             image_buffer,
             &position,
         )?;
-        let tile = builder.build_tile_unchecked(position, create_tile_id_from_pixels(&pixels));
+        let tile = builder.build_tile_unchecked(position, collection.auto_tile_id(&pixels));
         match collection.add_tile_pixels(&tile, image_buffer)? {
             super::collection::VisCollectionOutcome::Empty => {
                 continue;
@@ -144,6 +149,208 @@ where
     Ok(())
 }
 
+impl<Data> GridMap2D<Data>
+where
+    Data: IdentifiableTileData,
+{
+    /// Renders the map into new [`ImageBuffer`], using pixel data gathered in [`VisCollection`]. Empty positions are
+    /// left with default pixels.
+    ///
+    /// Shorthand for [`init_map_image_buffer`] followed by [`write_gridmap_identifiable`]. Results in
+    /// [`VisError`] if there are no pixels registered for some of the tiles.
+    pub fn vis_grid_map_with<P, const WIDTH: usize, const HEIGHT: usize>(
+        &self,
+        collection: &VisCollection<P, WIDTH, HEIGHT>,
+    ) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, VisError<WIDTH, HEIGHT>>
+    where
+        P: PixelWithDefault + 'static,
+    {
+        let mut image_buffer = collection.init_map_image_buffer(self.size());
+        collection.draw_map(self, &mut image_buffer)?;
+        Ok(image_buffer)
+    }
+}
+
+/// Width in pixels of the separator border drawn between layers by [`render_3d_sheet`].
+pub const SHEET_SEPARATOR_WIDTH: u32 = 1;
+
+/// Renders the layers of 3D grid map into single [`ImageBuffer`] sheet, using pixel data gathered in [`VisCollection`].
+///
+/// Each consecutive `z` layer is represented by distinct [`GridMap2D`] of the same [`GridSize`] in `layers`. Layers
+/// are laid out left-to-right, wrapping into the next row after `cols` layers, with the separator border
+/// of [`SHEET_SEPARATOR_WIDTH`] drawn with `separator` pixel between them. Results in [`VisError`] if the layers
+/// differ in size.
+pub fn render_3d_sheet<Data, P, const WIDTH: usize, const HEIGHT: usize>(
+    layers: &[GridMap2D<Data>],
+    collection: &VisCollection<P, WIDTH, HEIGHT>,
+    cols: u32,
+    separator: P,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, VisError<WIDTH, HEIGHT>>
+where
+    Data: IdentifiableTileData,
+    P: PixelWithDefault + 'static,
+{
+    let Some(first) = layers.first() else {
+        return Ok(ImageBuffer::new(0, 0));
+    };
+    let layer_size = *first.size();
+    let (layer_width, layer_height) = (
+        layer_size.x() * WIDTH as u32,
+        layer_size.y() * HEIGHT as u32,
+    );
+    let cols = cols.clamp(1, layers.len() as u32);
+    let rows = (layers.len() as u32).div_ceil(cols);
+
+    let mut sheet = ImageBuffer::from_pixel(
+        cols * layer_width + (cols - 1) * SHEET_SEPARATOR_WIDTH,
+        rows * layer_height + (rows - 1) * SHEET_SEPARATOR_WIDTH,
+        separator,
+    );
+
+    for (idx, layer) in layers.iter().enumerate() {
+        let mut layer_buffer = collection.init_map_image_buffer(&layer_size);
+        check_grid_image_size(&layer_buffer, layer.size())?;
+        collection.draw_map(layer, &mut layer_buffer)?;
+
+        let (col, row) = (idx as u32 % cols, idx as u32 / cols);
+        let x_offset = col * (layer_width + SHEET_SEPARATOR_WIDTH);
+        let y_offset = row * (layer_height + SHEET_SEPARATOR_WIDTH);
+        for (x, y, pixel) in layer_buffer.enumerate_pixels() {
+            sheet.put_pixel(x_offset + x, y_offset + y, *pixel);
+        }
+    }
+
+    Ok(sheet)
+}
+
+/// Renders the layers of 3D grid map into single [`ImageBuffer`] as a simple isometric preview, using pixel data
+/// gathered in [`VisCollection`].
+///
+/// Each consecutive `z` layer is represented by distinct [`GridMap2D`] of the same [`GridSize`] in `layers`. Layers are
+/// drawn bottom to top, each shifted by `z_offset` pixels from the one below, so with negative `y` offset the higher
+/// tiles appear behind-and-above the lower ones. Tiles of higher layers are painted over the lower ones, while empty
+/// positions leave the pixels below intact. Image is enlarged to fit all shifted layers.
+///
+/// Results in [`VisError`] if the layers differ in size or there are no pixels registered for some of the tiles.
+pub fn render_isometric<Data, P, const WIDTH: usize, const HEIGHT: usize>(
+    layers: &[GridMap2D<Data>],
+    collection: &VisCollection<P, WIDTH, HEIGHT>,
+    z_offset: (i32, i32),
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, VisError<WIDTH, HEIGHT>>
+where
+    Data: IdentifiableTileData,
+    P: PixelWithDefault + 'static,
+{
+    let Some(first) = layers.first() else {
+        return Ok(ImageBuffer::new(0, 0));
+    };
+    let layer_size = *first.size();
+    let (layer_width, layer_height) = (
+        layer_size.x() * WIDTH as u32,
+        layer_size.y() * HEIGHT as u32,
+    );
+    let max_z = layers.len() as u32 - 1;
+    let (x_span, y_span) = (
+        z_offset.0.unsigned_abs() * max_z,
+        z_offset.1.unsigned_abs() * max_z,
+    );
+    // Layer origin is moved to keep all layers within the image for negative offsets.
+    let (x_origin, y_origin) = (
+        if z_offset.0 < 0 { x_span } else { 0 },
+        if z_offset.1 < 0 { y_span } else { 0 },
+    );
+
+    let mut image = ImageBuffer::new(layer_width + x_span, layer_height + y_span);
+
+    for (z, layer) in layers.iter().enumerate() {
+        if *layer.size() != layer_size {
+            return Err(VisError::new_grid_save(
+                (layer_width, layer_height),
+                (
+                    layer.size().x() * WIDTH as u32,
+                    layer.size().y() * HEIGHT as u32,
+                ),
+            ));
+        }
+        let mut layer_buffer = collection.init_map_image_buffer(&layer_size);
+        collection.draw_map(layer, &mut layer_buffer)?;
+
+        let layer_x = x_origin.wrapping_add_signed(z_offset.0 * z as i32);
+        let layer_y = y_origin.wrapping_add_signed(z_offset.1 * z as i32);
+        for tile in layer.iter_tiles() {
+            let (tile_x, tile_y) = tile.grid_position().xy();
+            let (tile_x, tile_y) = (tile_x * WIDTH as u32, tile_y * HEIGHT as u32);
+            for y in tile_y..tile_y + HEIGHT as u32 {
+                for x in tile_x..tile_x + WIDTH as u32 {
+                    image.put_pixel(layer_x + x, layer_y + y, *layer_buffer.get_pixel(x, y));
+                }
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+/// Renders the layers of 3D grid map into single top-down [`ImageBuffer`] with the height encoded in brightness, using
+/// pixel data gathered in [`VisCollection`].
+///
+/// Each consecutive `z` layer is represented by distinct [`GridMap2D`] of the same [`GridSize`] in `layers`. For every
+/// position only the topmost filled tile is drawn, with its color channels multiplied by `1 + shade * z`, so positive
+/// `shade` makes the higher tiles brighter and negative one makes them darker. Positions empty on all layers are left
+/// with default pixels.
+///
+/// Results in [`VisError`] if the layers differ in size or there are no pixels registered for some of the drawn tiles.
+pub fn render_heightshaded<Data, P, const WIDTH: usize, const HEIGHT: usize>(
+    layers: &[GridMap2D<Data>],
+    collection: &VisCollection<P, WIDTH, HEIGHT>,
+    shade: f32,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, VisError<WIDTH, HEIGHT>>
+where
+    Data: IdentifiableTileData,
+    P: PixelWithDefault + 'static,
+{
+    let Some(first) = layers.first() else {
+        return Ok(ImageBuffer::new(0, 0));
+    };
+    let layer_size = *first.size();
+    let mut image = collection.init_map_image_buffer(&layer_size);
+    for layer in layers.iter() {
+        check_grid_image_size(&image, layer.size())?;
+    }
+
+    for position in layer_size.get_all_possible_positions() {
+        let Some((z, tile)) = layers
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(z, layer)| Some((z, layer.get_tile_at_position(&position)?)))
+        else {
+            continue;
+        };
+        collection.draw_tile(&tile, &mut image)?;
+
+        let factor = (1. + shade * z as f32).max(0.);
+        let (tile_x, tile_y) = position.xy();
+        let (tile_x, tile_y) = (tile_x * WIDTH as u32, tile_y * HEIGHT as u32);
+        for y in tile_y..tile_y + HEIGHT as u32 {
+            for x in tile_x..tile_x + WIDTH as u32 {
+                image
+                    .get_pixel_mut(x, y)
+                    .apply_without_alpha(|channel| shade_channel(channel, factor));
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+/// Multiplies the subpixel value by `factor`, saturating at the maximum value of its type.
+fn shade_channel<S: Primitive>(channel: S, factor: f32) -> S {
+    let shaded = channel.to_f32().unwrap_or_default() * factor;
+    let max = S::DEFAULT_MAX_VALUE.to_f32().unwrap_or(f32::MAX);
+    NumCast::from(shaded.min(max)).unwrap_or(S::DEFAULT_MAX_VALUE)
+}
+
 /// Checks the size of the [`ImageBuffer`] while loading [`GridMap2D`] from its visual representation, and produces
 /// the [`GridSize`] inferred from the image size. Results in [`VisError`] if the image size is not compatible
 /// with provided tile size in pixels.
@@ -192,3 +399,143 @@ pub(crate) fn create_tile_id_from_pixels<
     pixels.hash(&mut hasher);
     hasher.finish()
 }
+
+#[cfg(test)]
+mod test {
+    use image::ImageBuffer;
+
+    use crate::map::{GridMap2D, GridSize};
+    use crate::tile::identifiable::builders::ConstructableViaIdentifierTile;
+    use crate::tile::identifiable::BasicIdentTileData;
+    use crate::tile::GridPosition;
+    use crate::vis::collection::VisCollection;
+    use crate::vis::DefaultVisPixel;
+
+    use super::{
+        init_map_image_buffer, render_3d_sheet, render_heightshaded, render_isometric,
+        write_gridmap_identifiable, SHEET_SEPARATOR_WIDTH,
+    };
+
+    #[test]
+    fn sheet_has_layers_and_separators() {
+        let size = GridSize::new_xy(3, 2);
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        collection.add_tile_pixels_manual(0, [[DefaultVisPixel::from([100, 0, 0]); 4]; 4]);
+        collection.add_tile_pixels_manual(1, [[DefaultVisPixel::from([0, 100, 0]); 4]; 4]);
+
+        let layers = (0..2)
+            .map(|z| {
+                let mut layer = GridMap2D::new(size);
+                layer.fill_empty_with(BasicIdentTileData::tile_new(z));
+                layer
+            })
+            .collect::<Vec<_>>();
+
+        let separator = DefaultVisPixel::from([255, 255, 255]);
+        let sheet: ImageBuffer<DefaultVisPixel, _> =
+            render_3d_sheet(&layers, &collection, 2, separator).unwrap();
+
+        assert_eq!(3 * 4 * 2 + SHEET_SEPARATOR_WIDTH, sheet.width());
+        assert_eq!(2 * 4, sheet.height());
+        assert_eq!(&DefaultVisPixel::from([100, 0, 0]), sheet.get_pixel(0, 0));
+        assert_eq!(&separator, sheet.get_pixel(12, 0));
+        assert_eq!(&DefaultVisPixel::from([0, 100, 0]), sheet.get_pixel(13, 0));
+
+        let wrapped = render_3d_sheet(&layers, &collection, 1, separator).unwrap();
+        assert_eq!(3 * 4, wrapped.width());
+        assert_eq!(2 * 4 * 2 + SHEET_SEPARATOR_WIDTH, wrapped.height());
+    }
+
+    #[test]
+    fn isometric_enlarged_by_offset() {
+        let size = GridSize::new_xy(2, 2);
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        for z in 0..3 {
+            collection.add_tile_pixels_manual(
+                z,
+                [[DefaultVisPixel::from([50 * (z as u8 + 1), 0, 0]); 4]; 4],
+            );
+        }
+        let layers = (0..3)
+            .map(|z| {
+                let mut layer = GridMap2D::new(size);
+                layer.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(z));
+                if z == 0 {
+                    layer.fill_empty_with(BasicIdentTileData::tile_new(z));
+                }
+                layer
+            })
+            .collect::<Vec<_>>();
+
+        let image: ImageBuffer<DefaultVisPixel, _> =
+            render_isometric(&layers, &collection, (2, -3)).unwrap();
+
+        assert_eq!(2 * 4 + 2 * 2, image.width());
+        assert_eq!(2 * 4 + 2 * 3, image.height());
+        // Bottom layer is placed at the bottom-left, the top one at the top-right.
+        assert_eq!(&DefaultVisPixel::from([50, 0, 0]), image.get_pixel(0, 13));
+        assert_eq!(&DefaultVisPixel::from([150, 0, 0]), image.get_pixel(4, 0));
+        // Top layer is painted over the middle one, which is painted over the bottom one.
+        assert_eq!(&DefaultVisPixel::from([150, 0, 0]), image.get_pixel(4, 3));
+        assert_eq!(&DefaultVisPixel::from([100, 0, 0]), image.get_pixel(2, 3));
+        // Empty positions of higher layers leave the bottom layer visible.
+        assert_eq!(&DefaultVisPixel::from([50, 0, 0]), image.get_pixel(7, 13));
+
+        let single = render_isometric(&layers[..1], &collection, (2, -3)).unwrap();
+        assert_eq!((8, 8), (single.width(), single.height()));
+
+        let mismatched = [
+            GridMap2D::<BasicIdentTileData>::new(size),
+            GridMap2D::new(GridSize::new_xy(3, 2)),
+        ];
+        assert!(render_isometric(&mismatched, &collection, (2, -3)).is_err());
+    }
+
+    #[test]
+    fn heightshaded_taller_column_brighter() {
+        let size = GridSize::new_xy(3, 1);
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        collection.add_tile_pixels_manual(0, [[DefaultVisPixel::from([100, 50, 0]); 4]; 4]);
+
+        let mut layers = vec![GridMap2D::new(size), GridMap2D::new(size)];
+        layers[0].insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(0));
+        layers[0].insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(0));
+        layers[1].insert_data(&GridPosition::new_xy(1, 0), BasicIdentTileData::tile_new(0));
+
+        let image: ImageBuffer<DefaultVisPixel, _> =
+            render_heightshaded(&layers, &collection, 0.5).unwrap();
+
+        assert_eq!((12, 4), (image.width(), image.height()));
+        assert_eq!(&DefaultVisPixel::from([100, 50, 0]), image.get_pixel(0, 0));
+        assert_eq!(&DefaultVisPixel::from([150, 75, 0]), image.get_pixel(4, 0));
+        // Empty column is left with the background.
+        assert_eq!(&DefaultVisPixel::from([0, 0, 0]), image.get_pixel(8, 0));
+
+        let saturated = render_heightshaded(&layers, &collection, 2.).unwrap();
+        assert_eq!(
+            &DefaultVisPixel::from([255, 150, 0]),
+            saturated.get_pixel(4, 0)
+        );
+    }
+
+    #[test]
+    fn vis_grid_map_with_matches_manual() {
+        let size = GridSize::new_xy(3, 2);
+        let mut collection = VisCollection::<DefaultVisPixel, 4, 4>::default();
+        collection.add_tile_pixels_manual(0, [[DefaultVisPixel::from([100, 0, 0]); 4]; 4]);
+        collection.add_tile_pixels_manual(1, [[DefaultVisPixel::from([0, 100, 0]); 4]; 4]);
+
+        let mut map = GridMap2D::new(size);
+        for pos in size.get_all_possible_positions() {
+            map.insert_data(&pos, BasicIdentTileData::tile_new((pos.x() % 2) as u64));
+        }
+
+        let rendered = map.vis_grid_map_with(&collection).unwrap();
+        let mut manual = init_map_image_buffer::<DefaultVisPixel, 4, 4>(&size);
+        write_gridmap_identifiable(&mut manual, &map, &collection).unwrap();
+        assert_eq!(manual.as_raw(), rendered.as_raw());
+
+        map.insert_data(&GridPosition::new_xy(0, 0), BasicIdentTileData::tile_new(5));
+        assert!(map.vis_grid_map_with(&collection).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use grid_forge::identifiable::IdentifiableTileData;
+
+#[derive(IdentifiableTileData)]
+#[tile_type_id(7)]
+struct ConstantTile;
+
+#[derive(IdentifiableTileData)]
+struct FieldTile {
+    /// Ignored doc attribute.
+    #[allow(dead_code)]
+    name: &'static str,
+    #[tile_type_id]
+    kind: u32,
+}
+
+#[derive(IdentifiableTileData)]
+struct TupleTile(
+    #[allow(dead_code)] Vec<(u8, u8)>,
+    #[tile_type_id] pub(crate) u64,
+);
+
+#[test]
+fn constant_tile_type_id() {
+    assert_eq!(7, ConstantTile.tile_type_id());
+}
+
+#[test]
+fn field_tile_type_id() {
+    let tile = FieldTile {
+        name: "grass",
+        kind: 12,
+    };
+    assert_eq!(12, tile.tile_type_id());
+    assert_eq!(5, TupleTile(vec![(1, 2)], 5).tile_type_id());
+}